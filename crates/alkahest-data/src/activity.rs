@@ -1,9 +1,10 @@
 use destiny_pkg::TagHash;
+use serde::Serialize;
 use tiger_parse::{tiger_tag, NullString, Pointer, ResourcePointer, ResourcePointerWithClass};
 
-use crate::{common::ResourceHash, ExtendedHash, Tag};
+use crate::{common::ResourceHash, dump::remote, ExtendedHash, LazyTag, Tag};
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0x80808E8E, size = 0x80)]
 pub struct SActivity {
     pub file_size: u64,
@@ -11,6 +12,7 @@ pub struct SActivity {
     pub unkc: ResourceHash,
     pub unk10: ResourceHash,
     pub unk14: ResourceHash,
+    #[serde(serialize_with = "remote")]
     pub unk18: ResourcePointer,
     pub destination: ExtendedHash,
 
@@ -19,29 +21,35 @@ pub struct SActivity {
     pub unk50: Vec<Unk80808924>,
     pub unk60: [u32; 4],
     pub unk70: ResourceHash,
+    #[serde(serialize_with = "remote")]
     pub unk74: TagHash,
     pub ambient_activity: ExtendedHash,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0x80808E8B)]
 pub struct SDestination {
     pub file_size: u64,
     pub location_name: ResourceHash,
     pub unkc: u32,
     pub string_container: ExtendedHash,
+    #[serde(serialize_with = "remote")]
     pub events: TagHash,
+    #[serde(serialize_with = "remote")]
     pub patrols: TagHash,
     pub unk28: u32,
+    #[serde(serialize_with = "remote")]
     pub unk2c: TagHash,
+    #[serde(serialize_with = "remote")]
     pub tagbags: Vec<TagHash>, // 0x30
     pub unk40: u32,
     pub unk48: u32,
     pub activities: Vec<Unk8080892e>,
+    #[serde(serialize_with = "remote")]
     pub destination_name: Pointer<NullString>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0x8080892E)]
 pub struct Unk8080892e {
     /// Doesn't always map to a string
@@ -49,22 +57,24 @@ pub struct Unk8080892e {
     pub unk4: u32,
     pub unk8: ResourceHash,
     pub unkc: ResourceHash,
+    #[serde(serialize_with = "remote")]
     pub activity_code: Pointer<NullString>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0x80808924)]
 pub struct Unk80808924 {
     pub location_name: ResourceHash,
     pub activity_name: ResourceHash,
     pub bubble_name: ResourceHash,
     pub unkc: u32,
+    #[serde(serialize_with = "remote")]
     pub unk10: ResourcePointer,
     pub unk18: Vec<Unk80808948>,
     pub map_references: Vec<ExtendedHash>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0x80808926)]
 pub struct Unk80808926 {
     pub location_name: ResourceHash,
@@ -89,7 +99,7 @@ pub struct Unk80808926 {
     pub unk4c: [u32; 4],
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0x80808948)]
 pub struct Unk80808948 {
     pub location_name: ResourceHash,
@@ -97,41 +107,47 @@ pub struct Unk80808948 {
     pub bubble_name: ResourceHash,
     pub activity_phase_name: ResourceHash,
     pub activity_phase_name2: ResourceHash,
-    pub unk_entity_reference: Tag<Unk80808e89>,
+    pub unk_entity_reference: LazyTag<Unk80808e89>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct Unk80808e89 {
     pub file_size: u64,
     pub unk8: u64,
+    #[serde(serialize_with = "remote")]
     pub unk10: ResourcePointer,
-    pub unk18: Tag<Unk80808ebe>,
+    pub unk18: LazyTag<Unk80808ebe>,
     pub unk1c: u32,
     pub unk20: [u32; 4],
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct Unk80808ebe {
     pub file_size: u64,
+    // Eager, once this list is resolved the map loader walks every resource in it
     pub entity_resources: Vec<Tag<Unk80808943>>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 #[tiger_tag(id = 0xffffffff, size = 0x24)]
 pub struct Unk80808943 {
     pub file_size: u64,
     #[tag(offset = 0x20)]
+    #[serde(serialize_with = "remote")]
     pub entity_resource: TagHash,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff, size = 0x88)]
 pub struct SEntityResource {
     pub file_size: u64,
+    #[serde(serialize_with = "remote")]
     pub unk8: ResourcePointer,
+    #[serde(serialize_with = "remote")]
     pub unk10: ResourcePointerWithClass,
+    #[serde(serialize_with = "remote")]
     pub unk18: ResourcePointerWithClass,
 
     #[tag(offset = 0x40)]
@@ -141,14 +157,18 @@ pub struct SEntityResource {
     pub resource_table2: Vec<()>,
 
     #[tag(offset = 0x80)]
+    #[serde(serialize_with = "remote")]
     pub unk80: TagHash,
+    #[serde(serialize_with = "remote")]
     pub unk84: TagHash,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct Unk808092d8 {
+    #[serde(serialize_with = "remote")]
     pub unk0: [u32; 33],
+    #[serde(serialize_with = "remote")]
     pub unk84: TagHash,
     pub unk88: u32,
     pub unk8c: u32,
@@ -156,9 +176,10 @@ pub struct Unk808092d8 {
     pub translation: glam::Vec4,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct Unk80808cef {
     pub unk0: [u32; 22],
+    #[serde(serialize_with = "remote")]
     pub unk58: TagHash,
 }
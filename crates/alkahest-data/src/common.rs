@@ -1,6 +1,7 @@
 use std::fmt::{Debug, Formatter, Write};
 
 use binrw::BinRead;
+use serde::{Serialize, Serializer};
 use tiger_parse::TigerReadable;
 
 #[derive(BinRead, Copy, Clone, PartialEq)]
@@ -24,6 +25,12 @@ impl ResourceHash {
     }
 }
 
+impl Serialize for ResourceHash {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&format_args!("{:08X}", self.0))
+    }
+}
+
 impl TigerReadable for ResourceHash {
     fn read_ds_endian<R: std::io::prelude::Read + std::io::prelude::Seek>(
         reader: &mut R,
@@ -0,0 +1,155 @@
+//! Serialization of tag structs, for diffing tag contents between game versions.
+//! Hashes are written as hex strings, tag hashes in the same byte order as they're displayed in
+
+use destiny_pkg::{TagHash, TagHash64};
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+use tiger_parse::{
+    NullString, Pointer, PointerOptional, ResourcePointer, ResourcePointerWithClass, TigerReadable,
+};
+
+use crate::{
+    common::ResourceHash, entity::Unk8080894d, map::SSlipSurfaceVolume, resolver::read_tag_struct,
+};
+
+/// Reads a tag and turns it into pretty JSON
+pub fn dump_tag<T: TigerReadable + Serialize>(hash: TagHash) -> anyhow::Result<String> {
+    let tag: T = read_tag_struct(hash)?;
+    to_json(&tag)
+}
+
+pub fn to_json<T: Serialize>(value: &T) -> anyhow::Result<String> {
+    Ok(serde_json::to_string_pretty(value)?)
+}
+
+pub fn to_ron<T: Serialize>(value: &T) -> anyhow::Result<String> {
+    Ok(ron::ser::to_string_pretty(
+        value,
+        ron::ser::PrettyConfig::default(),
+    )?)
+}
+
+/// Serialization for types from other crates, used through `#[serde(serialize_with = "remote")]`
+pub trait RemoteSerialize {
+    fn serialize_remote<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+}
+
+pub fn remote<T: RemoteSerialize, S: Serializer>(
+    value: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    value.serialize_remote(serializer)
+}
+
+/// Lets a [`RemoteSerialize`] value be passed where [`Serialize`] is expected
+pub(crate) struct Remote<'a, T: RemoteSerialize>(pub &'a T);
+
+impl<T: RemoteSerialize> Serialize for Remote<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize_remote(serializer)
+    }
+}
+
+impl RemoteSerialize for TagHash {
+    fn serialize_remote<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&format_args!("{:08X}", self.0.to_be()))
+    }
+}
+
+impl RemoteSerialize for TagHash64 {
+    fn serialize_remote<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&format_args!("{:016X}", self.0.to_be()))
+    }
+}
+
+impl RemoteSerialize for NullString {
+    fn serialize_remote<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl RemoteSerialize for ResourcePointer {
+    fn serialize_remote<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_resource_pointer(serializer, self.offset, self.resource_type)
+    }
+}
+
+impl RemoteSerialize for ResourcePointerWithClass {
+    fn serialize_remote<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_resource_pointer(serializer, self.offset, self.resource_type)
+    }
+}
+
+fn serialize_resource_pointer<S: Serializer>(
+    serializer: S,
+    offset: u64,
+    resource_type: u32,
+) -> Result<S::Ok, S::Error> {
+    let mut s = serializer.serialize_struct("ResourcePointer", 2)?;
+    s.serialize_field("offset", &offset)?;
+    s.serialize_field("resource_type", &format!("{:08X}", resource_type.to_be()))?;
+    s.end()
+}
+
+/// Pointers are written as the data they point to
+impl<T: RemoteSerialize> RemoteSerialize for Pointer<T> {
+    fn serialize_remote<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize_remote(serializer)
+    }
+}
+
+impl<T: RemoteSerialize> RemoteSerialize for PointerOptional<T> {
+    fn serialize_remote<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.as_ref() {
+            Some(v) => serializer.serialize_some(&Remote(v)),
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+impl<T: RemoteSerialize> RemoteSerialize for Vec<T> {
+    fn serialize_remote<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter().map(Remote))
+    }
+}
+
+impl<A: RemoteSerialize, B: RemoteSerialize> RemoteSerialize for (A, B) {
+    fn serialize_remote<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (Remote(&self.0), Remote(&self.1)).serialize(serializer)
+    }
+}
+
+impl<A: RemoteSerialize, B: RemoteSerialize, C: RemoteSerialize, D: RemoteSerialize> RemoteSerialize
+    for (A, B, C, D)
+{
+    fn serialize_remote<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (
+            Remote(&self.0),
+            Remote(&self.1),
+            Remote(&self.2),
+            Remote(&self.3),
+        )
+            .serialize(serializer)
+    }
+}
+
+/// serde only implements [`Serialize`] for arrays of up to 32 elements
+impl<T: Serialize, const N: usize> RemoteSerialize for [T; N] {
+    fn serialize_remote<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self)
+    }
+}
+
+/// Our own types that are nested in remote ones
+macro_rules! remote_serialize_local {
+    ($($ty:ty),*) => {
+        $(
+            impl RemoteSerialize for $ty {
+                fn serialize_remote<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    self.serialize(serializer)
+                }
+            }
+        )*
+    };
+}
+
+remote_serialize_local!((), ResourceHash, SSlipSurfaceVolume, Unk8080894d);
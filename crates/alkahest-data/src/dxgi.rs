@@ -1,10 +1,11 @@
 use std::mem::transmute;
 
+use serde::Serialize;
 use tiger_parse::TigerReadable;
 
 #[allow(non_camel_case_types, dead_code, clippy::upper_case_acronyms)]
 #[repr(u32)]
-#[derive(Debug, Clone, Copy, PartialEq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Hash, Serialize)]
 pub enum DxgiFormat {
     Unknown = 0,
     R32G32B32A32_TYPELESS = 1,
@@ -1,16 +1,20 @@
 use destiny_pkg::TagHash;
+use serde::Serialize;
 use tiger_parse::{tiger_tag, FnvHash, NullString, Pointer, PointerOptional, ResourcePointer};
 
-use super::geometry::{ELodCategory, EPrimitiveType};
+use super::{
+    dump::remote,
+    geometry::{ELodCategory, EPrimitiveType},
+};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct Unk80809c0f {
     pub file_size: u64,
     pub entity_resources: Vec<Unk80809c04>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 #[tiger_tag(id = 0x80809ACD)]
 pub struct Unk80809c04 {
     pub unk0: super::Tag<Unk80809b06>,
@@ -19,20 +23,25 @@ pub struct Unk80809c04 {
 }
 
 /// Entity resource
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 #[tiger_tag(id = 0xffffffff, size = 0x90)]
 pub struct Unk80809b06 {
     pub file_size: u64,
+    #[serde(serialize_with = "remote")]
     pub unk8: ResourcePointer,
+    #[serde(serialize_with = "remote")]
     pub unk10: ResourcePointer,
+    #[serde(serialize_with = "remote")]
     pub unk18: ResourcePointer,
 
     #[tag(offset = 0x80)]
+    #[serde(serialize_with = "remote")]
     pub unk80: TagHash,
+    #[serde(serialize_with = "remote")]
     pub unk84: TagHash,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 #[tiger_tag(id = 0xffffffff, size = 0x70)]
 pub struct SEntityModel {
     pub file_size: u64,
@@ -45,25 +54,34 @@ pub struct SEntityModel {
     pub texcoord_offset: glam::Vec2,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 #[tiger_tag(id = 0x80806EC5)]
 pub struct SEntityModelMesh {
+    #[serde(serialize_with = "remote")]
     pub vertex_buffer1: TagHash,
+    #[serde(serialize_with = "remote")]
     pub vertex_buffer2: TagHash,
+    #[serde(serialize_with = "remote")]
     pub buffer2: TagHash,
+    #[serde(serialize_with = "remote")]
     pub buffer3: TagHash,
+    #[serde(serialize_with = "remote")]
     pub index_buffer: TagHash,
+    #[serde(serialize_with = "remote")]
     pub color_buffer: TagHash,
+    #[serde(serialize_with = "remote")]
     pub skinning_buffer: TagHash,
     pub unk1c: u32,
     pub parts: Vec<Unk8080737e>,
+    #[serde(serialize_with = "remote")]
     pub unk30: [u16; 37],
     _pad7a: [u16; 3],
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 #[tiger_tag(id = 0x80806ECB)]
 pub struct Unk8080737e {
+    #[serde(serialize_with = "remote")]
     pub material: TagHash,
     pub variant_shader_index: u16,
     pub primitive_type: EPrimitiveType,
@@ -81,7 +99,7 @@ pub struct Unk8080737e {
     pub unk20: u32,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 #[tiger_tag(id = 0x80806D97)]
 pub struct Unk808072c5 {
     pub material_count: u32,
@@ -89,7 +107,7 @@ pub struct Unk808072c5 {
     pub unk8: u32,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct VertexBufferHeader {
     pub data_size: u32,
@@ -99,7 +117,7 @@ pub struct VertexBufferHeader {
     pub deadbeef: u32,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct IndexBufferHeader {
     pub unk0: i8,
@@ -113,7 +131,7 @@ pub struct IndexBufferHeader {
     pub zero1: u32,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct Unk80809905 {
     pub name_hash: FnvHash,
@@ -121,22 +139,25 @@ pub struct Unk80809905 {
     pub world_id: u64,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct Unk8080906b {
     pub file_size: u64,
     pub unk0: Vec<Unk80809d02>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0x80809D02)]
 pub struct Unk80809d02 {
+    #[serde(serialize_with = "remote")]
     pub unk0_name_pointer: PointerOptional<Unk8080894d>,
+    #[serde(serialize_with = "remote")]
     pub unk8: PointerOptional<()>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct Unk8080894d {
+    #[serde(serialize_with = "remote")]
     pub name: Pointer<NullString>,
 }
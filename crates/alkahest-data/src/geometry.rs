@@ -1,24 +1,13 @@
-use std::{cmp::Ordering, mem::transmute};
+use std::cmp::Ordering;
 
-use tiger_parse::TigerReadable;
+use serde::Serialize;
 
-#[derive(Debug, PartialEq, Copy, Clone)]
-#[repr(u8)]
-pub enum EPrimitiveType {
-    Triangles = 3,
-    TriangleStrip = 5,
-}
-
-impl TigerReadable for EPrimitiveType {
-    fn read_ds_endian<R: std::io::prelude::Read + std::io::prelude::Seek>(
-        reader: &mut R,
-        endian: tiger_parse::Endian,
-    ) -> tiger_parse::Result<Self> {
-        Ok(unsafe { transmute(u8::read_ds_endian(reader, endian)?) })
+tiger_enum_u8! {
+    #[derive(Debug, PartialEq, Copy, Clone, Serialize)]
+    pub enum EPrimitiveType {
+        Triangles = 3,
+        TriangleStrip = 5,
     }
-
-    const ZEROCOPY: bool = true;
-    const SIZE: usize = 1;
 }
 
 // impl EPrimitiveType {
@@ -30,40 +19,29 @@ impl TigerReadable for EPrimitiveType {
 //     }
 // }
 
-#[allow(non_camel_case_types, clippy::derive_ord_xor_partial_ord)]
-#[derive(Debug, PartialEq, Eq, Ord, Copy, Clone)]
-#[repr(u8)]
-pub enum ELodCategory {
-    /// main geometry lod0
-    Lod_0_0 = 0,
-    /// grip/stock lod0
-    Lod_0_1 = 1,
-    /// stickers lod0
-    Lod_0_2 = 2,
-    /// internal geom lod0
-    Lod_0_3 = 3,
-    /// low poly geom lod1
-    Lod_1_0 = 4,
-    /// low poly geom lod2
-    Lod_2_0 = 7,
-    /// grip/stock/scope lod2
-    Lod_2_1 = 8,
-    /// low poly geom lod3
-    Lod_3_0 = 9,
-    /// detail lod0
-    Lod_Detail = 10,
-}
-
-impl TigerReadable for ELodCategory {
-    fn read_ds_endian<R: std::io::prelude::Read + std::io::prelude::Seek>(
-        reader: &mut R,
-        endian: tiger_parse::Endian,
-    ) -> tiger_parse::Result<Self> {
-        Ok(unsafe { transmute(u8::read_ds_endian(reader, endian)?) })
+tiger_enum_u8! {
+    #[allow(non_camel_case_types, clippy::derive_ord_xor_partial_ord)]
+    #[derive(Debug, PartialEq, Eq, Ord, Copy, Clone, Serialize)]
+    pub enum ELodCategory {
+        /// main geometry lod0
+        Lod_0_0 = 0,
+        /// grip/stock lod0
+        Lod_0_1 = 1,
+        /// stickers lod0
+        Lod_0_2 = 2,
+        /// internal geom lod0
+        Lod_0_3 = 3,
+        /// low poly geom lod1
+        Lod_1_0 = 4,
+        /// low poly geom lod2
+        Lod_2_0 = 7,
+        /// grip/stock/scope lod2
+        Lod_2_1 = 8,
+        /// low poly geom lod3
+        Lod_3_0 = 9,
+        /// detail lod0
+        Lod_Detail = 10,
     }
-
-    const ZEROCOPY: bool = true;
-    const SIZE: usize = 1;
 }
 
 impl PartialOrd for ELodCategory {
@@ -1,11 +1,17 @@
+#[macro_use]
+mod macros;
+
 pub mod activity;
 pub mod common;
+pub mod dump;
 pub mod dxgi;
 pub mod entity;
 pub mod geometry;
 pub mod map;
 pub mod occlusion;
 pub mod render_globals;
+pub mod resolver;
+pub mod schema;
 pub mod sound;
 pub mod statics;
 pub mod tag;
@@ -15,4 +21,4 @@ pub mod texture;
 pub mod tfx;
 pub mod unknown;
 
-pub use tag::{ExtendedHash, ExtendedTag, Tag};
+pub use tag::{ExtendedHash, ExtendedTag, LazyTag, Tag};
@@ -0,0 +1,87 @@
+/// Defines a `#[repr(u8)]` enum that is read with [`tiger_parse::TigerReadable`].
+/// Values that don't match any of the variants are rejected with an error instead of being transmuted
+macro_rules! tiger_enum_u8 {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $(
+                $(#[$variant_meta:meta])*
+                $variant:ident = $value:literal
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[repr(u8)]
+        $vis enum $name {
+            $(
+                $(#[$variant_meta])*
+                $variant = $value,
+            )*
+        }
+
+        impl $name {
+            pub const fn from_u8(value: u8) -> Option<Self> {
+                match value {
+                    $($value => Some(Self::$variant),)*
+                    _ => None,
+                }
+            }
+        }
+
+        impl tiger_parse::TigerReadable for $name {
+            fn read_ds_endian<R: std::io::prelude::Read + std::io::prelude::Seek>(
+                reader: &mut R,
+                endian: tiger_parse::Endian,
+            ) -> tiger_parse::Result<Self> {
+                let value = <u8 as tiger_parse::TigerReadable>::read_ds_endian(reader, endian)?;
+                Self::from_u8(value).ok_or_else(|| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        format!("Invalid {} value {value}", stringify!($name)),
+                    )
+                    .into()
+                })
+            }
+
+            // Every value has to go through `from_u8`, so this can't be read by copying
+            const ZEROCOPY: bool = false;
+            const SIZE: usize = 1;
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use tiger_parse::{Endian, TigerReadable};
+
+    use crate::{
+        geometry::{ELodCategory, EPrimitiveType},
+        tfx::{TfxFeatureType, TfxRenderStage, TfxShaderStage},
+    };
+
+    /// Reads every byte value, expecting exactly the values in `valid` to decode (to the variant with that value)
+    fn check_values<T: TigerReadable>(valid: &[u8], to_u8: impl Fn(T) -> u8) {
+        for value in 0..=u8::MAX {
+            let result = T::read_ds_endian(&mut Cursor::new([value]), Endian::Little);
+            if valid.contains(&value) {
+                match result {
+                    Ok(v) => assert_eq!(to_u8(v), value),
+                    Err(_) => panic!("{value} should decode"),
+                }
+            } else {
+                assert!(result.is_err(), "{value} should be rejected");
+            }
+        }
+    }
+
+    #[test]
+    fn enum_values() {
+        check_values(&(0..=23).collect::<Vec<_>>(), |v: TfxRenderStage| v as u8);
+        check_values(&(0..=26).collect::<Vec<_>>(), |v: TfxFeatureType| v as u8);
+        check_values(&[1, 2, 3, 4, 5, 6], |v: TfxShaderStage| v as u8);
+        check_values(&[3, 5], |v: EPrimitiveType| v as u8);
+        check_values(&[0, 1, 2, 3, 4, 7, 8, 9, 10], |v: ELodCategory| v as u8);
+    }
+}
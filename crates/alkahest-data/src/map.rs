@@ -1,34 +1,22 @@
 use destiny_pkg::TagHash;
+use serde::Serialize;
 use tiger_parse::{tiger_tag, NullString, Pointer, ResourcePointer};
 
 use crate::{
     common::ResourceHash,
+    dump::remote,
     occlusion::{SObjectOcclusionBounds, SOcclusionBounds, AABB},
     statics::SStaticMeshInstances,
     tfx::TfxRenderStage,
-    ExtendedHash, ExtendedTag, Tag,
+    ExtendedHash, ExtendedTag, LazyTag, Tag,
 };
 
-#[derive(Debug)]
-#[tiger_tag(id = 0x8080891E, size = 0x18)]
-// cohae: Shallow read to avoid too many package calls
-// TODO(cohae): Implement shallow reading in tiger-parse itself
-pub struct SBubbleParentShallow {
-    pub file_size: u64,
-    // 808091e0
-    pub child_map: TagHash,
-    pub unkc: u32,
-
-    pub unk10: u64,
-    pub map_name: ResourceHash,
-}
-
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0x8080891E, size = 0x50)]
 pub struct SBubbleParent {
     pub file_size: u64,
     // 808091e0
-    pub child_map: Tag<SBubbleDefinition>,
+    pub child_map: LazyTag<SBubbleDefinition>,
     pub unkc: u32,
 
     pub unk10: u64,
@@ -38,7 +26,7 @@ pub struct SBubbleParent {
     pub unk40: Vec<Unk80809644>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0x808096C9)]
 pub struct Unk80809644 {
     pub unk0: u32,
@@ -48,48 +36,53 @@ pub struct Unk80809644 {
 }
 
 // D2Class_01878080
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff, size = 0x18)]
 pub struct SBubbleDefinition {
     pub file_size: u64,
+    // Eager, this is only read through SBubbleParent::child_map when loading the whole map
     pub map_resources: Vec<ExtendedTag<SMapContainer>>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0x80808707, size = 0x38)]
 pub struct SMapContainer {
     pub file_size: u64,
     #[tag(offset = 0x28)]
+    // Eager for the same reason as SBubbleDefinition::map_resources
     pub data_tables: Vec<Tag<SMapDataTable>>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0x80809883)]
 pub struct SMapDataTable {
     pub file_size: u64,
     pub data_entries: Vec<SUnk80809885>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0x80809885)]
 pub struct SUnk80809885 {
     pub rotation: glam::Quat,    // 0x0
     pub translation: glam::Vec4, // 0x10
-    pub entity_old: TagHash,     // 0x20
+    #[serde(serialize_with = "remote")]
+    pub entity_old: TagHash, // 0x20
     pub unk24: u32,
     pub entity: ExtendedHash,
     pub unk38: [u32; 9], //
     pub unk5c: f32,
     pub unk60: f32,
+    #[serde(serialize_with = "remote")]
     pub unk64: TagHash,
     pub unk68: ResourceHash,
     pub unk6c: u32,
     pub world_id: u64,
+    #[serde(serialize_with = "remote")]
     pub data_resource: ResourcePointer,
     pub unk80: [u32; 4],
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct Unk80806ef4 {
     pub unk0: u64,
@@ -98,7 +91,7 @@ pub struct Unk80806ef4 {
 }
 
 /// Terrain
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff, size = 0x88)]
 pub struct STerrain {
     pub file_size: u64,
@@ -111,17 +104,22 @@ pub struct STerrain {
     #[tag(offset = 0x50)]
     pub mesh_groups: Vec<Unk80807154>,
 
+    #[serde(serialize_with = "remote")]
     pub vertex_buffer: TagHash,
+    #[serde(serialize_with = "remote")]
     pub vertex_buffer2: TagHash,
+    #[serde(serialize_with = "remote")]
     pub indices: TagHash,
+    #[serde(serialize_with = "remote")]
     pub material1: TagHash,
+    #[serde(serialize_with = "remote")]
     pub material2: TagHash,
 
     #[tag(offset = 0x78)]
     pub mesh_parts: Vec<Unk80807152>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0x80806C86)]
 pub struct Unk80807154 {
     pub unk0: glam::Vec4,
@@ -138,15 +136,17 @@ pub struct Unk80807154 {
     pub unk44: u32,
     pub unk48: u32,
     pub unk4c: u32,
+    #[serde(serialize_with = "remote")]
     pub dyemap: TagHash,
     pub unk54: u32,
     pub unk58: u32,
     pub unk5c: u32,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0x80806C84)]
 pub struct Unk80807152 {
+    #[serde(serialize_with = "remote")]
     pub material: TagHash,
     pub index_start: u32,
     pub index_count: u16,
@@ -155,19 +155,21 @@ pub struct Unk80807152 {
 }
 
 /// Terrain resource
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff, size = 0x20)]
 pub struct Unk8080714b {
     #[tag(offset = 0x10)]
     pub unk10: u16,
     pub unk12: u16,
     pub unk14: ResourceHash,
+    #[serde(serialize_with = "remote")]
     pub terrain: TagHash,
+    #[serde(serialize_with = "remote")]
     pub terrain_bounds: TagHash,
 }
 
 /// Cubemap volume resource
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff, size = 0x1e0)]
 pub struct Unk80806b7f {
     #[tag(offset = 0x20)]
@@ -187,21 +189,26 @@ pub struct Unk80806b7f {
 
     pub unk140: [u32; 28],
 
+    #[serde(serialize_with = "remote")]
     pub cubemap_name: Pointer<NullString>,
+    #[serde(serialize_with = "remote")]
     pub cubemap_texture: TagHash,
     pub unk1bc: u32,
+    #[serde(serialize_with = "remote")]
     pub unk1c0: TagHash,
     pub unk1c4: [u32; 7],
 }
 
 /// Decal collection resource
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct Unk80806e68 {
     pub file_size: u64,
     pub instances: Vec<Unk80806e6c>,
     pub transforms: Vec<glam::Vec4>, // 80806e6d
+    #[serde(serialize_with = "remote")]
     pub instance_points: TagHash,
+    #[serde(serialize_with = "remote")]
     pub unk_vertex_colors: TagHash,
 
     pub unk30: [u32; 2],
@@ -209,24 +216,28 @@ pub struct Unk80806e68 {
     _pad3c: u32,
     pub bounds: AABB,
 }
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0x80806963)]
 pub struct Unk80806e6c {
+    #[serde(serialize_with = "remote")]
     pub material: TagHash,
     pub start: u16,
     pub count: u16,
 }
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct Unk80806df3 {
     pub file_size: u64,
     pub unk8: Vec<Unk80806dec>,
 }
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct Unk80806dec {
+    #[serde(serialize_with = "remote")]
     pub material: TagHash,
+    #[serde(serialize_with = "remote")]
     pub index_buffer: TagHash,
+    #[serde(serialize_with = "remote")]
     pub vertex_buffer: TagHash,
     pub unkc: u32,
     pub unk10: [u32; 4],
@@ -239,60 +250,67 @@ pub struct Unk80806dec {
 }
 
 // Unknown resource (some kind of octree?)
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct Unk80807268 {
     pub file_size: u64,
     /// Vertex buffer
+    #[serde(serialize_with = "remote")]
     pub unk8: TagHash,
     pub unkc: u32,
     pub unk10: Vec<Unk8080726a>,
     pub unk20: [u32; 6],
     /// Vertex buffer
+    #[serde(serialize_with = "remote")]
     pub unk38: TagHash,
     pub unk3c: u32,
     pub unk40: Vec<Unk8080726a>,
     pub unk50: Vec<Unk8080726d>,
     pub unk60: Vec<u16>,
 }
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct Unk8080726a {
     pub unk0: [u32; 4],
 }
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct Unk8080726d {
     pub unk0: glam::Vec4,
     pub unk10: glam::Vec4,
     pub unk20: glam::Vec4,
 }
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct Unk80809162 {
     pub file_size: u64,
     pub unk8: Vec<Unk80809164>,
 }
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct Unk80809164 {
     pub unk0: glam::Vec4,
     pub unk10: glam::Vec4,
     pub unk20: [u32; 4],
 }
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct Unk80809802 {
     pub file_size: u64,
+    #[serde(serialize_with = "remote")]
     pub unk8: TagHash,
+    #[serde(serialize_with = "remote")]
     pub unkc: TagHash,
     pub unk10: u32,
+    #[serde(serialize_with = "remote")]
     pub unk14: TagHash,
+    #[serde(serialize_with = "remote")]
     pub unk18: TagHash,
     pub unk1c: u32,
+    #[serde(serialize_with = "remote")]
     pub streams: Vec<TagHash>,
 }
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0x80806AA7)]
 pub struct Unk80806aa7 {
     pub file_size: u64,
@@ -300,7 +318,7 @@ pub struct Unk80806aa7 {
     pub unk18: Vec<SObjectOcclusionBounds>,
     pub unk28: Vec<u32>,
 }
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0x80806AA9)]
 pub struct Unk80806aa9 {
     /// Transformation matrix
@@ -317,6 +335,7 @@ pub struct Unk80806aa9 {
 
     pub unk70: f32,
     pub unk74: u32,
+    #[serde(serialize_with = "remote")]
     pub unk78: TagHash,
     pub unk7c: u32,
 
@@ -324,13 +343,14 @@ pub struct Unk80806aa9 {
     pub unk88: u32,
     pub unk8c: u32,
 }
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct Unk80806aae {
     pub file_size: u64,
+    #[serde(serialize_with = "remote")]
     pub entity_model: TagHash,
 }
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct SLightCollection {
     pub file_size: u64,
@@ -344,7 +364,7 @@ pub struct SLightCollection {
 }
 
 // 706C8080
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0x80806C70)]
 pub struct SLight {
     pub unk0: glam::Vec4,
@@ -363,16 +383,21 @@ pub struct SLight {
     pub unkb8: f32,
     pub unkbc: f32,
 
+    #[serde(serialize_with = "remote")]
     pub technique_shading: TagHash,
+    #[serde(serialize_with = "remote")]
     pub technique_volumetrics: TagHash,
+    #[serde(serialize_with = "remote")]
     pub technique_compute_lightprobe: TagHash,
+    #[serde(serialize_with = "remote")]
     pub unkcc: TagHash, // Unk80806da1
+    #[serde(serialize_with = "remote")]
     pub unkd0: TagHash, // Unk80806da1
     pub unkd4: [u32; 7],
 }
 
 // 716C8080
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct SShadowingLight {
     pub unk0: glam::Vec4,
@@ -398,34 +423,42 @@ pub struct SShadowingLight {
     pub unkc8: f32,
     pub unkcc: f32,
 
+    #[serde(serialize_with = "remote")]
     pub technique_shading: TagHash,
+    #[serde(serialize_with = "remote")]
     pub technique_shading_shadow: TagHash,
+    #[serde(serialize_with = "remote")]
     pub technique_volumetrics: TagHash,
+    #[serde(serialize_with = "remote")]
     pub technique_volumetrics_shadow: TagHash,
+    #[serde(serialize_with = "remote")]
     pub technique_compute_lightprobe: TagHash,
+    #[serde(serialize_with = "remote")]
     pub technique_compute_lightprobe_shadow: TagHash,
 
+    #[serde(serialize_with = "remote")]
     pub unke8: TagHash, // Unk80806da1
+    #[serde(serialize_with = "remote")]
     pub unkec: TagHash, // Unk80806da1
 
     pub unkd0: [u32; 8],
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0x80809F4F)]
 pub struct Unk80809f4f {
     pub rotation: glam::Quat,
     pub translation: glam::Vec4,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct Unk80808cb7 {
     pub file_size: u64,
     pub unk8: Vec<Unk80808cb9>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0x80808CB9)]
 pub struct Unk80808cb9 {
     pub rotation: glam::Quat,
@@ -435,14 +468,14 @@ pub struct Unk80808cb9 {
     pub unk24: [u32; 3],
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct Unk808085c2 {
     pub file_size: u64,
     pub unk8: Vec<Unk808085c4>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0x808085C4)]
 pub struct Unk808085c4 {
     pub unk0: [u32; 4],
@@ -450,16 +483,19 @@ pub struct Unk808085c4 {
     pub translation: glam::Vec4,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct Unk80806d19 {
     pub file_size: u64,
+    #[serde(serialize_with = "remote")]
     pub unk8: TagHash,
     pub unkc: u32, // Padding
     pub unk10: Vec<()>,
+    #[serde(serialize_with = "remote")]
     pub unk20: TagHash,
     pub unk24: u32, // Padding
     pub unk28: Vec<()>,
+    #[serde(serialize_with = "remote")]
     pub unk38: TagHash,
     pub unk3c: u32, // Padding
     pub unk40: Vec<()>,
@@ -467,7 +503,7 @@ pub struct Unk80806d19 {
     pub unk60: Vec<()>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0x80806D4F)]
 pub struct Unk80806d4f {
     pub translation: glam::Vec4,
@@ -484,14 +520,16 @@ pub struct Unk80806d4f {
 //     /// Havok file
 //     pub unk28: TagHash,
 // }
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct Unk80806c98 {
     pub file_size: u64,
+    #[serde(serialize_with = "remote")]
     pub unk8: Vec<TagHash>,
     pub unk18: Vec<u32>,
     pub unk28: Vec<u32>,
     pub unk38: Vec<u32>,
+    #[serde(serialize_with = "remote")]
     pub unk48: TagHash,
     pub unk4c: Tag<SOcclusionBounds>,
     pub unk50: Vec<u32>,
@@ -499,10 +537,11 @@ pub struct Unk80806c98 {
     pub bounds: AABB,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct Unk80809178 {
     // Points to havok pre-tag
+    #[serde(serialize_with = "remote")]
     pub unk0: Pointer<SSlipSurfaceVolume>,
 
     pub unk8: u32,
@@ -515,10 +554,11 @@ pub struct Unk80809178 {
     pub unk1c: u64,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct Unk8080917b {
     // Points to havok pre-tag
+    #[serde(serialize_with = "remote")]
     pub unk0: Pointer<SSlipSurfaceVolume>,
     pub unk8: u32,
     pub unkc: u32,
@@ -526,33 +566,35 @@ pub struct Unk8080917b {
     pub unk11: u8,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct SSlipSurfaceVolume {
     pub unk0: [u32; 4],
+    #[serde(serialize_with = "remote")]
     pub havok_file: TagHash,
     pub unk14: u32,
     pub shape_index: u32,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct Unk808068d4 {
     pub unk0: u32,
     pub unk4: u32,
     pub unk8: u32,
     pub unkc: u32,
+    #[serde(serialize_with = "remote")]
     pub entity_model: TagHash,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct Unk80808604 {
     pub unk0: [u32; 4],
     pub unk10: Tag<Unk80808724>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0x80808606)]
 pub struct Unk80808606 {
     pub rotation: glam::Quat,
@@ -566,14 +608,15 @@ pub struct Unk80808606 {
     pub unk50: [u32; 4],
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct Unk80808724 {
     pub file_size: u64,
     pub unk8: Vec<Unk80808606>,
+    #[serde(serialize_with = "remote")]
     pub havok_file: TagHash,
 }
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0x8080824C)]
 pub struct Unk8080824c {
     pub rotation: glam::Quat,
@@ -591,36 +634,38 @@ pub struct Unk8080824c {
     pub shape_index: u32,
     pub unkb0: [u32; 4],
 }
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct Unk80808248 {
     pub file_size: u64,
+    #[serde(serialize_with = "remote")]
     pub havok_file: TagHash,
     _pad: u32,
     pub unk10: Vec<Unk8080824c>,
 }
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct Unk80808246 {
     pub unk0: [u32; 4],
     pub unk10: Tag<Unk80808248>,
 }
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct Unk80806ac2 {
     pub unk0: [u32; 4],
     pub unk10: Tag<Unk80806ac4>,
     pub array_index: u32,
 }
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct Unk80806ac4 {
     pub file_size: u64,
+    #[serde(serialize_with = "remote")]
     pub havok_file: TagHash,
     _pad: u32,
     pub unk10: Vec<Unk80806ed8>,
 }
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0x80806ED8)]
 pub struct Unk80806ed8 {
     pub rotation: glam::Quat,
@@ -645,16 +690,17 @@ pub struct Unk80806ed8 {
     pub unke0: [u32; 4],
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0x80806ABD)]
 pub struct Unk80806abd {
     pub file_size: u64,
+    #[serde(serialize_with = "remote")]
     pub havok_file: TagHash,
     _pad: u32,
     pub unk10: Vec<Unk80806bb2>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 #[tiger_tag(id = 0x80806BB2, size = 0xc0)]
 pub struct Unk80806bb2 {
     pub rotation: glam::Quat,
@@ -1,14 +1,15 @@
 use glam::{Mat4, Quat, Vec3};
+use serde::Serialize;
 use tiger_parse::{tiger_tag, TigerReadable};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 #[tiger_tag(id = 0xffffffff, size = 0x18)]
 pub struct SOcclusionBounds {
     pub file_size: u64,
     pub bounds: Vec<SObjectOcclusionBounds>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 #[tiger_tag(id = 0x808093B3, size = 0x30)]
 pub struct SObjectOcclusionBounds {
     pub bb: AABB,
@@ -16,7 +17,7 @@ pub struct SObjectOcclusionBounds {
 }
 
 #[allow(clippy::upper_case_acronyms)]
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
 #[repr(C, align(16))]
 pub struct AABB {
     pub min: Vec3,
@@ -1,9 +1,10 @@
 use destiny_pkg::TagHash;
+use serde::Serialize;
 use tiger_parse::{tiger_tag, NullString, Pointer};
 
-use super::{ExtendedHash, Tag};
+use super::{dump::remote, ExtendedHash, Tag};
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0x8080978C)]
 pub struct SRenderGlobals {
     pub file_size: u64,
@@ -11,7 +12,7 @@ pub struct SRenderGlobals {
     pub unk18: Vec<()>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0x8080870F)]
 pub struct Unk8080870f {
     pub unk0: u32,
@@ -20,51 +21,62 @@ pub struct Unk8080870f {
     pub unkc: u32,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct Unk808067a8 {
     pub file_size: u64,
+    #[serde(serialize_with = "remote")]
     pub unk8: TagHash,
     _pad10: u32,
     pub scopes: Vec<Unk808067ad>,
     pub unk20: Vec<Unk808067ac>,
     /// Lookup textures
     pub unk30: Tag<Unk808066ae>,
+    #[serde(serialize_with = "remote")]
     pub unk34: TagHash,
+    #[serde(serialize_with = "remote")]
     pub unk38: TagHash,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0x808066ae)]
 pub struct Unk808066ae {
     pub file_size: u64,
+    #[serde(serialize_with = "remote")]
     pub unk8_tex2d: TagHash,
+    #[serde(serialize_with = "remote")]
     pub unkc_tex2d: TagHash,
+    #[serde(serialize_with = "remote")]
     pub unk10_tex3d: TagHash,
+    #[serde(serialize_with = "remote")]
     pub iridescence_lookup_texture: TagHash,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0x808067AD)]
 pub struct Unk808067ad {
+    #[serde(serialize_with = "remote")]
     pub name: Pointer<NullString>,
     pub unk8: u32,
     pub scope: Tag<SScope>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0x808067AC)]
 pub struct Unk808067ac {
+    #[serde(serialize_with = "remote")]
     pub name: Pointer<NullString>,
     pub unk8: u32,
+    #[serde(serialize_with = "remote")]
     pub technique: TagHash,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff, size = 0x400)]
 
 pub struct SScope {
     pub file_size: u64,
+    #[serde(serialize_with = "remote")]
     pub name: Pointer<NullString>,
 
     #[tag(offset = 0x58)]
@@ -91,7 +103,7 @@ impl SScope {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct SScopeStage {
     pub unk0: u64,
@@ -102,6 +114,7 @@ pub struct SScopeStage {
     pub unk48: [u32; 4],
 
     pub constant_buffer_slot: u32,
+    #[serde(serialize_with = "remote")]
     pub constant_buffer: TagHash,
 
     pub unksomething: [u32; 10],
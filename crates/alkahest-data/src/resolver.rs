@@ -0,0 +1,176 @@
+//! Where nested tags (see [`crate::Tag`]) get their data from while parsing
+
+use std::{cell::RefCell, collections::HashMap, io::Cursor, sync::Arc};
+
+use alkahest_pm::package_manager_checked;
+use destiny_pkg::{PackageManager, TagHash, TagHash64};
+use tiger_parse::{dpkg::PackageManagerExt, TigerReadable};
+
+/// Provides the data of tags referenced by other tags.
+///
+/// Resolvers only hand out bytes, so tags read through a resolver set with [`with_resolver`] are parsed without
+/// checking their type. Only the global package manager path checks the tag type against the struct being read
+pub trait TagResolver: Send + Sync {
+    fn read_tag(&self, hash: TagHash) -> anyhow::Result<Vec<u8>>;
+
+    /// Looks up the 32-bit hash of a tag that is referenced by its 64-bit hash
+    fn hash64_to_hash32(&self, hash: TagHash64) -> Option<TagHash>;
+
+    fn read_tag64(&self, hash: TagHash64) -> anyhow::Result<Vec<u8>> {
+        let hash32 = self
+            .hash64_to_hash32(hash)
+            .ok_or_else(|| anyhow::anyhow!("Unknown 64-bit tag hash {:016X}", hash.0.to_be()))?;
+
+        self.read_tag(hash32)
+    }
+}
+
+impl TagResolver for PackageManager {
+    fn read_tag(&self, hash: TagHash) -> anyhow::Result<Vec<u8>> {
+        PackageManager::read_tag(self, hash)
+    }
+
+    fn hash64_to_hash32(&self, hash: TagHash64) -> Option<TagHash> {
+        self.hash64_table.get(&hash.0).map(|v| v.hash32)
+    }
+}
+
+/// Resolves tags from memory, for parsing data without any packages
+#[derive(Default, Clone)]
+pub struct MemoryResolver {
+    pub tags: HashMap<TagHash, Vec<u8>>,
+    pub hash64_table: HashMap<u64, TagHash>,
+}
+
+impl MemoryResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, hash: TagHash, data: Vec<u8>) -> &mut Self {
+        self.tags.insert(hash, data);
+        self
+    }
+
+    pub fn insert64(&mut self, hash64: TagHash64, hash: TagHash, data: Vec<u8>) -> &mut Self {
+        self.hash64_table.insert(hash64.0, hash);
+        self.insert(hash, data)
+    }
+}
+
+impl TagResolver for MemoryResolver {
+    fn read_tag(&self, hash: TagHash) -> anyhow::Result<Vec<u8>> {
+        self.tags
+            .get(&hash)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Tag {hash} is not in the resolver"))
+    }
+
+    fn hash64_to_hash32(&self, hash: TagHash64) -> Option<TagHash> {
+        self.hash64_table.get(&hash.0).copied()
+    }
+}
+
+thread_local! {
+    static RESOLVER: RefCell<Option<Arc<dyn TagResolver>>> = const { RefCell::new(None) };
+}
+
+/// Resolves tags through `resolver` for everything parsed by `f` on the current thread.
+/// Without a resolver, tags are read from the global package manager
+pub fn with_resolver<R>(resolver: Arc<dyn TagResolver>, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<Arc<dyn TagResolver>>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            RESOLVER.with(|r| *r.borrow_mut() = self.0.take());
+        }
+    }
+
+    let _restore = Restore(RESOLVER.with(|r| r.borrow_mut().replace(resolver)));
+    f()
+}
+
+pub(crate) fn scoped_resolver() -> Option<Arc<dyn TagResolver>> {
+    RESOLVER.with(|r| r.borrow().clone())
+}
+
+/// The resolver tags are currently read from, see [`with_resolver`]
+pub fn resolver() -> tiger_parse::Result<Arc<dyn TagResolver>> {
+    match scoped_resolver() {
+        Some(r) => Ok(r),
+        None => Ok(package_manager_checked()?),
+    }
+}
+
+pub fn read_tag_struct<T: TigerReadable>(hash: TagHash) -> tiger_parse::Result<T> {
+    match scoped_resolver() {
+        Some(r) => parse(&r.read_tag(hash)?),
+        // The package manager checks the tag type against T
+        None => Ok(package_manager_checked()?.read_tag_struct(hash)?),
+    }
+}
+
+pub fn read_tag64_struct<T: TigerReadable>(hash: TagHash64) -> tiger_parse::Result<T> {
+    match scoped_resolver() {
+        Some(r) => parse(&r.read_tag64(hash)?),
+        None => Ok(package_manager_checked()?.read_tag64_struct(hash)?),
+    }
+}
+
+fn parse<T: TigerReadable>(data: &[u8]) -> tiger_parse::Result<T> {
+    T::read_ds_endian(&mut Cursor::new(data), tiger_parse::Endian::Little)
+}
+
+#[cfg(test)]
+mod tests {
+    use tiger_parse::tiger_tag;
+
+    use super::*;
+    use crate::Tag;
+
+    #[derive(Debug)]
+    #[tiger_tag(id = 0xffffffff, size = 0x8)]
+    struct SFixtureParent {
+        value: u32,
+        child: Tag<SFixtureChild>,
+    }
+
+    #[derive(Debug)]
+    #[tiger_tag(id = 0xffffffff, size = 0x4)]
+    struct SFixtureChild {
+        value: u32,
+    }
+
+    const CHILD_HASH: TagHash = TagHash(0x80a01234);
+
+    fn fixture_resolver() -> Arc<dyn TagResolver> {
+        let mut resolver = MemoryResolver::new();
+        resolver.insert(
+            CHILD_HASH,
+            include_bytes!("../fixtures/nested_tag_child.bin").to_vec(),
+        );
+        Arc::new(resolver)
+    }
+
+    #[test]
+    fn nested_tag_from_memory() {
+        let parent: SFixtureParent = with_resolver(fixture_resolver(), || {
+            parse(include_bytes!("../fixtures/nested_tag_parent.bin"))
+        })
+        .unwrap();
+
+        assert_eq!(parent.value, 0x11223344);
+        assert_eq!(parent.child.hash(), CHILD_HASH);
+        assert_eq!(parent.child.value, 0xdeadbeef);
+    }
+
+    #[test]
+    fn missing_nested_tag() {
+        let result: tiger_parse::Result<SFixtureParent> =
+            with_resolver(Arc::new(MemoryResolver::new()), || {
+                parse(include_bytes!("../fixtures/nested_tag_parent.bin"))
+            });
+
+        assert!(result.is_err());
+        assert!(scoped_resolver().is_none());
+    }
+}
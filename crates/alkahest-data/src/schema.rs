@@ -0,0 +1,513 @@
+//! Tag layouts that are described at runtime instead of in Rust, for iterating on unknown structs without rebuilding.
+//! Schemas are written in RON, eg.
+//!
+//! ```ron
+//! (
+//!     structs: {
+//!         "Unk80806d19": (
+//!             size: Some(0x70),
+//!             fields: [
+//!                 (name: "file_size", type: U64),
+//!                 (name: "unk8", type: TagHash),
+//!                 (name: "unk50", offset: Some(0x50), type: Array(Struct("Unk80806d4f"))),
+//!             ],
+//!         ),
+//!         "Unk80806d4f": (
+//!             fields: [
+//!                 (name: "translation", type: Vec4),
+//!                 (name: "unk10", type: FixedArray(U32, 4)),
+//!             ],
+//!         ),
+//!     },
+//! )
+//! ```
+
+use std::collections::HashMap;
+
+use anyhow::Context;
+use destiny_pkg::{TagHash, TagHash64};
+use serde::{ser::SerializeMap, Deserialize, Serialize, Serializer};
+
+use crate::{resolver::resolver, ExtendedHash};
+
+/// Limits how deep structs, pointers and tags can be nested, so recursive schemas can't overflow the stack
+const MAX_DEPTH: usize = 32;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SchemaSet {
+    pub structs: HashMap<String, StructSchema>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StructSchema {
+    /// Defaults to the end of the last field
+    #[serde(default)]
+    pub size: Option<u64>,
+    pub fields: Vec<FieldSchema>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldSchema {
+    pub name: String,
+    /// Offset from the start of the struct. Defaults to the end of the previous field
+    #[serde(default)]
+    pub offset: Option<u64>,
+    #[serde(rename = "type")]
+    pub ty: FieldType,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum FieldType {
+    U8,
+    U16,
+    U32,
+    U64,
+    I8,
+    I16,
+    I32,
+    I64,
+    F32,
+    Bool,
+    TagHash,
+    TagHash64,
+    ExtendedHash,
+    ResourceHash,
+    Vec2,
+    Vec3,
+    Vec4,
+    Mat4,
+    /// Raw bytes, for fields that haven't been figured out yet
+    Bytes(u64),
+    /// Inline array with a fixed number of elements
+    FixedArray(Box<FieldType>, u64),
+    /// Array stored elsewhere in the tag (`Vec<T>`)
+    Array(Box<FieldType>),
+    /// Relative pointer (`Pointer<T>`)
+    Pointer(Box<FieldType>),
+    /// Relative pointer to a null-terminated string (`Pointer<NullString>`)
+    String,
+    /// Reference to another tag, which is read with the given type (`Tag<T>`)
+    Tag(Box<FieldType>),
+    /// Struct from the same schema set
+    Struct(String),
+}
+
+/// Data read through a schema. Serializes the same way as the Rust structs do, see [`crate::dump`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemaValue {
+    Null,
+    Bool(bool),
+    Unsigned(u64),
+    Signed(i64),
+    Float(f32),
+    /// Hashes and raw bytes
+    Hex(String),
+    String(String),
+    Array(Vec<SchemaValue>),
+    Struct(Vec<(String, SchemaValue)>),
+}
+
+impl Serialize for SchemaValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            SchemaValue::Null => serializer.serialize_none(),
+            SchemaValue::Bool(v) => serializer.serialize_bool(*v),
+            SchemaValue::Unsigned(v) => serializer.serialize_u64(*v),
+            SchemaValue::Signed(v) => serializer.serialize_i64(*v),
+            SchemaValue::Float(v) => serializer.serialize_f32(*v),
+            SchemaValue::Hex(v) | SchemaValue::String(v) => serializer.serialize_str(v),
+            SchemaValue::Array(v) => serializer.collect_seq(v),
+            SchemaValue::Struct(fields) => {
+                let mut map = serializer.serialize_map(Some(fields.len()))?;
+                for (name, value) in fields {
+                    map.serialize_entry(name, value)?;
+                }
+                map.end()
+            }
+        }
+    }
+}
+
+impl SchemaSet {
+    pub fn from_ron(source: &str) -> anyhow::Result<Self> {
+        Ok(ron::from_str(source)?)
+    }
+
+    pub fn load(path: impl AsRef<std::path::Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read schema {}", path.display()))?;
+
+        Self::from_ron(&source)
+            .with_context(|| format!("Failed to parse schema {}", path.display()))
+    }
+
+    fn get(&self, name: &str) -> anyhow::Result<&StructSchema> {
+        self.structs
+            .get(name)
+            .with_context(|| format!("Struct {name} is not in the schema"))
+    }
+
+    /// Applies the struct `name` to the start of `data`, which is usually the contents of a whole tag
+    pub fn read(&self, name: &str, data: &[u8]) -> anyhow::Result<SchemaValue> {
+        self.read_type(&FieldType::Struct(name.to_string()), data, 0, 0)
+    }
+
+    /// Reads the tag with the current resolver and applies the struct `name` to it
+    pub fn read_tag(&self, name: &str, hash: TagHash) -> anyhow::Result<SchemaValue> {
+        let data = resolver()?.read_tag(hash)?;
+        self.read(name, &data)
+    }
+
+    /// Inline size of a field of this type
+    pub fn size_of(&self, ty: &FieldType) -> anyhow::Result<u64> {
+        self.size_of_nested(ty, 0)
+    }
+
+    fn size_of_nested(&self, ty: &FieldType, depth: usize) -> anyhow::Result<u64> {
+        anyhow::ensure!(depth < MAX_DEPTH, "Schema nesting is too deep");
+
+        Ok(match ty {
+            FieldType::U8 | FieldType::I8 | FieldType::Bool => 1,
+            FieldType::U16 | FieldType::I16 => 2,
+            FieldType::U32
+            | FieldType::I32
+            | FieldType::F32
+            | FieldType::TagHash
+            | FieldType::ResourceHash
+            | FieldType::Tag(_) => 4,
+            FieldType::U64
+            | FieldType::I64
+            | FieldType::TagHash64
+            | FieldType::Vec2
+            | FieldType::Pointer(_)
+            | FieldType::String => 8,
+            FieldType::Vec3 => 12,
+            FieldType::ExtendedHash | FieldType::Vec4 | FieldType::Array(_) => 16,
+            FieldType::Mat4 => 64,
+            FieldType::Bytes(len) => *len,
+            FieldType::FixedArray(ty, count) => self
+                .size_of_nested(ty, depth + 1)?
+                .checked_mul(*count)
+                .context("Array size overflows")?,
+            FieldType::Struct(name) => {
+                let schema = self.get(name)?;
+                match schema.size {
+                    Some(size) => size,
+                    None => {
+                        let mut end = 0;
+                        for field in &schema.fields {
+                            end = field.offset.unwrap_or(end)
+                                + self.size_of_nested(&field.ty, depth + 1)?;
+                        }
+                        end
+                    }
+                }
+            }
+        })
+    }
+
+    fn read_type(
+        &self,
+        ty: &FieldType,
+        data: &[u8],
+        offset: u64,
+        depth: usize,
+    ) -> anyhow::Result<SchemaValue> {
+        anyhow::ensure!(depth < MAX_DEPTH, "Schema nesting is too deep");
+
+        Ok(match ty {
+            FieldType::U8 => SchemaValue::Unsigned(read_bytes::<1>(data, offset)?[0] as u64),
+            FieldType::U16 => {
+                SchemaValue::Unsigned(u16::from_le_bytes(read_bytes(data, offset)?) as u64)
+            }
+            FieldType::U32 => SchemaValue::Unsigned(read_u32(data, offset)? as u64),
+            FieldType::U64 => SchemaValue::Unsigned(read_u64(data, offset)?),
+            FieldType::I8 => SchemaValue::Signed(read_bytes::<1>(data, offset)?[0] as i8 as i64),
+            FieldType::I16 => {
+                SchemaValue::Signed(i16::from_le_bytes(read_bytes(data, offset)?) as i64)
+            }
+            FieldType::I32 => {
+                SchemaValue::Signed(i32::from_le_bytes(read_bytes(data, offset)?) as i64)
+            }
+            FieldType::I64 => SchemaValue::Signed(i64::from_le_bytes(read_bytes(data, offset)?)),
+            FieldType::F32 => SchemaValue::Float(read_f32(data, offset)?),
+            FieldType::Bool => SchemaValue::Bool(read_bytes::<1>(data, offset)?[0] != 0),
+            FieldType::TagHash => {
+                SchemaValue::Hex(ExtendedHash::Hash32(TagHash(read_u32(data, offset)?)).to_string())
+            }
+            FieldType::TagHash64 => SchemaValue::Hex(
+                ExtendedHash::Hash64(TagHash64(read_u64(data, offset)?)).to_string(),
+            ),
+            FieldType::ExtendedHash => {
+                let hash = if read_u32(data, offset + 4)? != 0 {
+                    ExtendedHash::Hash32(TagHash(read_u32(data, offset)?))
+                } else {
+                    ExtendedHash::Hash64(TagHash64(read_u64(data, offset + 8)?))
+                };
+                SchemaValue::Hex(hash.to_string())
+            }
+            FieldType::ResourceHash => SchemaValue::Hex(format!("{:08X}", read_u32(data, offset)?)),
+            FieldType::Vec2 => read_floats(data, offset, 2)?,
+            FieldType::Vec3 => read_floats(data, offset, 3)?,
+            FieldType::Vec4 => read_floats(data, offset, 4)?,
+            FieldType::Mat4 => read_floats(data, offset, 16)?,
+            FieldType::Bytes(len) => {
+                let bytes = slice(data, offset, *len)?;
+                SchemaValue::Hex(bytes.iter().map(|b| format!("{b:02X}")).collect())
+            }
+            FieldType::FixedArray(ty, count) => self.read_array(ty, data, offset, *count, depth)?,
+            FieldType::Array(ty) => {
+                let count = read_u64(data, offset)?;
+                if count == 0 {
+                    return Ok(SchemaValue::Array(vec![]));
+                }
+
+                // The offset points to the array header, which is followed by the data
+                let header = relative(offset + 8, read_u64(data, offset + 8)?)?;
+                self.read_array(ty, data, relative(header, 0x10)?, count, depth)?
+            }
+            FieldType::Pointer(ty) => match read_u64(data, offset)? {
+                0 => SchemaValue::Null,
+                rel => self.read_type(ty, data, relative(offset, rel)?, depth + 1)?,
+            },
+            FieldType::String => match read_u64(data, offset)? {
+                0 => SchemaValue::Null,
+                rel => {
+                    let start = relative(offset, rel)?;
+                    let bytes = data
+                        .get(start as usize..)
+                        .context("String is out of bounds")?;
+                    let len = bytes
+                        .iter()
+                        .position(|&b| b == 0)
+                        .context("String is not null-terminated")?;
+                    SchemaValue::String(String::from_utf8_lossy(&bytes[..len]).into_owned())
+                }
+            },
+            FieldType::Tag(ty) => {
+                let hash = TagHash(read_u32(data, offset)?);
+                let value = if hash.is_some() {
+                    let tag_data = resolver()?.read_tag(hash)?;
+                    self.read_type(ty, &tag_data, 0, depth + 1)
+                        .with_context(|| format!("In tag {hash}"))?
+                } else {
+                    SchemaValue::Null
+                };
+
+                SchemaValue::Struct(vec![
+                    (
+                        "hash".to_string(),
+                        SchemaValue::Hex(ExtendedHash::Hash32(hash).to_string()),
+                    ),
+                    ("data".to_string(), value),
+                ])
+            }
+            FieldType::Struct(name) => {
+                let schema = self.get(name)?;
+                let mut fields = Vec::with_capacity(schema.fields.len());
+                let mut end = 0;
+                for field in &schema.fields {
+                    let field_offset = field.offset.unwrap_or(end);
+                    let value = self
+                        .read_type(&field.ty, data, offset + field_offset, depth + 1)
+                        .with_context(|| {
+                            format!("{name}.{} (offset 0x{field_offset:X})", field.name)
+                        })?;
+
+                    end = field_offset + self.size_of_nested(&field.ty, depth + 1)?;
+                    fields.push((field.name.clone(), value));
+                }
+
+                SchemaValue::Struct(fields)
+            }
+        })
+    }
+
+    fn read_array(
+        &self,
+        ty: &FieldType,
+        data: &[u8],
+        offset: u64,
+        count: u64,
+        depth: usize,
+    ) -> anyhow::Result<SchemaValue> {
+        let stride = self.size_of_nested(ty, depth + 1)?;
+        // Zero-size elements would let any count through the bounds check below
+        anyhow::ensure!(stride != 0, "Array elements have a size of 0");
+
+        // Catch garbage counts before trying to allocate for them
+        let end = stride
+            .checked_mul(count)
+            .and_then(|size| size.checked_add(offset))
+            .context("Array size overflows")?;
+        anyhow::ensure!(
+            end <= data.len() as u64,
+            "Array of {count} elements at 0x{offset:X} is out of bounds"
+        );
+
+        let mut values = Vec::with_capacity(count as usize);
+        for i in 0..count {
+            values.push(
+                self.read_type(ty, data, offset + i * stride, depth + 1)
+                    .with_context(|| format!("[{i}]"))?,
+            );
+        }
+
+        Ok(SchemaValue::Array(values))
+    }
+}
+
+fn relative(offset: u64, rel: u64) -> anyhow::Result<u64> {
+    offset.checked_add(rel).context("Relative offset overflows")
+}
+
+fn slice(data: &[u8], offset: u64, len: u64) -> anyhow::Result<&[u8]> {
+    offset
+        .checked_add(len)
+        .and_then(|end| data.get(offset as usize..end as usize))
+        .with_context(|| format!("0x{len:X} bytes at 0x{offset:X} are out of bounds"))
+}
+
+fn read_bytes<const N: usize>(data: &[u8], offset: u64) -> anyhow::Result<[u8; N]> {
+    Ok(slice(data, offset, N as u64)?.try_into().unwrap())
+}
+
+fn read_u32(data: &[u8], offset: u64) -> anyhow::Result<u32> {
+    Ok(u32::from_le_bytes(read_bytes(data, offset)?))
+}
+
+fn read_u64(data: &[u8], offset: u64) -> anyhow::Result<u64> {
+    Ok(u64::from_le_bytes(read_bytes(data, offset)?))
+}
+
+fn read_f32(data: &[u8], offset: u64) -> anyhow::Result<f32> {
+    Ok(f32::from_le_bytes(read_bytes(data, offset)?))
+}
+
+fn read_floats(data: &[u8], offset: u64, count: u64) -> anyhow::Result<SchemaValue> {
+    Ok(SchemaValue::Array(
+        (0..count)
+            .map(|i| read_f32(data, offset + i * 4).map(SchemaValue::Float))
+            .collect::<anyhow::Result<_>>()?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &str = r#"(
+        structs: {
+            "Unk80806d19": (
+                size: Some(0x70),
+                fields: [
+                    (name: "file_size", type: U64),
+                    (name: "unk8", type: TagHash),
+                    (name: "unk50", offset: Some(0x50), type: Array(Struct("Unk80806d4f"))),
+                ],
+            ),
+            "Unk80806d4f": (
+                fields: [
+                    (name: "translation", type: Vec4),
+                    (name: "unk10", type: FixedArray(U32, 4)),
+                ],
+            ),
+            "Empty": (
+                fields: [],
+            ),
+        },
+    )"#;
+
+    /// `Unk80806d19` with `unk50` pointing at `elements` elements, of which only the first one is present
+    fn tag_data(elements: u64) -> Vec<u8> {
+        let mut data = vec![0u8; 0x70];
+        data[0..8].copy_from_slice(&0x1234u64.to_le_bytes());
+        data[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+        data[0x50..0x58].copy_from_slice(&elements.to_le_bytes());
+        // Relative to the pointer itself, the array header is at 0x70
+        data[0x58..0x60].copy_from_slice(&0x18u64.to_le_bytes());
+
+        data.extend_from_slice(&[0; 0x10]);
+        for v in [1.0f32, 2.0, 3.0, 4.0] {
+            data.extend_from_slice(&v.to_le_bytes());
+        }
+        for v in 0..4u32 {
+            data.extend_from_slice(&v.to_le_bytes());
+        }
+
+        data
+    }
+
+    #[test]
+    fn ron_round_trip() {
+        let schema = SchemaSet::from_ron(SCHEMA).unwrap();
+        let ron = ron::ser::to_string_pretty(&schema, ron::ser::PrettyConfig::default()).unwrap();
+
+        assert_eq!(SchemaSet::from_ron(&ron).unwrap(), schema);
+        assert_eq!(
+            schema
+                .size_of(&FieldType::Struct("Unk80806d4f".to_string()))
+                .unwrap(),
+            0x20
+        );
+    }
+
+    #[test]
+    fn read_struct_with_array() {
+        let schema = SchemaSet::from_ron(SCHEMA).unwrap();
+        let SchemaValue::Struct(fields) = schema.read("Unk80806d19", &tag_data(1)).unwrap() else {
+            panic!("Expected a struct");
+        };
+
+        assert_eq!(
+            fields[0],
+            ("file_size".to_string(), SchemaValue::Unsigned(0x1234))
+        );
+        let SchemaValue::Array(elements) = &fields[2].1 else {
+            panic!("Expected an array");
+        };
+        assert_eq!(
+            elements[0],
+            SchemaValue::Struct(vec![
+                (
+                    "translation".to_string(),
+                    SchemaValue::Array(
+                        [1.0, 2.0, 3.0, 4.0]
+                            .into_iter()
+                            .map(SchemaValue::Float)
+                            .collect()
+                    )
+                ),
+                (
+                    "unk10".to_string(),
+                    SchemaValue::Array((0..4).map(SchemaValue::Unsigned).collect())
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn array_out_of_bounds() {
+        let schema = SchemaSet::from_ron(SCHEMA).unwrap();
+
+        assert!(schema.read("Unk80806d19", &tag_data(2)).is_err());
+        assert!(schema.read("Unk80806d19", &tag_data(u64::MAX)).is_err());
+    }
+
+    #[test]
+    fn zero_stride_array() {
+        let schema = SchemaSet::from_ron(SCHEMA).unwrap();
+        let ty = FieldType::FixedArray(Box::new(FieldType::Struct("Empty".to_string())), u64::MAX);
+
+        assert!(schema.read_type(&ty, &[], 0, 0).is_err());
+        assert!(schema
+            .read_type(
+                &FieldType::FixedArray(Box::new(FieldType::Bytes(0)), 1 << 40),
+                &[],
+                0,
+                0
+            )
+            .is_err());
+    }
+}
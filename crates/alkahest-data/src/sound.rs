@@ -1,16 +1,23 @@
 use destiny_pkg::TagHash;
+use serde::Serialize;
 use tiger_parse::tiger_tag;
 
-#[derive(Debug)]
+use crate::dump::remote;
+
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0x80809738)]
 pub struct SRandomSound {
     pub file_size: u64,
     pub unk8: u32,
     pub unkc: u32,
     pub unk10: u32,
+    #[serde(serialize_with = "remote")]
     pub unk14: TagHash,
+    #[serde(serialize_with = "remote")]
     pub unk18: TagHash,
     pub unk1c: u32,
+    #[serde(serialize_with = "remote")]
     pub streams: Vec<TagHash>,
+    #[serde(serialize_with = "remote")]
     pub unk30: TagHash,
 }
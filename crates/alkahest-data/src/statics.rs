@@ -1,19 +1,23 @@
 use destiny_pkg::TagHash;
+use serde::Serialize;
 use tiger_parse::tiger_tag;
 
 use crate::{
+    dump::remote,
     geometry::{ELodCategory, EPrimitiveType},
     occlusion::SOcclusionBounds,
     tag::Tag,
     tfx::TfxRenderStage,
 };
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct SStaticMesh {
     pub file_size: u64,
+    #[serde(serialize_with = "remote")]
     pub unk8: TagHash,
     pub unkc: u32,
+    #[serde(serialize_with = "remote")]
     pub materials: Vec<TagHash>,
     pub unk20: Vec<SStaticMeshOverlay>, // Overlay/transparent meshes
     pub unk30: [u32; 2],
@@ -22,12 +26,13 @@ pub struct SStaticMesh {
     pub unk60: glam::Vec4,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff, size = 0x60)]
 pub struct SStaticMeshData {
     pub file_size: u64,
     pub mesh_groups: Vec<Unk8080719b>,
     pub parts: Vec<Unk8080719a>,
+    #[serde(serialize_with = "remote")]
     pub buffers: Vec<(TagHash, TagHash, TagHash, TagHash)>,
 
     #[tag(offset = 0x40)]
@@ -37,7 +42,7 @@ pub struct SStaticMeshData {
     pub texture_coordinate_offset: glam::Vec2,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 #[tiger_tag(id = 0x80806D37)]
 pub struct Unk8080719a {
     pub index_start: u32,
@@ -48,7 +53,7 @@ pub struct Unk8080719a {
     pub primitive_type: EPrimitiveType,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 #[tiger_tag(id = 0x80806D38)]
 pub struct Unk8080719b {
     pub part_index: u16,
@@ -57,7 +62,7 @@ pub struct Unk8080719b {
     pub unk5: u16,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 #[tiger_tag(id = 0xffffffff, size = 0x98)]
 pub struct SStaticMeshInstances {
     #[tag(offset = 0x18)]
@@ -67,11 +72,12 @@ pub struct SStaticMeshInstances {
     pub transforms: Vec<Unk808071a3>,
     pub unk50: u64,
     pub unk58: [u64; 4],
+    #[serde(serialize_with = "remote")]
     pub statics: Vec<TagHash>,
     pub instance_groups: Vec<SStaticMeshInstanceGroup>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 #[tiger_tag(id = 0x80806D28)]
 pub struct SStaticMeshInstanceGroup {
     pub instance_count: u16,
@@ -80,7 +86,7 @@ pub struct SStaticMeshInstanceGroup {
     pub unk6: u16,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 #[tiger_tag(id = 0x80806D40)]
 pub struct Unk808071a3 {
     pub rotation: glam::Vec4, // TODO(cohae): Quat type? (alias?)
@@ -91,7 +97,7 @@ pub struct Unk808071a3 {
     pub unk30: [u32; 4],
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 #[tiger_tag(id = 0x80806D2F)]
 pub struct SStaticMeshOverlay {
     pub render_stage: TfxRenderStage,
@@ -101,11 +107,16 @@ pub struct SStaticMeshOverlay {
     pub primitive_type: EPrimitiveType,
     pub unk5: u8,
     pub unk6: u16,
+    #[serde(serialize_with = "remote")]
     pub index_buffer: TagHash,
+    #[serde(serialize_with = "remote")]
     pub vertex_buffer: TagHash,
+    #[serde(serialize_with = "remote")]
     pub vertex_buffer2: TagHash,
+    #[serde(serialize_with = "remote")]
     pub color_buffer: TagHash,
     pub index_start: u32,
     pub index_count: u32,
+    #[serde(serialize_with = "remote")]
     pub material: TagHash,
 }
@@ -1,12 +1,20 @@
 use std::{
     fmt::{Debug, Display, Formatter},
     ops::Deref,
+    sync::{Arc, OnceLock},
 };
 
-use alkahest_pm::package_manager;
 use binrw::{BinRead, BinReaderExt};
 use destiny_pkg::{TagHash, TagHash64};
-use tiger_parse::{dpkg::PackageManagerExt, TigerReadable};
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+use tiger_parse::TigerReadable;
+
+use crate::{
+    dump::{Remote, RemoteSerialize},
+    resolver::{
+        read_tag64_struct, read_tag_struct, resolver, scoped_resolver, with_resolver, TagResolver,
+    },
+};
 
 #[derive(Clone)]
 pub struct Tag<T: TigerReadable>(pub T, TagHash);
@@ -17,7 +25,7 @@ impl<T: TigerReadable> TigerReadable for Tag<T> {
         endian: tiger_parse::Endian,
     ) -> tiger_parse::Result<Self> {
         let tag = TagHash::read_ds_endian(reader, endian)?;
-        Ok(Tag(package_manager().read_tag_struct(tag)?, tag))
+        Ok(Tag(read_tag_struct(tag)?, tag))
     }
 
     const ZEROCOPY: bool = false;
@@ -43,6 +51,94 @@ impl<T: TigerReadable + Debug> Debug for Tag<T> {
     }
 }
 
+impl<T: TigerReadable + Serialize> Serialize for Tag<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("Tag", 2)?;
+        s.serialize_field("hash", &Remote(&self.hash()))?;
+        s.serialize_field("data", &self.0)?;
+        s.end()
+    }
+}
+
+/// Tag reference that is only read when it's first accessed, unlike [`Tag`] which reads the tag along with its parent
+pub struct LazyTag<T: TigerReadable> {
+    hash: TagHash,
+    /// Resolver that was active when the reference was read, so the tag comes from the same place as its parent
+    resolver: Option<Arc<dyn TagResolver>>,
+    value: OnceLock<T>,
+}
+
+impl<T: TigerReadable> LazyTag<T> {
+    pub fn new(hash: TagHash) -> Self {
+        Self {
+            hash,
+            resolver: scoped_resolver(),
+            value: OnceLock::new(),
+        }
+    }
+
+    pub fn hash(&self) -> TagHash {
+        self.hash
+    }
+
+    /// Reads the tag if it hasn't been read yet
+    pub fn get(&self) -> tiger_parse::Result<&T> {
+        if let Some(value) = self.value.get() {
+            return Ok(value);
+        }
+
+        let value = match &self.resolver {
+            Some(r) => with_resolver(r.clone(), || read_tag_struct(self.hash))?,
+            None => read_tag_struct(self.hash)?,
+        };
+
+        Ok(self.value.get_or_init(|| value))
+    }
+
+    /// Returns the tag if it has already been read
+    pub fn get_loaded(&self) -> Option<&T> {
+        self.value.get()
+    }
+}
+
+impl<T: TigerReadable> TigerReadable for LazyTag<T> {
+    fn read_ds_endian<R: std::io::prelude::Read + std::io::prelude::Seek>(
+        reader: &mut R,
+        endian: tiger_parse::Endian,
+    ) -> tiger_parse::Result<Self> {
+        Ok(LazyTag::new(TagHash::read_ds_endian(reader, endian)?))
+    }
+
+    const ZEROCOPY: bool = false;
+    const SIZE: usize = TagHash::SIZE;
+}
+
+impl<T: TigerReadable + Clone> Clone for LazyTag<T> {
+    fn clone(&self) -> Self {
+        Self {
+            hash: self.hash,
+            resolver: self.resolver.clone(),
+            value: self.value.clone(),
+        }
+    }
+}
+
+impl<T: TigerReadable + Debug> Debug for LazyTag<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.value.get() {
+            Some(v) => v.fmt(f),
+            None => f.write_fmt(format_args!("LazyTag({})", self.hash)),
+        }
+    }
+}
+
+/// Only the hash is written, so the output doesn't depend on whether the tag has been read yet
+impl<T: TigerReadable> Serialize for LazyTag<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.hash.serialize_remote(serializer)
+    }
+}
+
 // TODO(cohae): Custom reader once new tag parser comes around
 #[derive(Clone, Copy, Eq, PartialEq)]
 pub enum ExtendedHash {
@@ -59,17 +155,17 @@ impl ExtendedHash {
         }
     }
 
-    /// Will lookup hash64 in package managers's h64 table in the case of a 64 bit hash
+    /// Will lookup hash64 in the current resolver's h64 table in the case of a 64 bit hash
     /// Falls back to TagHash::NONE if not found
     pub fn hash32(&self) -> TagHash {
         self.hash32_checked().unwrap_or(TagHash::NONE)
     }
 
-    /// Will lookup hash64 in package managers's h64 table in the case of a 64 bit hash
+    /// Will lookup hash64 in the current resolver's h64 table in the case of a 64 bit hash
     pub fn hash32_checked(&self) -> Option<TagHash> {
         match self {
             ExtendedHash::Hash32(v) => Some(*v),
-            ExtendedHash::Hash64(v) => package_manager().hash64_table.get(&v.0).map(|v| v.hash32),
+            ExtendedHash::Hash64(v) => resolver().ok()?.hash64_to_hash32(*v),
         }
     }
 
@@ -100,6 +196,12 @@ impl Display for ExtendedHash {
     }
 }
 
+impl Serialize for ExtendedHash {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
 impl std::hash::Hash for ExtendedHash {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         state.write_u64(self.key());
@@ -164,8 +266,8 @@ impl<T: TigerReadable> TigerReadable for ExtendedTag<T> {
     ) -> tiger_parse::Result<Self> {
         let tag = ExtendedHash::read_ds_endian(reader, endian)?;
         match tag {
-            ExtendedHash::Hash32(h) => Ok(ExtendedTag(package_manager().read_tag_struct(h)?)),
-            ExtendedHash::Hash64(h) => Ok(ExtendedTag(package_manager().read_tag64_struct(h)?)),
+            ExtendedHash::Hash32(h) => Ok(ExtendedTag(read_tag_struct(h)?)),
+            ExtendedHash::Hash64(h) => Ok(ExtendedTag(read_tag64_struct(h)?)),
         }
     }
 
@@ -185,3 +287,9 @@ impl<T: TigerReadable + Debug> Debug for ExtendedTag<T> {
         self.0.fmt(f)
     }
 }
+
+impl<T: TigerReadable + Serialize> Serialize for ExtendedTag<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
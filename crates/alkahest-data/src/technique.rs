@@ -1,9 +1,10 @@
 use destiny_pkg::TagHash;
+use serde::Serialize;
 use tiger_parse::{tiger_tag, NullString, Pointer};
 
-use crate::{tfx::TfxShaderStage, ExtendedHash};
+use crate::{dump::remote, tfx::TfxShaderStage, ExtendedHash};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 #[tiger_tag(id = 0x80806DAA)]
 pub struct STechnique {
     pub file_size: u64,
@@ -80,9 +81,10 @@ impl STechnique {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct STechniqueShader {
+    #[serde(serialize_with = "remote")]
     pub shader: TagHash,
     pub unk4: u32,
     pub textures: Vec<SMaterialTextureAssignment>, // 0x8
@@ -95,12 +97,13 @@ pub struct STechniqueShader {
     pub unk60: [u32; 4], // 0x60
 
     pub constant_buffer_slot: u32, // 0x70
-    pub constant_buffer: TagHash,  // 0x74
+    #[serde(serialize_with = "remote")]
+    pub constant_buffer: TagHash, // 0x74
 
     pub unk78: [u32; 6],
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 #[tiger_tag(id = 0x80806DCF)]
 pub struct SMaterialTextureAssignment {
     /// Material slot to assign to
@@ -109,30 +112,36 @@ pub struct SMaterialTextureAssignment {
     pub texture: ExtendedHash,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct Unk80806cb1 {
     pub file_size: u64,
+    #[serde(serialize_with = "remote")]
     pub unk8: TagHash,
     pub unkc: u32,
     pub unk10: Vec<Unk80806cb6>,
     pub unk20: Vec<Unk80806cb5>,
+    #[serde(serialize_with = "remote")]
     pub unk30: TagHash,
+    #[serde(serialize_with = "remote")]
     pub unk34: TagHash,
+    #[serde(serialize_with = "remote")]
     pub unk38: TagHash,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct Unk80806cb5 {
+    #[serde(serialize_with = "remote")]
     pub name: Pointer<NullString>,
     pub unk8: u32,
+    #[serde(serialize_with = "remote")]
     pub unkc: TagHash,
 }
 
 pub type Unk80806cb6 = Unk80806cb5;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct Unk80806da1 {
     pub file_size: u64,
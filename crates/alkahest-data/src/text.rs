@@ -1,13 +1,15 @@
 use destiny_pkg::TagHash;
+use serde::Serialize;
 use tiger_parse::{tiger_tag, Pointer};
 
-use crate::common::ResourceHash;
+use crate::{common::ResourceHash, dump::remote};
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0x808099EF)]
 pub struct SLocalizedStrings {
     pub file_size: u64,
     pub string_hashes: Vec<ResourceHash>,
+    #[serde(serialize_with = "remote")]
     pub language_english: TagHash,
     // pub language_unk1: TagHash,
     // pub language_german: TagHash,
@@ -23,7 +25,7 @@ pub struct SLocalizedStrings {
     // pub language_unk12: TagHash,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct SStringData {
     pub file_size: u64,
@@ -34,17 +36,19 @@ pub struct SStringData {
     pub string_combinations: Vec<SStringCombination>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0x808099F5)]
 pub struct SStringCombination {
+    #[serde(serialize_with = "remote")]
     pub data: Pointer<()>,
     pub part_count: i64,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0x808099F7)]
 pub struct SStringPart {
     pub _unk0: u64,
+    #[serde(serialize_with = "remote")]
     pub data: Pointer<()>,
     pub _unk1: u32,
 
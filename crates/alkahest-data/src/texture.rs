@@ -1,9 +1,10 @@
 use destiny_pkg::TagHash;
+use serde::Serialize;
 use tiger_parse::tiger_tag;
 
-use crate::dxgi::DxgiFormat;
+use crate::{dump::remote, dxgi::DxgiFormat};
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff, size = 0x40)]
 pub struct STextureHeader {
     pub data_size: u32,
@@ -25,10 +26,11 @@ pub struct STextureHeader {
     pub unk38: u32,
 
     /// Optional
+    #[serde(serialize_with = "remote")]
     pub large_buffer: TagHash,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct TexturePlate {
     pub file_size: u64,
@@ -36,20 +38,24 @@ pub struct TexturePlate {
     pub transforms: Vec<TexturePlateTransform>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct TexturePlateTransform {
+    #[serde(serialize_with = "remote")]
     pub texture: TagHash,
     pub translation: glam::IVec2,
     pub dimensions: glam::IVec2,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[tiger_tag(id = 0xffffffff)]
 pub struct TexturePlateSet {
     pub file_size: u64,
     pub _unk: [u32; 7],
+    #[serde(serialize_with = "remote")]
     pub diffuse: TagHash,
+    #[serde(serialize_with = "remote")]
     pub normal: TagHash,
+    #[serde(serialize_with = "remote")]
     pub gstack: TagHash,
 }
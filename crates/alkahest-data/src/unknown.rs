@@ -1,16 +1,17 @@
 use destiny_pkg::TagHash;
+use serde::Serialize;
 use tiger_parse::tiger_tag;
 
-use crate::common::ResourceHash;
+use crate::{common::ResourceHash, dump::remote};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 #[tiger_tag(id = 0xffffffff, size = 0x30)]
 pub struct Unk80804f72 {
     pub file_size: u64,
     pub unk8: Vec<Unk80804f74>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 #[tiger_tag(id = 0xffffffff, size = 0x30)]
 pub struct Unk80804f74 {
     pub unk0: ResourceHash,
@@ -20,11 +21,15 @@ pub struct Unk80804f74 {
     pub unk20: u64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 #[tiger_tag(id = 0xffffffff, size = 0x30)]
 pub struct Unk80804f76 {
+    #[serde(serialize_with = "remote")]
     pub unk0: (TagHash, ResourceHash),
+    #[serde(serialize_with = "remote")]
     pub unk8: (TagHash, ResourceHash),
+    #[serde(serialize_with = "remote")]
     pub unk10: (TagHash, ResourceHash),
+    #[serde(serialize_with = "remote")]
     pub unk18: (TagHash, ResourceHash),
 }
@@ -0,0 +1,416 @@
+use anyhow::Context;
+use binrw::Endian;
+use num_traits::FromPrimitive;
+
+use crate::{
+    externs::{TfxExtern, TfxShaderStage},
+    opcodes::TfxBytecodeOp,
+};
+
+/// Turns disassembly (as produced by [`TfxBytecodeOp::disassemble`]) back into opcodes
+pub struct TfxBytecodeAssembler;
+
+impl TfxBytecodeAssembler {
+    pub fn assemble(source: &str) -> anyhow::Result<Vec<TfxBytecodeOp>> {
+        let mut opcodes = vec![];
+        for (i, line) in source.lines().enumerate() {
+            // Strip comments and the instruction index printed by the console commands
+            let line = line.split("//").next().unwrap_or_default().trim();
+            let line = match line.split_once(':') {
+                Some((index, rest)) if index.trim().parse::<usize>().is_ok() => rest.trim(),
+                _ => line,
+            };
+
+            if line.is_empty() {
+                continue;
+            }
+
+            opcodes.push(
+                Self::assemble_line(line).with_context(|| format!("Line {}: '{line}'", i + 1))?,
+            );
+        }
+
+        Ok(opcodes)
+    }
+
+    pub fn assemble_bytes(source: &str, endian: Endian) -> anyhow::Result<Vec<u8>> {
+        Ok(TfxBytecodeOp::write_all(&Self::assemble(source)?, endian))
+    }
+
+    pub fn assemble_line(line: &str) -> anyhow::Result<TfxBytecodeOp> {
+        let split = line.find(['(', ' ']).unwrap_or(line.len());
+        let (mnemonic, args) = line.split_at(split);
+        let args = args.trim();
+        let args = args
+            .strip_prefix('(')
+            .and_then(|a| a.strip_suffix(')'))
+            .unwrap_or(args)
+            .trim();
+
+        Ok(match mnemonic {
+            "add" => TfxBytecodeOp::Add,
+            "subtract" => TfxBytecodeOp::Subtract,
+            "multiply" => TfxBytecodeOp::Multiply,
+            "divide" => TfxBytecodeOp::Divide,
+            "multiply2" => TfxBytecodeOp::Multiply2,
+            "add2" => TfxBytecodeOp::Add2,
+            "is_zero" => TfxBytecodeOp::IsZero,
+            "min" => TfxBytecodeOp::Min,
+            "max" => TfxBytecodeOp::Max,
+            "less_than" => TfxBytecodeOp::LessThan,
+            "dot" => TfxBytecodeOp::Dot,
+            "merge_1_3" => TfxBytecodeOp::Merge1_3,
+            "merge_2_2" => TfxBytecodeOp::Merge2_2,
+            "unk0e" => TfxBytecodeOp::Unk0e,
+            "unk0f" => TfxBytecodeOp::Unk0f,
+            "lerp" => TfxBytecodeOp::Lerp,
+            "lerp_saturated" => TfxBytecodeOp::LerpSaturated,
+            "multiply_add" => TfxBytecodeOp::MultiplyAdd,
+            "clamp" => TfxBytecodeOp::Clamp,
+            "unk14" => TfxBytecodeOp::Unk14,
+            "abs" => TfxBytecodeOp::Abs,
+            "signum" => TfxBytecodeOp::Signum,
+            "floor" => TfxBytecodeOp::Floor,
+            "ceil" => TfxBytecodeOp::Ceil,
+            "round" => TfxBytecodeOp::Round,
+            "frac" => TfxBytecodeOp::Frac,
+            "unk1b" => TfxBytecodeOp::Unk1b,
+            "unk1c" => TfxBytecodeOp::Unk1c,
+            "negate" => TfxBytecodeOp::Negate,
+            "vector_rotations_sin" => TfxBytecodeOp::VectorRotationsSin,
+            "vector_rotations_cos" => TfxBytecodeOp::VectorRotationsCos,
+            "vector_rotations_sin_cos" => TfxBytecodeOp::VectorRotationsSinCos,
+            "permute_extend_x" => TfxBytecodeOp::PermuteExtendX,
+            "permute" => TfxBytecodeOp::Permute {
+                fields: parse_permute(args)?,
+            },
+            "saturate" => TfxBytecodeOp::Saturate,
+            "unk24" => TfxBytecodeOp::Unk24,
+            "unk25" => TfxBytecodeOp::Unk25,
+            "unk26" => TfxBytecodeOp::Unk26,
+            "triangle" => TfxBytecodeOp::Triangle,
+            "jitter" => TfxBytecodeOp::Jitter,
+            "wander" => TfxBytecodeOp::Wander,
+            "rand" => TfxBytecodeOp::Rand,
+            "rand_smooth" => TfxBytecodeOp::RandSmooth,
+            "unk2c" => TfxBytecodeOp::Unk2c,
+            "unk2d" => TfxBytecodeOp::Unk2d,
+            "transform_vec4" => TfxBytecodeOp::TransformVec4,
+            "spline4_const" => TfxBytecodeOp::Spline4Const {
+                constant_start: parse_first(args)?,
+            },
+            "spline8_const" => TfxBytecodeOp::Spline8Const {
+                constant_start: parse_first(args)?,
+            },
+            "spline8_chain_const" => TfxBytecodeOp::Spline8ChainConst {
+                constant_start: parse_first(args)?,
+            },
+            "push_const_vec4" => TfxBytecodeOp::PushConstVec4 {
+                constant_index: parse_u8(args)?,
+            },
+            "lerp_constant" => TfxBytecodeOp::LerpConstant {
+                constant_start: parse_first(args)?,
+            },
+            "lerp_constant_saturated" => TfxBytecodeOp::LerpConstantSaturated {
+                constant_start: parse_first(args)?,
+            },
+            "unk37" => TfxBytecodeOp::Unk37 {
+                constant_start: parse_first(args)?,
+            },
+            "unk38" => {
+                let [unk1] = parse_unk_operands(args)?;
+                TfxBytecodeOp::Unk38 { unk1 }
+            }
+            "unk39" => {
+                let [unk1] = parse_unk_operands(args)?;
+                TfxBytecodeOp::Unk39 { unk1 }
+            }
+            "unk3a" => {
+                let [unk1] = parse_unk_operands(args)?;
+                TfxBytecodeOp::Unk3a { unk1 }
+            }
+            "unk_load_constant" => TfxBytecodeOp::UnkLoadConstant {
+                constant_index: parse_u8(
+                    args.strip_prefix("constants[")
+                        .and_then(|a| a.strip_suffix(']'))
+                        .unwrap_or(args),
+                )?,
+            },
+            "push_extern_input_float" => {
+                let (extern_, offset) = parse_extern(args, 4)?;
+                TfxBytecodeOp::PushExternInputFloat { extern_, offset }
+            }
+            "push_extern_input_vec4" => {
+                let (extern_, offset) = parse_extern(args, 16)?;
+                TfxBytecodeOp::PushExternInputVec4 { extern_, offset }
+            }
+            "push_extern_input_mat4" => {
+                let (extern_, offset) = parse_extern(args, 16)?;
+                TfxBytecodeOp::PushExternInputMat4 { extern_, offset }
+            }
+            "push_extern_input_tex" => {
+                let (extern_, offset) = parse_extern(args, 8)?;
+                TfxBytecodeOp::PushExternInputTexture { extern_, offset }
+            }
+            "push_extern_input_u32" => {
+                let (extern_, offset) = parse_extern(args, 4)?;
+                TfxBytecodeOp::PushExternInputU32 { extern_, offset }
+            }
+            "push_extern_input_uav" => {
+                let (extern_, offset) = parse_extern(args, 8)?;
+                TfxBytecodeOp::PushExternInputUav { extern_, offset }
+            }
+            "unk42" => TfxBytecodeOp::Unk42,
+            "push_from_output" => TfxBytecodeOp::PushFromOutput {
+                element: parse_u8(args)?,
+            },
+            "pop_output" => TfxBytecodeOp::PopOutput {
+                element: parse_u8(args)?,
+            },
+            "pop_output_mat4" => TfxBytecodeOp::PopOutputMat4 {
+                element: parse_u8(args)?,
+            },
+            "push_temp" => TfxBytecodeOp::PushTemp {
+                slot: parse_u8(args)?,
+            },
+            "pop_temp" => TfxBytecodeOp::PopTemp {
+                slot: parse_u8(args)?,
+            },
+            "set_shader_texture" => {
+                let (value, stage, slot) = parse_stage_slot(args)?;
+                TfxBytecodeOp::SetShaderTexture { value, stage, slot }
+            }
+            "set_shader_sampler" => {
+                let (value, stage, slot) = parse_stage_slot(args)?;
+                TfxBytecodeOp::SetShaderSampler { value, stage, slot }
+            }
+            "set_shader_uav" => {
+                let (value, stage, slot) = parse_stage_slot(args)?;
+                TfxBytecodeOp::SetShaderUav { value, stage, slot }
+            }
+            "unk49" => {
+                let [unk1] = parse_unk_operands(args)?;
+                TfxBytecodeOp::Unk49 { unk1 }
+            }
+            "unk4c" => {
+                let [unk1] = parse_unk_operands(args)?;
+                TfxBytecodeOp::Unk4c { unk1 }
+            }
+            "push_sampler" => TfxBytecodeOp::PushSampler {
+                index: parse_u8(args.strip_prefix("index=").unwrap_or(args))?,
+            },
+            "unk4e" => {
+                let [unk1, unk2, unk3, unk4] = parse_unk_operands(args)?;
+                TfxBytecodeOp::Unk4e {
+                    unk1,
+                    unk2,
+                    unk3,
+                    unk4,
+                }
+            }
+            "unk4f" => {
+                let [unk1] = parse_unk_operands(args)?;
+                TfxBytecodeOp::Unk4f { unk1 }
+            }
+            "unk50" => {
+                let [unk1] = parse_unk_operands(args)?;
+                TfxBytecodeOp::Unk50 { unk1 }
+            }
+            "unk51" => TfxBytecodeOp::Unk51,
+            "unk52" => {
+                let [unk1, unk2] = parse_unk_operands(args)?;
+                TfxBytecodeOp::Unk52 { unk1, unk2 }
+            }
+            "unk53" => {
+                let [unk1, unk2] = parse_unk_operands(args)?;
+                TfxBytecodeOp::Unk53 { unk1, unk2 }
+            }
+            "unk54" => {
+                let [unk1, unk2] = parse_unk_operands(args)?;
+                TfxBytecodeOp::Unk54 { unk1, unk2 }
+            }
+            "unk55" => TfxBytecodeOp::Unk55,
+            "unk56" => TfxBytecodeOp::Unk56,
+            "unk57" => TfxBytecodeOp::Unk57,
+            "unk58" => TfxBytecodeOp::Unk58,
+            u => anyhow::bail!("Unknown mnemonic '{u}'"),
+        })
+    }
+}
+
+fn parse_u32(s: &str) -> anyhow::Result<u32> {
+    let s = s.trim();
+    if let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        u32::from_str_radix(hex, 16).with_context(|| format!("Invalid hex number '{s}'"))
+    } else {
+        s.parse().with_context(|| format!("Invalid number '{s}'"))
+    }
+}
+
+fn parse_u8(s: &str) -> anyhow::Result<u8> {
+    let v = parse_u32(s)?;
+    u8::try_from(v).with_context(|| format!("Operand {v} does not fit in a byte"))
+}
+
+/// Parses the first index of `a, b` or `a..=b` operand lists
+fn parse_first(s: &str) -> anyhow::Result<u8> {
+    let first = s.split([',', '.']).next().unwrap_or_default();
+    parse_u8(first)
+}
+
+fn parse_component(s: &str) -> anyhow::Result<u8> {
+    match s.trim().trim_start_matches('.') {
+        "x" => Ok(0),
+        "y" => Ok(1),
+        "z" => Ok(2),
+        "w" => Ok(3),
+        u => anyhow::bail!("Invalid component '{u}'"),
+    }
+}
+
+/// Parses permute fields such as `.xyzw`
+fn parse_permute(s: &str) -> anyhow::Result<u8> {
+    let fields = s.trim().trim_start_matches('.');
+    anyhow::ensure!(
+        fields.len() == 4,
+        "Permute needs exactly 4 components, got '{fields}'"
+    );
+
+    let mut value = 0;
+    for (i, c) in fields.chars().enumerate() {
+        value |= parse_component(&c.to_string())? << (6 - i * 2);
+    }
+
+    Ok(value)
+}
+
+/// Parses the `unk1=A unk2=B` operands of unknown opcodes
+fn parse_unk_operands<const N: usize>(s: &str) -> anyhow::Result<[u8; N]> {
+    let mut values = [None; N];
+    for kv in s.split_whitespace() {
+        let (key, value) = kv
+            .split_once('=')
+            .with_context(|| format!("Unexpected operand '{kv}'"))?;
+        let index = key
+            .strip_prefix("unk")
+            .and_then(|i| i.parse::<usize>().ok())
+            .filter(|i| (1..=N).contains(i))
+            .with_context(|| format!("Unexpected operand '{key}'"))?;
+        values[index - 1] = Some(parse_u8(value)?);
+    }
+
+    let mut result = [0; N];
+    for (i, v) in values.into_iter().enumerate() {
+        result[i] = v.with_context(|| format!("Missing operand unk{}", i + 1))?;
+    }
+
+    Ok(result)
+}
+
+/// Parses `Extern+0xOFFSET`, where the offset is in bytes, or a named field such as `View.world_to_projective`
+fn parse_extern(s: &str, unit_size: usize) -> anyhow::Result<(TfxExtern, u8)> {
+    let split = s
+        .find(['+', '.'])
+        .context("Expected an extern operand in the form of Extern+0xOFFSET or Extern.field")?;
+    let (name, operand) = s.split_at(split);
+
+    let extern_ = (0..=u8::MAX)
+        .filter_map(TfxExtern::from_u8)
+        .find(|e| format!("{e:?}") == name.trim())
+        .with_context(|| format!("Unknown extern '{}'", name.trim()))?;
+
+    let offset_bytes = match operand.split_at(1) {
+        ("+", offset) => parse_u32(offset)? as usize,
+        (_, path) => extern_
+            .field_offset(path.trim())
+            .with_context(|| format!("Unknown field '{}' in extern {extern_:?}", path.trim()))?,
+    };
+    anyhow::ensure!(
+        offset_bytes % unit_size == 0,
+        "Extern offset 0x{offset_bytes:X} is not aligned to {unit_size} bytes"
+    );
+
+    let offset = u8::try_from(offset_bytes / unit_size)
+        .with_context(|| format!("Extern offset 0x{offset_bytes:X} is out of range"))?;
+
+    Ok((extern_, offset))
+}
+
+/// Parses `stage=Pixel slot=3` into the encoded operand value, stage and slot
+fn parse_stage_slot(s: &str) -> anyhow::Result<(u8, TfxShaderStage, u8)> {
+    let mut stage = None;
+    let mut slot = None;
+    for kv in s.split_whitespace() {
+        match kv.split_once('=') {
+            Some(("stage", v)) => {
+                stage = Some(
+                    (0..8)
+                        .filter_map(TfxShaderStage::from_u8)
+                        .find(|s| format!("{s:?}") == v)
+                        .with_context(|| format!("Unknown shader stage '{v}'"))?,
+                )
+            }
+            Some(("slot", v)) => slot = Some(parse_u8(v)?),
+            _ => anyhow::bail!("Unexpected operand '{kv}'"),
+        }
+    }
+
+    let stage = stage.context("Missing stage operand")?;
+    let slot = slot.context("Missing slot operand")?;
+    anyhow::ensure!(slot <= 0x1f, "Slot {slot} is out of range");
+
+    Ok(((stage as u8) << 5 | slot, stage, slot))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeSet, io::Cursor};
+
+    use binrw::BinReaderExt;
+    use glam::Vec4;
+
+    use super::*;
+
+    /// Operand bytes following the opcode. Between them these hit known and unknown extern fields and every shader
+    /// stage
+    const OPERANDS: [[u8; 4]; 4] = [
+        [0x25, 0x07, 0xab, 0xcd],
+        [0x01, 0x02, 0x03, 0x04],
+        [0x02, 0x0c, 0xff, 0x00],
+        [0xdf, 0x03, 0x61, 0x80],
+    ];
+
+    #[test]
+    fn round_trip_every_opcode() {
+        let constants = [Vec4::ONE; 4];
+        let mut seen = BTreeSet::new();
+
+        for endian in [Endian::Little, Endian::Big] {
+            for opcode in 0..=u8::MAX {
+                for operands in OPERANDS {
+                    let mut data = vec![opcode];
+                    data.extend(operands);
+
+                    let mut cur = Cursor::new(&data);
+                    let Ok(op) = cur.read_type::<TfxBytecodeOp>(endian) else {
+                        continue;
+                    };
+                    let encoded = &data[..cur.position() as usize];
+                    seen.insert(opcode);
+
+                    assert_eq!(TfxBytecodeOp::write_all(&[op], endian), encoded);
+                    let op = TfxBytecodeOp::parse_all(encoded, endian).unwrap().remove(0);
+
+                    for disassembly in [op.disassemble(None), op.disassemble(Some(&constants))] {
+                        let assembled = TfxBytecodeAssembler::assemble_bytes(&disassembly, endian)
+                            .unwrap_or_else(|e| panic!("'{disassembly}': {e:?}"));
+                        assert_eq!(assembled, encoded, "'{disassembly}'");
+                    }
+                }
+            }
+        }
+
+        let expected: BTreeSet<u8> = (0x01..=0x31).chain(0x34..=0x58).collect();
+        assert_eq!(seen, expected);
+    }
+}
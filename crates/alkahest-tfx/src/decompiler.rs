@@ -0,0 +1,878 @@
+use std::fmt::{Display, Formatter};
+
+use anyhow::Context;
+use glam::Vec4;
+use itertools::Itertools;
+use tinyvec::ArrayVec;
+
+use crate::{
+    externs::{TfxExtern, TfxExternType, TfxShaderStage},
+    interpreter::tfx_converted,
+    opcodes::{component_name, TfxBytecodeOp},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TfxBinaryOp {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    LessThan,
+    Equal,
+}
+
+impl TfxBinaryOp {
+    fn symbol(&self) -> &'static str {
+        match self {
+            TfxBinaryOp::Add => "+",
+            TfxBinaryOp::Subtract => "-",
+            TfxBinaryOp::Multiply => "*",
+            TfxBinaryOp::Divide => "/",
+            TfxBinaryOp::LessThan => "<",
+            TfxBinaryOp::Equal => "==",
+        }
+    }
+
+    fn precedence(&self) -> u8 {
+        match self {
+            TfxBinaryOp::LessThan | TfxBinaryOp::Equal => 1,
+            TfxBinaryOp::Add | TfxBinaryOp::Subtract => 2,
+            TfxBinaryOp::Multiply | TfxBinaryOp::Divide => 3,
+        }
+    }
+
+    fn apply(&self, a: Vec4, b: Vec4) -> Vec4 {
+        match self {
+            TfxBinaryOp::Add => a + b,
+            TfxBinaryOp::Subtract => a - b,
+            TfxBinaryOp::Multiply => a * b,
+            TfxBinaryOp::Divide => a / b,
+            TfxBinaryOp::LessThan => Vec4::select(a.cmplt(b), Vec4::ONE, Vec4::ZERO),
+            TfxBinaryOp::Equal => Vec4::select(a.cmpeq(b), Vec4::ONE, Vec4::ZERO),
+        }
+    }
+}
+
+/// Functions that operate on each component separately
+const ELEMENTWISE_FUNCTIONS: &[&str] = &[
+    "abs",
+    "sign",
+    "floor",
+    "ceil",
+    "round",
+    "frac",
+    "saturate",
+    "min",
+    "max",
+    "lerp",
+    "clamp",
+    "bytecode_op_triangle",
+    "bytecode_op_jitter",
+    "bytecode_op_wander",
+];
+
+/// Functions that return the same value in every component
+const SCALAR_FUNCTIONS: &[&str] = &[
+    "dot",
+    "bytecode_op_rand",
+    "bytecode_op_rand_smooth",
+    "spline4_const",
+    "spline8_const",
+    "spline8_chain_const",
+];
+
+/// Expression tree node produced by the decompiler.
+/// Nodes should be created through the constructor functions, which fold constants and simplify the tree.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TfxExpr {
+    Constant(Vec4),
+    /// Named input value, scalar inputs have the same value in every component
+    Input {
+        name: String,
+        scalar: bool,
+    },
+    /// Column of a float4x4 input, as pushed by push_extern_input_mat4
+    MatrixColumn {
+        matrix: String,
+        column: u8,
+    },
+    Matrix(Box<[TfxExpr; 4]>),
+    Negate(Box<TfxExpr>),
+    Binary {
+        op: TfxBinaryOp,
+        lhs: Box<TfxExpr>,
+        rhs: Box<TfxExpr>,
+    },
+    Call {
+        function: &'static str,
+        args: Vec<TfxExpr>,
+    },
+    Swizzle {
+        value: Box<TfxExpr>,
+        components: [u8; 4],
+    },
+    /// float4 made from the leading components of each part (eg. `float4(a.x, b.xyz)`)
+    Merge(Vec<(TfxExpr, u8)>),
+}
+
+impl Default for TfxExpr {
+    fn default() -> Self {
+        TfxExpr::Constant(Vec4::ZERO)
+    }
+}
+
+impl TfxExpr {
+    pub fn input(name: impl Into<String>) -> Self {
+        TfxExpr::Input {
+            name: name.into(),
+            scalar: false,
+        }
+    }
+
+    pub fn scalar_input(name: impl Into<String>) -> Self {
+        TfxExpr::Input {
+            name: name.into(),
+            scalar: true,
+        }
+    }
+
+    pub fn as_constant(&self) -> Option<Vec4> {
+        match self {
+            TfxExpr::Constant(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    fn is_splat_constant(&self, value: f32) -> bool {
+        self.as_constant() == Some(Vec4::splat(value))
+    }
+
+    /// Returns true if every component of the expression has the same value
+    pub fn is_scalar(&self) -> bool {
+        match self {
+            TfxExpr::Constant(v) => *v == Vec4::splat(v.x),
+            TfxExpr::Input { scalar, .. } => *scalar,
+            TfxExpr::MatrixColumn { .. } | TfxExpr::Matrix(_) | TfxExpr::Merge(_) => false,
+            TfxExpr::Negate(v) => v.is_scalar(),
+            TfxExpr::Binary { lhs, rhs, .. } => lhs.is_scalar() && rhs.is_scalar(),
+            TfxExpr::Call { function, args } => {
+                SCALAR_FUNCTIONS.contains(function)
+                    || (ELEMENTWISE_FUNCTIONS.contains(function)
+                        && args.iter().all(TfxExpr::is_scalar))
+            }
+            TfxExpr::Swizzle { value, components } => {
+                value.is_scalar() || components.iter().all_equal()
+            }
+        }
+    }
+
+    /// Expressions that are cheap enough to repeat instead of storing them in a local
+    fn is_trivial(&self) -> bool {
+        match self {
+            TfxExpr::Constant(_) | TfxExpr::Input { .. } | TfxExpr::MatrixColumn { .. } => true,
+            TfxExpr::Swizzle { value, .. } => value.is_trivial(),
+            _ => false,
+        }
+    }
+
+    pub fn negate(value: TfxExpr) -> Self {
+        match value {
+            TfxExpr::Constant(v) => TfxExpr::Constant(-v),
+            TfxExpr::Negate(v) => *v,
+            v => TfxExpr::Negate(Box::new(v)),
+        }
+    }
+
+    pub fn binary(op: TfxBinaryOp, lhs: TfxExpr, rhs: TfxExpr) -> Self {
+        if let (Some(a), Some(b)) = (lhs.as_constant(), rhs.as_constant()) {
+            return TfxExpr::Constant(op.apply(a, b));
+        }
+
+        match op {
+            TfxBinaryOp::Add if lhs.is_splat_constant(0.0) => return rhs,
+            TfxBinaryOp::Add | TfxBinaryOp::Subtract if rhs.is_splat_constant(0.0) => return lhs,
+            TfxBinaryOp::Multiply if lhs.is_splat_constant(1.0) => return rhs,
+            TfxBinaryOp::Multiply | TfxBinaryOp::Divide if rhs.is_splat_constant(1.0) => {
+                return lhs
+            }
+            TfxBinaryOp::Add => {
+                // a + -b => a - b
+                if let TfxExpr::Negate(rhs) = rhs {
+                    return TfxExpr::binary(TfxBinaryOp::Subtract, lhs, *rhs);
+                }
+            }
+            _ => {}
+        }
+
+        TfxExpr::Binary {
+            op,
+            lhs: Box::new(lhs),
+            rhs: Box::new(rhs),
+        }
+    }
+
+    pub fn call(function: &'static str, args: Vec<TfxExpr>) -> Self {
+        let constants: Option<Vec<Vec4>> = args.iter().map(TfxExpr::as_constant).collect();
+        if let Some(v) = constants.and_then(|c| Self::fold_call(function, &c)) {
+            return TfxExpr::Constant(v);
+        }
+
+        TfxExpr::Call { function, args }
+    }
+
+    fn fold_call(function: &str, args: &[Vec4]) -> Option<Vec4> {
+        Some(match (function, args) {
+            ("abs", [v]) => v.abs(),
+            ("sign", [v]) => v.signum(),
+            ("floor", [v]) => v.floor(),
+            ("ceil", [v]) => v.ceil(),
+            ("round", [v]) => v.round(),
+            ("frac", [v]) => v.fract(),
+            ("saturate", [v]) => v.clamp(Vec4::ZERO, Vec4::ONE),
+            ("min", [a, b]) => a.min(*b),
+            ("max", [a, b]) => a.max(*b),
+            ("dot", [a, b]) => Vec4::splat(a.dot(*b)),
+            ("lerp", [a, b, t]) => *a + *t * (*b - *a),
+            // Not using Vec4::clamp, as it asserts that min <= max
+            ("clamp", [v, min, max]) => v.max(*min).min(*max),
+            ("unk0f", [v, c]) => {
+                (Vec4::splat(c.x) * *v + Vec4::splat(c.y)) * (*v * *v)
+                    + (Vec4::splat(c.z) * *v + Vec4::splat(c.w))
+            }
+            ("spline4_const", [t, c @ ..]) if c.len() == 5 => {
+                tfx_converted::bytecode_op_spline4_const(*t, c)
+            }
+            ("spline8_const", [t, c @ ..]) if c.len() == 10 => {
+                tfx_converted::bytecode_op_spline8_const(*t, c)
+            }
+            ("bytecode_op_triangle", [v]) => tfx_converted::bytecode_op_triangle(*v),
+            ("_trig_helper_vector_sin_rotations_estimate", [v]) => {
+                tfx_converted::_trig_helper_vector_sin_rotations_estimate(*v)
+            }
+            ("_trig_helper_vector_cos_rotations_estimate", [v]) => {
+                tfx_converted::_trig_helper_vector_cos_rotations_estimate(*v)
+            }
+            ("_trig_helper_vector_sin_cos_rotations_estimate", [v]) => {
+                tfx_converted::_trig_helper_vector_sin_cos_rotations_estimate(*v)
+            }
+            _ => return None,
+        })
+    }
+
+    /// Creates a swizzle, collapsing permute chains and swizzles that don't change the value
+    pub fn swizzle(value: TfxExpr, components: [u8; 4]) -> Self {
+        let components = components.map(|c| c & 0b11);
+
+        if value.is_scalar() || components == [0, 1, 2, 3] {
+            return value;
+        }
+
+        match value {
+            TfxExpr::Constant(v) => {
+                TfxExpr::Constant(Vec4::from_array(components.map(|c| v[c as usize])))
+            }
+            // a.yzwx.xxyy => a.yyzz
+            TfxExpr::Swizzle {
+                value: inner,
+                components: inner_components,
+            } => TfxExpr::swizzle(*inner, components.map(|c| inner_components[c as usize])),
+            // float4(a.x, b.xyz).yzw => b.xyz
+            TfxExpr::Merge(parts) => {
+                let sources = components.map(|c| Self::merge_source(&parts, c));
+                if sources.iter().map(|(part, _)| part).all_equal() {
+                    let part = parts[sources[0].0].0.clone();
+                    TfxExpr::swizzle(part, sources.map(|(_, c)| c))
+                } else {
+                    TfxExpr::Swizzle {
+                        value: Box::new(TfxExpr::Merge(parts)),
+                        components,
+                    }
+                }
+            }
+            value => TfxExpr::Swizzle {
+                value: Box::new(value),
+                components,
+            },
+        }
+    }
+
+    /// Returns the part index and part component that make up the given merge component
+    fn merge_source(parts: &[(TfxExpr, u8)], component: u8) -> (usize, u8) {
+        let mut start = 0;
+        for (i, (_, count)) in parts.iter().enumerate() {
+            if component < start + count {
+                return (i, component - start);
+            }
+            start += count;
+        }
+
+        (parts.len() - 1, component - start)
+    }
+
+    pub fn merge(parts: Vec<(TfxExpr, u8)>) -> Self {
+        let components: Vec<Option<f32>> = parts
+            .iter()
+            .flat_map(|(part, count)| {
+                (0..*count).map(|c| part.as_constant().map(|v| v[c as usize]))
+            })
+            .collect();
+
+        if let Some(components) = components.into_iter().collect::<Option<Vec<f32>>>() {
+            return TfxExpr::Constant(Vec4::from_slice(&components));
+        }
+
+        TfxExpr::Merge(parts)
+    }
+
+    /// Binding strength of the expression when printed, higher binds tighter
+    fn precedence(&self) -> u8 {
+        match self {
+            TfxExpr::Binary { op, .. } => op.precedence(),
+            TfxExpr::Negate(_) => 4,
+            TfxExpr::Constant(v) if v.is_negative_bitmask() != 0 && self.is_scalar() => 4,
+            _ => 5,
+        }
+    }
+
+    fn fmt_prec(&self, f: &mut Formatter<'_>, min_precedence: u8) -> std::fmt::Result {
+        if self.precedence() < min_precedence {
+            write!(f, "(")?;
+            self.fmt_inner(f)?;
+            write!(f, ")")
+        } else {
+            self.fmt_inner(f)
+        }
+    }
+
+    fn fmt_inner(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TfxExpr::Constant(v) => {
+                if self.is_scalar() {
+                    write!(f, "{:?}", v.x)
+                } else {
+                    write!(f, "float4({:?}, {:?}, {:?}, {:?})", v.x, v.y, v.z, v.w)
+                }
+            }
+            TfxExpr::Input { name, .. } => write!(f, "{name}"),
+            TfxExpr::MatrixColumn { matrix, column } => write!(f, "{matrix}[{column}]"),
+            TfxExpr::Matrix(columns) => {
+                // Print the matrix by name if it's passed through as a whole
+                let name = match &columns[0] {
+                    TfxExpr::MatrixColumn { matrix, .. } => Some(matrix),
+                    _ => None,
+                };
+                let name = name.filter(|name| {
+                    columns.iter().enumerate().all(|(i, c)| {
+                        matches!(c, TfxExpr::MatrixColumn { matrix, column } if matrix == *name && *column as usize == i)
+                    })
+                });
+
+                match name {
+                    Some(name) => write!(f, "{name}"),
+                    None => write!(f, "float4x4({})", columns.iter().join(", ")),
+                }
+            }
+            TfxExpr::Negate(v) => {
+                write!(f, "-")?;
+                v.fmt_prec(f, 5)
+            }
+            TfxExpr::Binary { op, lhs, rhs } => {
+                lhs.fmt_prec(f, op.precedence())?;
+                write!(f, " {} ", op.symbol())?;
+                rhs.fmt_prec(f, op.precedence() + 1)
+            }
+            TfxExpr::Call { function, args } => {
+                write!(f, "{function}({})", args.iter().join(", "))
+            }
+            TfxExpr::Swizzle { value, components } => {
+                value.fmt_prec(f, 5)?;
+                write!(f, ".")?;
+                // Splat swizzles are implicit in HLSL
+                let count = if components.iter().all_equal() { 1 } else { 4 };
+                for c in &components[..count] {
+                    write!(f, "{}", component_name(*c))?;
+                }
+                Ok(())
+            }
+            TfxExpr::Merge(parts) => {
+                write!(f, "float4(")?;
+                for (i, (part, count)) in parts.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, ", ")?;
+                    }
+
+                    if part.is_scalar() {
+                        write!(f, "{part}")?;
+                        continue;
+                    }
+
+                    let (value, components) = match part {
+                        TfxExpr::Swizzle { value, components } => (value.as_ref(), *components),
+                        v => (v, [0, 1, 2, 3]),
+                    };
+
+                    value.fmt_prec(f, 5)?;
+                    write!(f, ".")?;
+                    for c in &components[..*count as usize] {
+                        write!(f, "{}", component_name(*c))?;
+                    }
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+impl Display for TfxExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.fmt_prec(f, 0)
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct DecompilationResult {
+    pub locals: Vec<(String, TfxExpr)>,
+    pub textures: Vec<(usize, TfxShaderStage, TfxExpr)>,
+    pub samplers: Vec<(usize, TfxShaderStage, TfxExpr)>,
+    pub uavs: Vec<(usize, TfxShaderStage, TfxExpr)>,
+    pub cb_expressions: Vec<(usize, TfxExpr)>,
+}
+
+impl DecompilationResult {
+    pub fn pretty_print(&self) -> String {
+        let mut r = String::new();
+
+        if !self.locals.is_empty() {
+            r.push_str("// Locals\n");
+            for (name, expr) in &self.locals {
+                let ty = if expr.is_scalar() { "float" } else { "float4" };
+                r.push_str(&format!("{ty} {name} = {expr};\n"));
+            }
+        }
+
+        if !self.samplers.is_empty() {
+            r.push_str("\n// Samplers\n");
+            for (slot, _stage, expr) in &self.samplers {
+                r.push_str(&format!("SamplerState s{slot} = {expr};\n"));
+            }
+        }
+
+        if !self.textures.is_empty() {
+            r.push_str("\n// Textures\n");
+            for (slot, _stage, expr) in &self.textures {
+                r.push_str(&format!("Texture<float4> t{slot} = {expr};\n"));
+            }
+        }
+
+        if !self.uavs.is_empty() {
+            r.push_str("\n// UAVs\n");
+            for (slot, _stage, expr) in &self.uavs {
+                r.push_str(&format!("RWTexture<float4> t{slot} = {expr};\n"));
+            }
+        }
+
+        if !self.cb_expressions.is_empty() {
+            r.push_str("\n// Constant buffer\n");
+            for (slot, expr) in &self.cb_expressions {
+                let slot_fixed = if matches!(expr, TfxExpr::Matrix(_)) {
+                    format!("{slot}..={}", slot + 3)
+                } else {
+                    format!("{slot}")
+                };
+                r.push_str(&format!("cb0[{slot_fixed}] = {expr};\n"));
+            }
+        }
+
+        r
+    }
+}
+
+/// Returns a readable name for an extern field, eg. `View.world_to_projective`
+fn extern_field_name(extern_: TfxExtern, offset_bytes: usize, ty: TfxExternType) -> String {
+    extern_
+        .field_name(offset_bytes, ty)
+        .unwrap_or_else(|| format!("{extern_:?}.unk{offset_bytes:x}"))
+}
+
+pub struct TfxBytecodeDecompiler;
+
+impl TfxBytecodeDecompiler {
+    pub fn decompile(
+        opcodes: impl IntoIterator<Item = TfxBytecodeOp>,
+        constants: &[Vec4],
+    ) -> anyhow::Result<DecompilationResult> {
+        let mut r = DecompilationResult::default();
+
+        let mut stack: ArrayVec<[TfxExpr; 64]> = Default::default();
+        let mut temp: ArrayVec<[TfxExpr; 16]> = Default::default();
+        for i in 0..temp.capacity() {
+            temp.push(TfxExpr::input(format!("TEMP_UNDEFINED_{i}")));
+        }
+        let mut temp_writes = [0usize; 16];
+
+        // Values written to the output buffer by this program, used to resolve reads from the output
+        let mut outputs: Vec<Option<TfxExpr>> = vec![None; 256];
+
+        // Pops values in the same order as the interpreter (bottom to top)
+        macro_rules! stack_pop {
+            () => {{
+                anyhow::ensure!(!stack.is_empty(), "Stack underflow");
+                stack.pop().unwrap()
+            }};
+            ($pops:literal) => {{
+                anyhow::ensure!(stack.len() >= $pops, "Stack underflow");
+                let start = stack.len() - $pops;
+                let mut values = stack.drain(start..);
+                let v: [TfxExpr; $pops] = std::array::from_fn(|_| values.next().unwrap());
+                v
+            }};
+        }
+
+        macro_rules! stack_push {
+            ($value:expr) => {{
+                anyhow::ensure!(stack.len() < stack.capacity(), "Stack overflow");
+                stack.push($value);
+            }};
+        }
+
+        macro_rules! stack_top {
+            () => {{
+                anyhow::ensure!(!stack.is_empty(), "Stack underflow");
+                stack.last_mut().unwrap()
+            }};
+        }
+
+        macro_rules! constant_range {
+            ($start:expr, $count:expr) => {{
+                let start = $start as usize;
+                constants
+                    .get(start..start + $count)
+                    .context("Constant range is out of bounds")?
+                    .iter()
+                    .map(|c| TfxExpr::Constant(*c))
+            }};
+        }
+
+        macro_rules! binary {
+            ($op:ident) => {{
+                let [t1, t0] = stack_pop!(2);
+                stack_push!(TfxExpr::binary(TfxBinaryOp::$op, t1, t0));
+            }};
+        }
+
+        macro_rules! unary_call {
+            ($function:expr) => {{
+                let v = stack_pop!();
+                stack_push!(TfxExpr::call($function, vec![v]));
+            }};
+        }
+
+        macro_rules! constant_call {
+            ($function:expr, $start:expr, $count:expr) => {{
+                let v = stack_pop!();
+                let args = std::iter::once(v)
+                    .chain(constant_range!($start, $count))
+                    .collect();
+                stack_push!(TfxExpr::call($function, args));
+            }};
+        }
+
+        macro_rules! constant_chain_call {
+            ($function:expr, $start:expr) => {{
+                let [t, previous] = stack_pop!(2);
+                let args = [t.clone(), previous]
+                    .into_iter()
+                    .chain(constant_range!($start, 10))
+                    .collect();
+                stack_push!(t);
+                stack_push!(TfxExpr::call($function, args));
+            }};
+        }
+
+        for op in opcodes.into_iter() {
+            match op {
+                TfxBytecodeOp::Add | TfxBytecodeOp::Add2 => binary!(Add),
+                TfxBytecodeOp::Subtract => binary!(Subtract),
+                TfxBytecodeOp::Multiply | TfxBytecodeOp::Multiply2 => binary!(Multiply),
+                TfxBytecodeOp::Divide => binary!(Divide),
+                TfxBytecodeOp::IsZero => {
+                    let v = stack_pop!();
+                    stack_push!(TfxExpr::binary(
+                        TfxBinaryOp::Equal,
+                        v,
+                        TfxExpr::Constant(Vec4::ZERO)
+                    ));
+                }
+                TfxBytecodeOp::Min => {
+                    let [t1, t0] = stack_pop!(2);
+                    stack_push!(TfxExpr::call("min", vec![t1, t0]));
+                }
+                TfxBytecodeOp::Max => {
+                    let [t1, t0] = stack_pop!(2);
+                    stack_push!(TfxExpr::call("max", vec![t1, t0]));
+                }
+                TfxBytecodeOp::LessThan => {
+                    let [t1, t0] = stack_pop!(2);
+                    stack_push!(TfxExpr::binary(TfxBinaryOp::LessThan, t0, t1));
+                }
+                TfxBytecodeOp::Dot => {
+                    let [t1, t0] = stack_pop!(2);
+                    stack_push!(TfxExpr::call("dot", vec![t0, t1]));
+                }
+                TfxBytecodeOp::Merge1_3 => {
+                    let [t1, t0] = stack_pop!(2);
+                    stack_push!(TfxExpr::merge(vec![(t1, 1), (t0, 3)]));
+                }
+                TfxBytecodeOp::Merge2_2 => {
+                    let [t1, t0] = stack_pop!(2);
+                    stack_push!(TfxExpr::merge(vec![(t1, 2), (t0, 2)]));
+                }
+                TfxBytecodeOp::Unk0e => {
+                    let [t1, t0] = stack_pop!(2);
+                    stack_push!(TfxExpr::merge(vec![(t1, 3), (t0, 1)]));
+                }
+                TfxBytecodeOp::Unk0f => {
+                    let [v, coefficients] = stack_pop!(2);
+                    stack_push!(TfxExpr::call("unk0f", vec![v, coefficients]));
+                }
+                TfxBytecodeOp::Lerp => {
+                    let [b, a, v] = stack_pop!(3);
+                    stack_push!(TfxExpr::call("lerp", vec![a, b, v]));
+                }
+                TfxBytecodeOp::LerpSaturated => {
+                    let [b, a, v] = stack_pop!(3);
+                    let lerp = TfxExpr::call("lerp", vec![a, b, v]);
+                    stack_push!(TfxExpr::call("saturate", vec![lerp]));
+                }
+                TfxBytecodeOp::MultiplyAdd => {
+                    let [t2, t1, t0] = stack_pop!(3);
+                    let mul = TfxExpr::binary(TfxBinaryOp::Multiply, t1, t2);
+                    stack_push!(TfxExpr::binary(TfxBinaryOp::Add, t0, mul));
+                }
+                TfxBytecodeOp::Clamp => {
+                    let [value, min, max] = stack_pop!(3);
+                    stack_push!(TfxExpr::call("clamp", vec![value, min, max]));
+                }
+                TfxBytecodeOp::Abs => unary_call!("abs"),
+                TfxBytecodeOp::Signum => unary_call!("sign"),
+                TfxBytecodeOp::Floor => unary_call!("floor"),
+                TfxBytecodeOp::Ceil => unary_call!("ceil"),
+                TfxBytecodeOp::Round => unary_call!("round"),
+                TfxBytecodeOp::Frac => unary_call!("frac"),
+                TfxBytecodeOp::Negate => {
+                    let v = stack_pop!();
+                    stack_push!(TfxExpr::negate(v));
+                }
+                TfxBytecodeOp::VectorRotationsSin => {
+                    unary_call!("_trig_helper_vector_sin_rotations_estimate")
+                }
+                TfxBytecodeOp::VectorRotationsCos => {
+                    unary_call!("_trig_helper_vector_cos_rotations_estimate")
+                }
+                TfxBytecodeOp::VectorRotationsSinCos => {
+                    unary_call!("_trig_helper_vector_sin_cos_rotations_estimate")
+                }
+                TfxBytecodeOp::PermuteExtendX => {
+                    let v = stack_pop!();
+                    stack_push!(TfxExpr::swizzle(v, [0, 0, 0, 0]));
+                }
+                TfxBytecodeOp::Permute { fields } => {
+                    let v = stack_pop!();
+                    stack_push!(TfxExpr::swizzle(
+                        v,
+                        [fields >> 6, fields >> 4, fields >> 2, fields]
+                    ));
+                }
+                TfxBytecodeOp::Saturate => unary_call!("saturate"),
+                TfxBytecodeOp::Triangle => unary_call!("bytecode_op_triangle"),
+                TfxBytecodeOp::Jitter => unary_call!("bytecode_op_jitter"),
+                TfxBytecodeOp::Wander => unary_call!("bytecode_op_wander"),
+                TfxBytecodeOp::Rand => unary_call!("bytecode_op_rand"),
+                TfxBytecodeOp::RandSmooth => unary_call!("bytecode_op_rand_smooth"),
+                TfxBytecodeOp::TransformVec4 => {
+                    let [x_axis, y_axis, z_axis, w_axis, value] = stack_pop!(5);
+                    let matrix = TfxExpr::Matrix(Box::new([x_axis, y_axis, z_axis, w_axis]));
+                    stack_push!(TfxExpr::call("mul", vec![value, matrix]));
+                }
+                TfxBytecodeOp::Spline4Const { constant_start } => {
+                    constant_call!("spline4_const", constant_start, 5)
+                }
+                TfxBytecodeOp::Spline8Const { constant_start } => {
+                    constant_call!("spline8_const", constant_start, 10)
+                }
+                TfxBytecodeOp::Spline8ChainConst { constant_start } => {
+                    constant_chain_call!("spline8_chain_const", constant_start)
+                }
+                TfxBytecodeOp::PushConstVec4 { constant_index } => {
+                    let v = constant_range!(constant_index, 1).next().unwrap();
+                    stack_push!(v);
+                }
+                TfxBytecodeOp::LerpConstant { constant_start } => {
+                    let v = stack_pop!();
+                    let args = constant_range!(constant_start, 2)
+                        .chain(std::iter::once(v))
+                        .collect();
+                    stack_push!(TfxExpr::call("lerp", args));
+                }
+                TfxBytecodeOp::LerpConstantSaturated { constant_start } => {
+                    let v = stack_pop!();
+                    let args = constant_range!(constant_start, 2)
+                        .chain(std::iter::once(v))
+                        .collect();
+                    let lerp = TfxExpr::call("lerp", args);
+                    stack_push!(TfxExpr::call("saturate", vec![lerp]));
+                }
+                TfxBytecodeOp::Unk37 { constant_start } => {
+                    constant_call!("unk37", constant_start, 5)
+                }
+                TfxBytecodeOp::UnkLoadConstant { constant_index } => {
+                    let v = constant_range!(constant_index, 1).next().unwrap();
+                    *stack_top!() = v;
+                }
+                TfxBytecodeOp::PushExternInputFloat { extern_, offset } => {
+                    let name =
+                        extern_field_name(extern_, offset as usize * 4, TfxExternType::Float);
+                    stack_push!(TfxExpr::scalar_input(name));
+                }
+                TfxBytecodeOp::PushExternInputVec4 { extern_, offset } => {
+                    let name =
+                        extern_field_name(extern_, offset as usize * 16, TfxExternType::Vec4);
+                    stack_push!(TfxExpr::input(name));
+                }
+                TfxBytecodeOp::PushExternInputMat4 { extern_, offset } => {
+                    let matrix =
+                        extern_field_name(extern_, offset as usize * 16, TfxExternType::Mat4);
+                    for column in 0..4 {
+                        stack_push!(TfxExpr::MatrixColumn {
+                            matrix: matrix.clone(),
+                            column
+                        });
+                    }
+                }
+                TfxBytecodeOp::PushExternInputTexture { extern_, offset }
+                | TfxBytecodeOp::PushExternInputUav { extern_, offset } => {
+                    let name =
+                        extern_field_name(extern_, offset as usize * 8, TfxExternType::Texture);
+                    stack_push!(TfxExpr::input(name));
+                }
+                TfxBytecodeOp::PushExternInputU32 { extern_, offset } => {
+                    let name = extern_field_name(extern_, offset as usize * 4, TfxExternType::U32);
+                    stack_push!(TfxExpr::input(name));
+                }
+                TfxBytecodeOp::PushFromOutput { element } => {
+                    let v = outputs[element as usize]
+                        .clone()
+                        .unwrap_or_else(|| TfxExpr::input(format!("cb0[{element}]")));
+                    stack_push!(v);
+                }
+                TfxBytecodeOp::PopOutput { element } => {
+                    let v = stack_pop!();
+                    outputs[element as usize] = Some(v.clone());
+                    r.cb_expressions.push((element as usize, v));
+                }
+                TfxBytecodeOp::PopOutputMat4 { element } => {
+                    let columns = stack_pop!(4);
+                    for (i, c) in columns.iter().enumerate() {
+                        if let Some(o) = outputs.get_mut(element as usize + i) {
+                            *o = Some(c.clone());
+                        }
+                    }
+                    r.cb_expressions
+                        .push((element as usize, TfxExpr::Matrix(Box::new(columns))));
+                }
+                TfxBytecodeOp::PushTemp { slot } => {
+                    let slotu = slot as usize;
+                    anyhow::ensure!(slotu < temp.len(), "Temp slot is out of range");
+
+                    stack_push!(temp[slotu].clone());
+                }
+                TfxBytecodeOp::PopTemp { slot } => {
+                    let slotu = slot as usize;
+                    anyhow::ensure!(slotu < temp.len(), "Temp slot is out of range");
+
+                    let v = stack_pop!();
+                    temp[slotu] = if v.is_trivial() {
+                        v
+                    } else {
+                        let name = match temp_writes[slotu] {
+                            0 => format!("temp{slotu}"),
+                            n => format!("temp{slotu}_{n}"),
+                        };
+                        temp_writes[slotu] += 1;
+
+                        let local = TfxExpr::Input {
+                            name: name.clone(),
+                            scalar: v.is_scalar(),
+                        };
+                        r.locals.push((name, v));
+                        local
+                    };
+                }
+                TfxBytecodeOp::SetShaderTexture { stage, slot, .. } => {
+                    let v = stack_pop!();
+                    r.textures.push((slot as usize, stage, v));
+                }
+                TfxBytecodeOp::SetShaderSampler { stage, slot, .. } => {
+                    let v = stack_pop!();
+                    r.samplers.push((slot as usize, stage, v));
+                }
+                TfxBytecodeOp::SetShaderUav { stage, slot, .. } => {
+                    let v = stack_pop!();
+                    r.uavs.push((slot as usize, stage, v));
+                }
+                TfxBytecodeOp::Unk4c { unk1 } => {
+                    stack_push!(TfxExpr::input(format!("unk4c({unk1})")));
+                }
+                TfxBytecodeOp::PushSampler { index } => {
+                    stack_push!(TfxExpr::input(format!("get_sampler({index})")));
+                }
+                TfxBytecodeOp::Unk4e {
+                    unk1,
+                    unk2,
+                    unk3,
+                    unk4,
+                } => {
+                    stack_push!(TfxExpr::input(format!(
+                        "unk4e({unk1}, {unk2}, {unk3}, {unk4})"
+                    )));
+                }
+                TfxBytecodeOp::Unk4f { unk1 } => {
+                    stack_push!(TfxExpr::input(format!("unk4f({unk1})")));
+                }
+                TfxBytecodeOp::Unk50 { unk1 } => {
+                    stack_push!(TfxExpr::input(format!("unk50({unk1})")));
+                }
+                TfxBytecodeOp::Unk52 { unk1, unk2 } => {
+                    stack_push!(TfxExpr::input(format!("unk52({unk1}, {unk2})")));
+                }
+                TfxBytecodeOp::Unk53 { unk1, unk2 } => {
+                    stack_push!(TfxExpr::input(format!("unk53({unk1}, {unk2})")));
+                }
+                TfxBytecodeOp::Unk54 { unk1, unk2 } => {
+                    stack_push!(TfxExpr::input(format!("unk54({unk1}, {unk2})")));
+                }
+                // Not implemented by the interpreter
+                TfxBytecodeOp::Unk14
+                | TfxBytecodeOp::Unk1b
+                | TfxBytecodeOp::Unk1c
+                | TfxBytecodeOp::Unk24
+                | TfxBytecodeOp::Unk25
+                | TfxBytecodeOp::Unk26
+                | TfxBytecodeOp::Unk2c
+                | TfxBytecodeOp::Unk2d
+                | TfxBytecodeOp::Unk38 { .. }
+                | TfxBytecodeOp::Unk39 { .. }
+                | TfxBytecodeOp::Unk3a { .. }
+                | TfxBytecodeOp::Unk42
+                | TfxBytecodeOp::Unk49 { .. }
+                | TfxBytecodeOp::Unk51
+                | TfxBytecodeOp::Unk55
+                | TfxBytecodeOp::Unk56
+                | TfxBytecodeOp::Unk57
+                | TfxBytecodeOp::Unk58 => {}
+            }
+        }
+
+        Ok(r)
+    }
+}
@@ -0,0 +1,320 @@
+use anyhow::Context;
+use binrw::binread;
+use num_derive::{FromPrimitive, ToPrimitive};
+use num_traits::FromPrimitive;
+
+use crate::opcodes::component_name;
+
+#[binread]
+#[br(repr(u8))]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, FromPrimitive, ToPrimitive)]
+pub enum TfxExtern {
+    None = 0,
+    Frame = 1,
+    View = 2,
+    Deferred = 3,
+    DeferredLight = 4,
+    DeferredUberLight = 5,
+    DeferredShadow = 6,
+    Atmosphere = 7,
+    RigidModel = 8,
+    EditorMesh = 9,
+    EditorMeshMaterial = 10,
+    EditorDecal = 11,
+    EditorTerrain = 12,
+    EditorTerrainPatch = 13,
+    EditorTerrainDebug = 14,
+    SimpleGeometry = 15,
+    UiFont = 16,
+    CuiView = 17,
+    CuiObject = 18,
+    CuiBitmap = 19,
+    CuiVideo = 20,
+    CuiStandard = 21,
+    CuiHud = 22,
+    CuiScreenspaceBoxes = 23,
+    TextureVisualizer = 24,
+    Generic = 25,
+    Particle = 26,
+    ParticleDebug = 27,
+    GearDyeVisualizationMode = 28,
+    ScreenArea = 29,
+    Mlaa = 30,
+    Msaa = 31,
+    Hdao = 32,
+    DownsampleTextureGeneric = 33,
+    DownsampleDepth = 34,
+    Ssao = 35,
+    VolumetricObscurance = 36,
+    Postprocess = 37,
+    TextureSet = 38,
+    Transparent = 39,
+    Vignette = 40,
+    GlobalLighting = 41,
+    ShadowMask = 42,
+    ObjectEffect = 43,
+    Decal = 44,
+    DecalSetTransform = 45,
+    DynamicDecal = 46,
+    DecoratorWind = 47,
+    TextureCameraLighting = 48,
+    VolumeFog = 49,
+    Fxaa = 50,
+    Smaa = 51,
+    Letterbox = 52,
+    DepthOfField = 53,
+    PostprocessInitialDownsample = 54,
+    CopyDepth = 55,
+    DisplacementMotionBlur = 56,
+    DebugShader = 57,
+    MinmaxDepth = 58,
+    SdsmBiasAndScale = 59,
+    SdsmBiasAndScaleTextures = 60,
+    ComputeShadowMapData = 61,
+    ComputeLocalLightShadowMapData = 62,
+    BilateralUpsample = 63,
+    HealthOverlay = 64,
+    LightProbeDominantLight = 65,
+    LightProbeLightInstance = 66,
+    Water = 67,
+    LensFlare = 68,
+    ScreenShader = 69,
+    Scaler = 70,
+    GammaControl = 71,
+    SpeedtreePlacements = 72,
+    Reticle = 73,
+    Distortion = 74,
+    WaterDebug = 75,
+    ScreenAreaInput = 76,
+    WaterDepthPrepass = 77,
+    OverheadVisibilityMap = 78,
+    ParticleCompute = 79,
+    CubemapFiltering = 80,
+    ParticleFastpath = 81,
+    VolumetricsPass = 82,
+    TemporalReprojection = 83,
+    FxaaCompute = 84,
+    VbCopyCompute = 85,
+    UberDepth = 86,
+    GearDye = 87,
+    Cubemaps = 88,
+    ShadowBlendWithPrevious = 89,
+    DebugShadingOutput = 90,
+    Ssao3d = 91,
+    WaterDisplacement = 92,
+    PatternBlending = 93,
+    UiHdrTransform = 94,
+    PlayerCenteredCascadedGrid = 95,
+    SoftDeform = 96,
+}
+
+/// Type of a value read from an extern
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TfxExternType {
+    Float,
+    Vec4,
+    Mat4,
+    Texture,
+    U32,
+    Uav,
+}
+
+impl TfxExternType {
+    /// Size of the value in bytes
+    pub fn size(&self) -> usize {
+        match self {
+            TfxExternType::Float | TfxExternType::U32 => 4,
+            TfxExternType::Vec4 => 16,
+            TfxExternType::Mat4 => 64,
+            TfxExternType::Texture | TfxExternType::Uav => 8,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct TfxExternField {
+    /// Offset in bytes
+    pub offset: usize,
+    pub name: &'static str,
+    pub ty: TfxExternType,
+}
+
+macro_rules! extern_fields {
+    ($($offset:literal => $ty:ident $name:ident),* $(,)?) => {
+        &[$(TfxExternField {
+            offset: $offset,
+            name: stringify!($name),
+            ty: TfxExternType::$ty,
+        }),*]
+    };
+}
+
+impl TfxExtern {
+    /// Returns the known fields of this extern, sorted by offset
+    #[rustfmt::skip]
+    pub fn fields(&self) -> &'static [TfxExternField] {
+        match self {
+            // Field names are shared with scope_frame
+            TfxExtern::Frame => extern_fields! {
+                0x00 => Float game_time,
+                0x04 => Float render_time,
+                0x08 => Float delta_game_time,
+                0x0c => Float exposure_time,
+                0x10 => Float exposure_scale,
+                0x14 => Float exposure_illum_relative_glow,
+                0x18 => Float exposure_scale_for_shading,
+                0x1c => Float exposure_illum_relative,
+                0x20 => Vec4 random_seed_scales,
+                0x30 => Vec4 overrides,
+                0xc0 => Texture iridescence_lookup,
+                0x1a0 => Vec4 unk1a0, // Alpha clip related
+                0x1b0 => Vec4 unk1b0,
+            },
+            TfxExtern::View => extern_fields! {
+                0x40 => Mat4 world_to_camera,
+                0x80 => Mat4 camera_to_projective,
+                0xc0 => Mat4 camera_to_world,
+                0x100 => Mat4 projective_to_camera,
+                0x140 => Mat4 world_to_projective,
+                0x180 => Mat4 projective_to_world,
+                0x1c0 => Mat4 target_pixel_to_world,
+                0x200 => Mat4 target_pixel_to_camera,
+                0x280 => Mat4 combined_tptoc_wtoc, // ?
+            },
+            TfxExtern::Deferred => extern_fields! {
+                0x00 => Vec4 depth_constants,
+                0x38 => Texture deferred_depth,
+                0x48 => Texture deferred_rt0,
+                0x50 => Texture deferred_rt1,
+                0x58 => Texture deferred_rt2,
+                0x60 => Texture light_diffuse,
+                0x68 => Texture light_specular,
+                0x70 => Texture light_ibl_specular,
+            },
+            TfxExtern::DeferredLight => extern_fields! {
+                0x10 => Float unk10,
+                0x20 => Float unk20,
+                0x40 => Mat4 unk40, // Used for transforming projective textures
+                0x80 => Mat4 light_to_world, // ?
+                0xc0 => Vec4 light_position, // ?
+                0xd0 => Vec4 unkd0,
+                0xe0 => Vec4 unke0,
+                0xf0 => Vec4 unkf0,
+                0x100 => Vec4 unk100,
+                0x110 => Float unk110,
+                0x120 => Float unk120,
+            },
+            // Field names are shared with scope_rigid_model
+            TfxExtern::RigidModel => extern_fields! {
+                0x00 => Mat4 mesh_to_world,
+                0x40 => Vec4 position_scale,
+                0x50 => Vec4 position_offset,
+                0x60 => Vec4 texcoord0_scale_offset,
+                0x70 => Vec4 dynamic_sh_ao_values,
+            },
+            TfxExtern::SimpleGeometry => extern_fields! {
+                0x00 => Mat4 transform, // ?
+            },
+            TfxExtern::Atmosphere => extern_fields! {
+                0x58 => Texture unk58,
+                0x70 => Vec4 unk70,
+                0xe0 => Texture unke0,
+            },
+            TfxExtern::Decal => extern_fields! {
+                0x08 => Texture unk08, // Copy of deferred_rt1
+            },
+            TfxExtern::ShadowMask => extern_fields! {
+                0x08 => Texture unk08,
+            },
+            TfxExtern::WaterDisplacement => extern_fields! {
+                0x00 => Texture unk00,
+            },
+            _ => &[],
+        }
+    }
+
+    /// Returns the field that contains the given byte offset
+    pub fn field_at(&self, offset: usize) -> Option<&'static TfxExternField> {
+        self.fields()
+            .iter()
+            .find(|f| (f.offset..f.offset + f.ty.size()).contains(&offset))
+    }
+
+    /// Formats a read of `ty` at `offset` bytes as a field path (eg. `View.world_to_projective`, `View.camera_to_world[3]` or `Frame.overrides.y`).
+    /// Returns `None` if the offset doesn't map to a known field.
+    pub fn field_name(&self, offset: usize, ty: TfxExternType) -> Option<String> {
+        let field = self.field_at(offset)?;
+        let relative = offset - field.offset;
+        if !relative.is_multiple_of(ty.size()) || relative + ty.size() > field.ty.size() {
+            return None;
+        }
+
+        let mut name = format!("{self:?}.{}", field.name);
+        if field.ty == ty {
+            return Some(name);
+        }
+
+        match (field.ty, ty) {
+            (TfxExternType::Mat4, TfxExternType::Vec4) => {
+                name.push_str(&format!("[{}]", relative / 16));
+            }
+            (TfxExternType::Mat4, TfxExternType::Float | TfxExternType::U32) => {
+                name.push_str(&format!(
+                    "[{}].{}",
+                    relative / 16,
+                    component_name((relative % 16 / 4) as u8)
+                ));
+            }
+            (TfxExternType::Vec4, TfxExternType::Float | TfxExternType::U32) => {
+                name.push_str(&format!(".{}", component_name((relative / 4) as u8)));
+            }
+            _ => return None,
+        }
+
+        Some(name)
+    }
+
+    /// Resolves a field path as produced by [`TfxExtern::field_name`] (without the extern name) to a byte offset
+    pub fn field_offset(&self, path: &str) -> Option<usize> {
+        let (name, mut rest) = path.split_at(path.find(['[', '.']).unwrap_or(path.len()));
+        let field = self.fields().iter().find(|f| f.name == name)?;
+
+        let mut offset = field.offset;
+        if let Some(r) = rest.strip_prefix('[') {
+            let (index, r) = r.split_once(']')?;
+            offset += index.trim().parse::<usize>().ok()? * 16;
+            rest = r;
+        }
+
+        if let Some(component) = rest.strip_prefix('.') {
+            if component.len() != 1 {
+                return None;
+            }
+            offset += "xyzw".find(component)? * 4;
+            rest = "";
+        }
+
+        rest.is_empty().then_some(offset)
+    }
+}
+
+#[binread]
+#[br(repr(u8))]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, FromPrimitive)]
+pub enum TfxShaderStage {
+    Pixel = 1,
+    Vertex = 2,
+    Geometry = 3,
+    Hull = 4,
+    Compute = 5,
+    Domain = 6,
+}
+
+impl TfxShaderStage {
+    /// Decodes shader stage from TFX bytecode value
+    pub fn from_tfx_value(value: u8) -> anyhow::Result<TfxShaderStage> {
+        Self::from_u8(value >> 5).context("Invalid shader stage index")
+    }
+}
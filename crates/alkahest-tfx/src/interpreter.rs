@@ -0,0 +1,970 @@
+use std::ops::Neg;
+
+use anyhow::Context;
+use glam::{Mat4, Vec4, Vec4Swizzles};
+use tinyvec::ArrayVec;
+
+use crate::{
+    externs::{TfxExtern, TfxExternType, TfxShaderStage},
+    opcodes::TfxBytecodeOp,
+};
+
+/// Supplies extern values to the interpreter.
+///
+/// Offsets are in the units of the opcode that requested them (see [`TfxBytecodeOp`])
+pub trait TfxExternProvider {
+    fn get_extern_float(&self, extern_: TfxExtern, offset: usize) -> anyhow::Result<f32>;
+    fn get_extern_vec4(&self, extern_: TfxExtern, offset: usize) -> anyhow::Result<Vec4>;
+    fn get_extern_mat4(&self, extern_: TfxExtern, offset: usize) -> anyhow::Result<Mat4>;
+    /// Returns an opaque texture handle, 0 means no texture
+    fn get_extern_texture(&self, extern_: TfxExtern, offset: usize) -> anyhow::Result<u64>;
+
+    fn get_extern_u32(&self, _extern_: TfxExtern, _offset: usize) -> anyhow::Result<u32> {
+        Ok(u32::MAX)
+    }
+
+    fn get_extern_uav(&self, _extern_: TfxExtern, _offset: usize) -> anyhow::Result<u64> {
+        Ok(u64::MAX)
+    }
+}
+
+/// Resource binding requested by the bytecode. Handles are whatever the [`TfxExternProvider`] handed out
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TfxBindCommand {
+    Texture {
+        stage: TfxShaderStage,
+        slot: u8,
+        handle: u64,
+    },
+    Sampler {
+        stage: TfxShaderStage,
+        slot: u8,
+        handle: u64,
+    },
+    Uav {
+        stage: TfxShaderStage,
+        slot: u8,
+        handle: u64,
+    },
+}
+
+/// Time inputs for a single evaluation.
+///
+/// The `Frame` time externs are read from here instead of the [`TfxExternProvider`], so a frame can be reproduced
+/// by evaluating with the same context
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TfxEvaluationContext {
+    /// Time in seconds, as seen by `Frame.game_time` and `Frame.render_time`
+    pub time: f32,
+    /// Frame time in seconds, as seen by `Frame.delta_game_time`
+    pub delta_time: f32,
+    /// Offset added to the input of the rand, rand_smooth, jitter and wander ops.
+    /// This isn't a game input, 0 leaves the ops as the game evaluates them
+    pub seed: u32,
+}
+
+impl TfxEvaluationContext {
+    /// Offset added to the input of the random ops
+    pub fn seed_offset(&self) -> f32 {
+        // Kept small so the offset input doesn't lose precision
+        (self.seed % 4096) as f32
+    }
+
+    /// Resolves the externs that come from this context rather than from the [`TfxExternProvider`]
+    pub fn get_extern_float(&self, extern_: TfxExtern, offset: usize) -> Option<f32> {
+        match (extern_, offset) {
+            // game_time and render_time
+            (TfxExtern::Frame, 0 | 1) => Some(self.time),
+            // delta_game_time
+            (TfxExtern::Frame, 2) => Some(self.delta_time),
+            _ => None,
+        }
+    }
+}
+
+impl Default for TfxEvaluationContext {
+    fn default() -> Self {
+        Self {
+            time: 0.0,
+            delta_time: 1.0 / 60.0,
+            seed: 0,
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone)]
+pub struct TfxEvaluationOutput {
+    /// cbuffer elements written by the program, in execution order
+    pub cb_writes: Vec<(usize, Vec4)>,
+    pub binds: Vec<TfxBindCommand>,
+}
+
+/// Machine state after a single op, recorded by [`TfxBytecodeInterpreter::evaluate_traced`]
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TfxTraceStep {
+    pub ip: usize,
+    /// Disassembly of the executed op
+    pub op: String,
+    /// Stack contents, bottom to top
+    pub stack: Vec<Vec4>,
+    pub temp: [Vec4; 16],
+    /// cbuffer elements written by this op
+    pub cb_writes: Vec<(usize, Vec4)>,
+}
+
+/// Step-by-step record of a single evaluation
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct TfxTrace {
+    /// cbuffer contents before the first op
+    pub initial_buffer: Vec<Vec4>,
+    pub steps: Vec<TfxTraceStep>,
+    /// IP and message of the op that failed, if evaluation didn't finish
+    pub error: Option<(usize, String)>,
+}
+
+impl TfxTrace {
+    /// Reconstructs the cbuffer contents after `step` has executed
+    pub fn buffer_at(&self, step: usize) -> Vec<Vec4> {
+        let mut buffer = self.initial_buffer.clone();
+        for (element, v) in self.steps.iter().take(step + 1).flat_map(|s| &s.cb_writes) {
+            buffer[*element] = *v;
+        }
+
+        buffer
+    }
+
+    /// Returns the first step after `from` that writes to any of the given cbuffer elements
+    pub fn next_output_write(&self, from: usize, elements: &[usize]) -> Option<usize> {
+        self.steps
+            .iter()
+            .enumerate()
+            .skip(from + 1)
+            .find(|(_, s)| s.cb_writes.iter().any(|(e, _)| elements.contains(e)))
+            .map(|(i, _)| i)
+    }
+
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+pub struct TfxBytecodeInterpreter {
+    opcodes: Vec<TfxBytecodeOp>,
+    pub error_shown: bool,
+}
+
+fn extern_error(extern_: TfxExtern, offset: usize, ty: TfxExternType) -> String {
+    let field = extern_
+        .field_name(offset, ty)
+        .unwrap_or_else(|| format!("{extern_:?}+0x{offset:X}"));
+
+    format!("Failed to read {ty:?} extern {field}")
+}
+
+impl TfxBytecodeInterpreter {
+    pub fn new(opcodes: Vec<TfxBytecodeOp>) -> Self {
+        Self {
+            opcodes,
+            error_shown: false,
+        }
+    }
+
+    pub fn opcodes(&self) -> &[TfxBytecodeOp] {
+        &self.opcodes
+    }
+
+    /// Runs the program against `buffer`, which holds the current cbuffer contents.
+    /// Written elements are stored in `buffer` and also returned in the output.
+    pub fn evaluate(
+        &self,
+        context: &TfxEvaluationContext,
+        externs: &dyn TfxExternProvider,
+        buffer: &mut [Vec4],
+        constants: &[Vec4],
+    ) -> anyhow::Result<TfxEvaluationOutput> {
+        self.evaluate_inner(context, externs, buffer, constants, None)
+    }
+
+    /// Runs the program like [`Self::evaluate`], recording the machine state after every op.
+    /// An evaluation error ends the trace instead of being returned.
+    pub fn evaluate_traced(
+        &self,
+        context: &TfxEvaluationContext,
+        externs: &dyn TfxExternProvider,
+        buffer: &mut [Vec4],
+        constants: &[Vec4],
+    ) -> TfxTrace {
+        let mut trace = TfxTrace {
+            initial_buffer: buffer.to_vec(),
+            ..Default::default()
+        };
+
+        if let Err(e) =
+            self.evaluate_inner(context, externs, buffer, constants, Some(&mut trace.steps))
+        {
+            trace.error = Some((trace.steps.len(), format!("{e:?}")));
+        }
+
+        trace
+    }
+
+    fn evaluate_inner(
+        &self,
+        context: &TfxEvaluationContext,
+        externs: &dyn TfxExternProvider,
+        buffer: &mut [Vec4],
+        constants: &[Vec4],
+        mut trace: Option<&mut Vec<TfxTraceStep>>,
+    ) -> anyhow::Result<TfxEvaluationOutput> {
+        let mut stack: ArrayVec<[Vec4; 64]> = Default::default();
+        let mut temp = [Vec4::ZERO; 16];
+        let mut output = TfxEvaluationOutput::default();
+
+        macro_rules! stack_pop {
+            ($pops:literal) => {{
+                anyhow::ensure!(!stack.is_empty() && stack.len() >= $pops);
+                let v: [Vec4; $pops] = stack[stack.len() - $pops..stack.len()].try_into().unwrap();
+                stack.truncate(stack.len() - $pops);
+                v
+            }};
+        }
+
+        macro_rules! stack_push {
+            ($value:expr) => {{
+                anyhow::ensure!(stack.len() < stack.capacity());
+                stack.push($value);
+            }};
+        }
+
+        macro_rules! stack_top {
+            () => {{
+                anyhow::ensure!(!stack.is_empty());
+                stack.last_mut().unwrap()
+            }};
+        }
+
+        for (ip, op) in self.opcodes.iter().enumerate() {
+            let cb_writes_start = output.cb_writes.len();
+            match op {
+                TfxBytecodeOp::Add | TfxBytecodeOp::Add2 => {
+                    let [t1, t2] = stack_pop!(2);
+                    stack_push!(t1 + t2);
+                }
+                TfxBytecodeOp::Subtract => {
+                    let [t1, t0] = stack_pop!(2);
+                    stack_push!(t1 - t0);
+                }
+                TfxBytecodeOp::Multiply | TfxBytecodeOp::Multiply2 => {
+                    let [t1, t0] = stack_pop!(2);
+                    stack_push!(t1 * t0);
+                }
+                TfxBytecodeOp::Divide => {
+                    // cohae: Bungie's SIMD implementation appears to do some extra zero checks, don't know if those are necessary.
+                    let [t1, t0] = stack_pop!(2);
+                    stack_push!(t1 / t0);
+                }
+                TfxBytecodeOp::IsZero => {
+                    // Cleaned up SIMD: _mm_and_ps(_mm_cmpeq_ps(a, _mm_setzero_ps()), _mm_set1_ps(1.0));
+                    // Decompiled and simplified: value == 0.0 ? 1.0 : 0.0 (for each element in the vector)
+                    let v = stack_top!();
+                    let c = v.cmpeq(Vec4::ZERO);
+                    *v = Vec4::new(
+                        c.test(0).into(),
+                        c.test(1).into(),
+                        c.test(2).into(),
+                        c.test(3).into(),
+                    );
+                }
+                TfxBytecodeOp::LessThan => {
+                    let [t1, t0] = stack_pop!(2);
+                    let c = t0.cmplt(t1);
+                    let v = Vec4::new(
+                        c.test(0).into(),
+                        c.test(1).into(),
+                        c.test(2).into(),
+                        c.test(3).into(),
+                    );
+                    stack_push!(v);
+                }
+                TfxBytecodeOp::Dot => {
+                    let [t1, t0] = stack_pop!(2);
+                    stack_push!(Vec4::splat(t0.dot(t1)));
+                }
+                TfxBytecodeOp::MultiplyAdd => {
+                    let [t2, t1, t0] = stack_pop!(3);
+                    stack_push!(t0 + (t1 * t2));
+                }
+                TfxBytecodeOp::Clamp => {
+                    let [value, min, max] = stack_pop!(3);
+                    stack_push!(value.clamp(min, max));
+                }
+                TfxBytecodeOp::Negate => {
+                    let v = stack_top!();
+                    *v = v.neg();
+                }
+                TfxBytecodeOp::Abs => {
+                    let v = stack_top!();
+                    *v = v.abs();
+                }
+                TfxBytecodeOp::Signum => {
+                    let v = stack_top!();
+                    *v = v.signum();
+                }
+                TfxBytecodeOp::Floor => {
+                    let v = stack_top!();
+                    *v = v.floor();
+                }
+                TfxBytecodeOp::Ceil => {
+                    let v = stack_top!();
+                    *v = v.ceil();
+                }
+                TfxBytecodeOp::Round => {
+                    let v = stack_top!();
+                    *v = v.round();
+                }
+                TfxBytecodeOp::VectorRotationsSin => {
+                    let v = stack_top!();
+                    *v = tfx_converted::_trig_helper_vector_sin_rotations_estimate(*v);
+                }
+                TfxBytecodeOp::VectorRotationsCos => {
+                    let v = stack_top!();
+                    *v = tfx_converted::_trig_helper_vector_cos_rotations_estimate(*v);
+                }
+                TfxBytecodeOp::VectorRotationsSinCos => {
+                    let v = stack_top!();
+                    *v = tfx_converted::_trig_helper_vector_sin_cos_rotations_estimate(*v);
+                }
+                TfxBytecodeOp::Merge1_3 => {
+                    let [t1, t0] = stack_pop!(2);
+                    stack_push!(Vec4::new(t1.x, t0.x, t0.y, t0.z));
+                }
+                TfxBytecodeOp::Merge2_2 => {
+                    let [t1, t0] = stack_pop!(2);
+                    stack_push!(Vec4::new(t1.x, t1.y, t0.x, t0.y));
+                }
+                TfxBytecodeOp::Unk0e => {
+                    // Cleaned up SIMD: keeps t1.xyz and takes w from t0.x, like a merge_3_1 would
+                    let [t1, t0] = stack_pop!(2);
+                    stack_push!(Vec4::new(t1.x, t1.y, t1.z, t0.x));
+                }
+                TfxBytecodeOp::Unk0f => {
+                    let [t1, t0] = stack_pop!(2);
+
+                    stack_push!(
+                        (t0.xxxx() * t1 + t0.yyyy()) * (t1 * t1) + (t0.zzzz() * t1 + t0.wwww())
+                    );
+                }
+                TfxBytecodeOp::Lerp => {
+                    let [b, a, v] = stack_pop!(3);
+                    stack_push!(a + v * (b - a));
+                }
+                TfxBytecodeOp::LerpSaturated => {
+                    let [b, a, v] = stack_pop!(3);
+                    stack_push!((a + v * (b - a)).clamp(Vec4::ZERO, Vec4::ONE));
+                }
+                TfxBytecodeOp::Frac => {
+                    let v = stack_top!();
+                    *v = v.fract();
+                }
+
+                TfxBytecodeOp::PushExternInputFloat { extern_, offset } => {
+                    let v = match context.get_extern_float(*extern_, *offset as usize) {
+                        Some(v) => v,
+                        None => externs
+                            .get_extern_float(*extern_, *offset as usize)
+                            .with_context(|| {
+                                extern_error(*extern_, *offset as usize * 4, TfxExternType::Float)
+                            })?,
+                    };
+                    stack_push!(Vec4::splat(v));
+                }
+                TfxBytecodeOp::PushExternInputVec4 { extern_, offset } => {
+                    let v = externs
+                        .get_extern_vec4(*extern_, *offset as usize)
+                        .with_context(|| {
+                            extern_error(*extern_, *offset as usize * 16, TfxExternType::Vec4)
+                        })?;
+                    stack_push!(v);
+                }
+                TfxBytecodeOp::PushExternInputMat4 { extern_, offset } => {
+                    let v = externs
+                        .get_extern_mat4(*extern_, *offset as usize)
+                        .with_context(|| {
+                            extern_error(*extern_, *offset as usize * 16, TfxExternType::Mat4)
+                        })?;
+                    stack_push!(v.x_axis);
+                    stack_push!(v.y_axis);
+                    stack_push!(v.z_axis);
+                    stack_push!(v.w_axis);
+                }
+                TfxBytecodeOp::PushExternInputTexture { extern_, offset } => {
+                    let handle = externs
+                        .get_extern_texture(*extern_, *offset as usize)
+                        .with_context(|| {
+                            extern_error(*extern_, *offset as usize * 8, TfxExternType::Texture)
+                        })?;
+                    let v: Vec4 = bytemuck::cast([handle, 0]);
+                    stack_push!(v);
+                }
+                TfxBytecodeOp::PushExternInputUav { extern_, offset } => {
+                    let handle = externs
+                        .get_extern_uav(*extern_, *offset as usize)
+                        .with_context(|| {
+                            extern_error(*extern_, *offset as usize * 8, TfxExternType::Uav)
+                        })?;
+                    let v: Vec4 = bytemuck::cast([handle, 0]);
+                    stack_push!(v);
+                }
+                TfxBytecodeOp::PushExternInputU32 { extern_, offset } => {
+                    let value = externs
+                        .get_extern_u32(*extern_, *offset as usize)
+                        .with_context(|| {
+                            extern_error(*extern_, *offset as usize * 4, TfxExternType::U32)
+                        })?;
+                    let v: Vec4 = bytemuck::cast([value, 0, 0, 0]);
+                    stack_push!(v);
+                }
+                TfxBytecodeOp::SetShaderSampler { stage, slot, .. } => {
+                    let [v] = stack_pop!(1);
+                    let [handle, _]: [u64; 2] = bytemuck::cast(v);
+                    output.binds.push(TfxBindCommand::Sampler {
+                        stage: *stage,
+                        slot: *slot,
+                        handle,
+                    });
+                }
+                TfxBytecodeOp::SetShaderTexture { stage, slot, .. } => {
+                    let [v] = stack_pop!(1);
+                    let [handle, _]: [u64; 2] = bytemuck::cast(v);
+                    output.binds.push(TfxBindCommand::Texture {
+                        stage: *stage,
+                        slot: *slot,
+                        handle,
+                    });
+                }
+                TfxBytecodeOp::SetShaderUav { stage, slot, .. } => {
+                    let [v] = stack_pop!(1);
+                    let [handle, _]: [u64; 2] = bytemuck::cast(v);
+                    output.binds.push(TfxBindCommand::Uav {
+                        stage: *stage,
+                        slot: *slot,
+                        handle,
+                    });
+                }
+
+                TfxBytecodeOp::Triangle => {
+                    let v = stack_top!();
+                    *v = tfx_converted::bytecode_op_triangle(*v);
+                }
+                TfxBytecodeOp::Jitter => {
+                    let v = stack_top!();
+                    *v = tfx_converted::bytecode_op_jitter(*v + context.seed_offset());
+                }
+                TfxBytecodeOp::Wander => {
+                    let v = stack_top!();
+                    *v = tfx_converted::bytecode_op_wander(*v + context.seed_offset());
+                }
+                TfxBytecodeOp::Rand => {
+                    let v = stack_top!();
+                    *v = tfx_converted::bytecode_op_rand(*v + context.seed_offset());
+                }
+                TfxBytecodeOp::RandSmooth => {
+                    let v = stack_top!();
+                    *v = tfx_converted::bytecode_op_rand_smooth(*v + context.seed_offset());
+                }
+                TfxBytecodeOp::TransformVec4 => {
+                    let [x_axis, y_axis, z_axis, w_axis, value] = stack_pop!(5);
+                    let mat = Mat4 {
+                        x_axis,
+                        y_axis,
+                        z_axis,
+                        w_axis,
+                    };
+
+                    stack_push!(mat.mul_vec4(value));
+                }
+
+                &TfxBytecodeOp::Unk4c { unk1 }
+                | &TfxBytecodeOp::PushSampler { index: unk1 }
+                | &TfxBytecodeOp::Unk4e { unk1, .. }
+                | &TfxBytecodeOp::Unk4f { unk1 }
+                | &TfxBytecodeOp::Unk50 { unk1 }
+                | &TfxBytecodeOp::Unk52 { unk1, .. }
+                | &TfxBytecodeOp::Unk53 { unk1, .. }
+                | &TfxBytecodeOp::Unk54 { unk1, .. } => match unk1 {
+                    97 => stack_push!(Vec4::ZERO),
+                    _ => stack_push!(Vec4::ONE),
+                },
+                TfxBytecodeOp::UnkLoadConstant { constant_index } => {
+                    anyhow::ensure!((*constant_index as usize) < constants.len());
+                    *stack_top!() = constants[*constant_index as usize];
+                }
+                TfxBytecodeOp::PushConstVec4 { constant_index } => {
+                    anyhow::ensure!((*constant_index as usize) < constants.len());
+                    stack_push!(constants[*constant_index as usize])
+                }
+                TfxBytecodeOp::LerpConstant { constant_start } => {
+                    anyhow::ensure!((*constant_start as usize + 1) < constants.len());
+                    let a = constants[*constant_start as usize];
+                    let b = constants[*constant_start as usize + 1];
+
+                    let v = stack_top!();
+                    *v = a + *v * (b - a);
+                }
+                TfxBytecodeOp::LerpConstantSaturated { constant_start } => {
+                    anyhow::ensure!((*constant_start as usize + 1) < constants.len());
+                    let a = constants[*constant_start as usize];
+                    let b = constants[*constant_start as usize + 1];
+
+                    let v = stack_top!();
+                    *v = (a + *v * (b - a)).clamp(Vec4::ZERO, Vec4::ONE);
+                }
+                // cohae: Unk37 runs the same SIMD code as spline4_const
+                TfxBytecodeOp::Spline4Const { constant_start }
+                | TfxBytecodeOp::Unk37 { constant_start } => {
+                    let start = *constant_start as usize;
+                    anyhow::ensure!((start + 4) < constants.len());
+                    let v = stack_top!();
+                    *v = tfx_converted::bytecode_op_spline4_const(*v, &constants[start..start + 5]);
+                }
+                TfxBytecodeOp::Spline8Const { constant_start } => {
+                    let start = *constant_start as usize;
+                    anyhow::ensure!((start + 9) < constants.len());
+                    let v = stack_top!();
+                    *v =
+                        tfx_converted::bytecode_op_spline8_const(*v, &constants[start..start + 10]);
+                }
+                TfxBytecodeOp::Spline8ChainConst { constant_start } => {
+                    let start = *constant_start as usize;
+                    anyhow::ensure!((start + 9) < constants.len());
+                    anyhow::ensure!(stack.len() >= 2);
+                    let t = stack[stack.len() - 2];
+                    let coefficients = &constants[start..start + 10];
+                    if t.x >= coefficients[4].x {
+                        *stack_top!() = tfx_converted::bytecode_op_spline8_const(t, coefficients);
+                    }
+                }
+                TfxBytecodeOp::PermuteExtendX => {
+                    let v = stack_top!();
+                    *v = v.xxxx();
+                }
+                TfxBytecodeOp::Permute { fields } => {
+                    let s0 = (fields >> 6) & 0b11;
+                    let s1 = (fields >> 4) & 0b11;
+                    let s2 = (fields >> 2) & 0b11;
+                    let s3 = fields & 0b11;
+
+                    let v = stack_top!();
+                    let v2 = v.to_array();
+
+                    *v = Vec4::new(
+                        v2[s0 as usize],
+                        v2[s1 as usize],
+                        v2[s2 as usize],
+                        v2[s3 as usize],
+                    );
+                }
+                TfxBytecodeOp::Saturate => {
+                    let v = stack_top!();
+                    *v = v.clamp(Vec4::ZERO, Vec4::ONE);
+                }
+                TfxBytecodeOp::Min => {
+                    let [t1, t0] = stack_pop!(2);
+                    stack_push!(t1.min(t0))
+                }
+                TfxBytecodeOp::Max => {
+                    let [t1, t0] = stack_pop!(2);
+                    stack_push!(t1.max(t0))
+                }
+                TfxBytecodeOp::PushFromOutput { element } => {
+                    anyhow::ensure!(
+                        (*element as usize) < buffer.len(),
+                        "Push from output element is out of range"
+                    );
+
+                    stack_push!(buffer[*element as usize]);
+                }
+                TfxBytecodeOp::PopOutput { element } => {
+                    anyhow::ensure!(
+                        (*element as usize) < buffer.len(),
+                        "Pop output element is out of range"
+                    );
+
+                    let [v] = stack_pop!(1);
+                    buffer[*element as usize] = v;
+                    output.cb_writes.push((*element as usize, v));
+                }
+                TfxBytecodeOp::PopOutputMat4 { element } => {
+                    anyhow::ensure!(
+                        (*element as usize + 3) < buffer.len(),
+                        "Pop output mat4 element is out of range"
+                    );
+
+                    let [x_axis, y_axis, z_axis, w_axis] = stack_pop!(4);
+
+                    let start = *element as usize;
+                    buffer[start..start + 4].copy_from_slice(&[x_axis, y_axis, z_axis, w_axis]);
+                    output.cb_writes.extend([
+                        (start, x_axis),
+                        (start + 1, y_axis),
+                        (start + 2, z_axis),
+                        (start + 3, w_axis),
+                    ]);
+                }
+                TfxBytecodeOp::PushTemp { slot } => {
+                    let slotu = *slot as usize;
+                    anyhow::ensure!(slotu < temp.len(), "Temp slot is out of range");
+                    stack_push!(temp[slotu]);
+                }
+                TfxBytecodeOp::PopTemp { slot } => {
+                    let slotu = *slot as usize;
+                    anyhow::ensure!(slotu < temp.len(), "Temp slot is out of range");
+                    let [v] = stack_pop!(1);
+                    temp[slotu] = v;
+                }
+                #[cfg(not(feature = "tfx_strict_interpreter"))]
+                _ => {
+                    let _ = ip;
+                }
+                #[cfg(feature = "tfx_strict_interpreter")]
+                u => {
+                    anyhow::bail!("Unimplemented TFX bytecode op '{u:?}' at IP {ip}")
+                }
+            }
+
+            if let Some(steps) = trace.as_deref_mut() {
+                steps.push(TfxTraceStep {
+                    ip,
+                    op: op.disassemble(Some(constants)),
+                    stack: stack.to_vec(),
+                    temp,
+                    cb_writes: output.cb_writes[cb_writes_start..].to_vec(),
+                });
+            }
+        }
+
+        Ok(output)
+    }
+
+    pub fn dump(&self, constants: &[Vec4], buffer: &[Vec4]) {
+        debug!("Dumping TFX interpreter");
+        debug!("- cb0 size: {} elements", buffer.len());
+        if !constants.is_empty() {
+            debug!("- Constant table:");
+            for (i, v) in constants.iter().enumerate() {
+                debug!("\t{i} = {v:?}")
+            }
+        }
+
+        // debug!("- Stack:");
+        // for (i, v) in self.stack_backup.iter().enumerate() {
+        //     debug!("\t{i} = {v:?}")
+        // }
+
+        debug!("- Bytecode:");
+        for (i, op) in self.opcodes.iter().enumerate() {
+            debug!("\t{i}: {}", op.disassemble(Some(constants)));
+        }
+    }
+}
+
+// Methods adapted from HLSL TFX sources
+pub(crate) mod tfx_converted {
+    use glam::{Vec4, Vec4Swizzles};
+
+    fn lerp(start: f32, end: f32, t: f32) -> f32 {
+        start + (end - start) * t
+    }
+
+    fn _trig_helper_vector_pseudo_sin_rotations_clamped(a: Vec4) -> Vec4 {
+        a * (a.abs() * -16.0 + 8.0)
+    }
+
+    fn _trig_helper_vector_pseudo_sin_rotations(a: Vec4) -> Vec4 {
+        let w = a - a.round(); // wrap to [-0.5, 0.5] range
+        _trig_helper_vector_pseudo_sin_rotations_clamped(w)
+    }
+
+    pub fn bytecode_op_triangle(x: Vec4) -> Vec4 {
+        let wrapped = x - x.round(); // wrap to [-0.5, 0.5] range
+        let abs_wrap = wrapped.abs(); // abs turns into triangle wave between [0, 0.5]
+
+        abs_wrap * 2.0 // scale to [0, 1] range
+    }
+
+    pub fn bytecode_op_jitter(x: Vec4) -> Vec4 {
+        let rotations =
+            x.xxxx() * Vec4::new(4.67, 2.99, 1.08, 1.35) + Vec4::new(0.52, 0.37, 0.16, 0.79);
+
+        // optimized scaled-sum-of-sines
+        let a = rotations - rotations.round(); // wrap to [-0.5, 0.5] range
+        let ma = a.abs() * -16.0 + 8.0;
+        let sa = a * 0.25;
+        let v = sa.dot(ma) + 0.5;
+
+        // hermite smooth interpolation (3*v^2 - 2*v^3)
+        let v2 = v * v;
+        let jitter_result = (-2.0 * v + 3.0) * v2;
+
+        Vec4::splat(jitter_result)
+    }
+
+    pub fn bytecode_op_wander(x: Vec4) -> Vec4 {
+        let rot0 = x.xxxx() * Vec4::new(4.08, 1.02, 3.0 / 5.37, 3.0 / 9.67)
+            + Vec4::new(0.92, 0.33, 0.26, 0.54);
+        let rot1 = x.xxxx() * Vec4::new(1.83, 3.09, 0.39, 0.87) + Vec4::new(0.12, 0.37, 0.16, 0.79);
+        let sines0 = _trig_helper_vector_pseudo_sin_rotations(rot0);
+        let sines1 =
+            _trig_helper_vector_pseudo_sin_rotations(rot1) * Vec4::new(0.02, 0.02, 0.28, 0.28);
+        let wander_result = 0.5 + sines0.dot(sines1);
+
+        Vec4::splat(wander_result)
+    }
+
+    pub fn bytecode_op_rand(x: Vec4) -> Vec4 {
+        // these magic numbers are 1/(prime/1000000)
+        let v0 = x.x.floor();
+        let mut val0 = Vec4::splat(v0).dot(Vec4::new(
+            1.0 / 1.043501,
+            1.0 / 0.794471,
+            1.0 / 0.113777,
+            1.0 / 0.015101,
+        ));
+        val0 = val0.fract();
+
+        //			val0=	bbs(val0);		// Blum-Blum-Shub randomimzer
+        val0 = val0 * val0 * 251.0;
+        val0 = val0.fract();
+
+        Vec4::splat(val0)
+    }
+
+    pub fn bytecode_op_rand_smooth(x: Vec4) -> Vec4 {
+        let v = x.x;
+        let v0 = v.round();
+        let v1 = v0 + 1.0;
+        let f = v - v0;
+        let f2 = f * f;
+
+        // hermite smooth interpolation (3*f^2 - 2*f^3)
+        let smooth_f = (-2.0 * f + 3.0) * f2;
+
+        // these magic numbers are 1/(prime/1000000)
+        let mut val0 = Vec4::splat(v0).dot(Vec4::new(
+            1.0 / 1.043501,
+            1.0 / 0.794471,
+            1.0 / 0.113777,
+            1.0 / 0.015101,
+        ));
+        let mut val1 = Vec4::splat(v1).dot(Vec4::new(
+            1.0 / 1.043501,
+            1.0 / 0.794471,
+            1.0 / 0.113777,
+            1.0 / 0.015101,
+        ));
+
+        val0 = val0.fract();
+        val1 = val1.fract();
+
+        //			val0=	bbs(val0);		// Blum-Blum-Shub randomimzer
+        val0 = val0 * val0 * 251.0;
+        val0 = val0.fract();
+
+        //			val10=	bbs(val1);		// Blum-Blum-Shub randomimzer
+        val1 = val1 * val1 * 251.0;
+        val1 = val1.fract();
+
+        let rand_smooth_result = lerp(val0, val1, smooth_f);
+
+        Vec4::splat(rand_smooth_result)
+    }
+
+    /// Each lane holds one cubic segment, `c[4]` holds the segment start thresholds.
+    /// The segment selection and lane folding mirror the SIMD implementation.
+    pub fn bytecode_op_spline4_const(t: Vec4, c: &[Vec4]) -> Vec4 {
+        let segments = (t * c[0] + c[1]) * (t * t) + (c[2] * t + c[3]);
+        let thresholds = c[4];
+
+        let mut result = 0u32;
+        for i in 0..4 {
+            let active = thresholds[i] <= t[i];
+            let next_active = i < 3 && thresholds[i + 1] <= t[i + 1];
+            if active != next_active {
+                result ^= segments[i].to_bits();
+            }
+        }
+
+        Vec4::splat(f32::from_bits(result))
+    }
+
+    pub fn bytecode_op_spline8_const(t: Vec4, c: &[Vec4]) -> Vec4 {
+        if t.x >= c[9].x {
+            bytecode_op_spline4_const(t, &c[5..10])
+        } else {
+            bytecode_op_spline4_const(t, &c[0..5])
+        }
+    }
+
+    pub fn _trig_helper_vector_sin_rotations_estimate_clamped(a: Vec4) -> Vec4 {
+        let y = a * (-16.0 * a.abs() + 8.0);
+        y * (0.225 * y.abs() + 0.775)
+    }
+
+    pub fn _trig_helper_vector_sin_rotations_estimate(a: Vec4) -> Vec4 {
+        let w = a - a.round(); // wrap to [-0.5, 0.5] range
+        _trig_helper_vector_sin_rotations_estimate_clamped(w)
+    }
+
+    pub fn _trig_helper_vector_cos_rotations_estimate(a: Vec4) -> Vec4 {
+        _trig_helper_vector_sin_rotations_estimate(a + 0.25)
+    }
+
+    pub fn _trig_helper_vector_sin_cos_rotations_estimate(a: Vec4) -> Vec4 {
+        _trig_helper_vector_sin_rotations_estimate(a + Vec4::new(0.0, 0.25, 0.0, 0.25))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use binrw::Endian;
+
+    use super::*;
+
+    struct TestExterns;
+
+    impl TfxExternProvider for TestExterns {
+        fn get_extern_float(&self, _extern_: TfxExtern, _offset: usize) -> anyhow::Result<f32> {
+            Ok(0.0)
+        }
+
+        fn get_extern_vec4(&self, _extern_: TfxExtern, _offset: usize) -> anyhow::Result<Vec4> {
+            Ok(Vec4::ZERO)
+        }
+
+        fn get_extern_mat4(&self, _extern_: TfxExtern, _offset: usize) -> anyhow::Result<Mat4> {
+            Ok(Mat4::IDENTITY)
+        }
+
+        fn get_extern_texture(&self, _extern_: TfxExtern, _offset: usize) -> anyhow::Result<u64> {
+            Ok(0)
+        }
+    }
+
+    /// Runs hand-built bytecode and returns the first 4 cbuffer elements
+    fn run(bytecode: &[u8], constants: &[Vec4]) -> [Vec4; 4] {
+        let opcodes = TfxBytecodeOp::parse_all(bytecode, Endian::Little).unwrap();
+        let mut buffer = [Vec4::splat(-1.0); 4];
+        TfxBytecodeInterpreter::new(opcodes)
+            .evaluate(
+                &TfxEvaluationContext::default(),
+                &TestExterns,
+                &mut buffer,
+                constants,
+            )
+            .unwrap();
+
+        buffer
+    }
+
+    #[test]
+    fn lerp_saturated() {
+        let c = [
+            Vec4::new(0.5, 0.0, 2.0, 1.0),
+            Vec4::new(-1.0, 0.0, 0.0, 0.0),
+            Vec4::new(1.0, 4.0, 4.0, -1.0),
+        ];
+        let out = run(&[0x34, 0, 0x34, 1, 0x34, 2, 0x11, 0x44, 0], &c);
+        assert_eq!(out[0], Vec4::new(0.5, 0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn lerp_constant_saturated() {
+        let c = [
+            Vec4::new(0.5, 0.0, 2.0, 0.25),
+            Vec4::new(-1.0, 0.0, 0.0, -1.0),
+            Vec4::new(1.0, 4.0, 4.0, 0.0),
+        ];
+        let out = run(&[0x34, 0, 0x36, 1, 0x44, 0, 0x34, 0, 0x35, 1, 0x44, 1], &c);
+        assert_eq!(out[0], Vec4::new(0.0, 0.0, 1.0, 0.0));
+        assert_eq!(out[1], Vec4::new(0.0, 0.0, 8.0, -0.75));
+    }
+
+    #[test]
+    fn unk37_matches_spline4_const() {
+        // t = 2, followed by spline4 constants that evaluate t^3 in the first segment
+        let c = [
+            Vec4::splat(2.0),
+            Vec4::new(1.0, 0.0, 0.0, 0.0),
+            Vec4::ZERO,
+            Vec4::ZERO,
+            Vec4::ZERO,
+            Vec4::new(0.0, 10.0, 10.0, 10.0),
+        ];
+        let out = run(&[0x34, 0, 0x37, 1, 0x44, 0, 0x34, 0, 0x2f, 1, 0x44, 1], &c);
+        assert_eq!(out[0], out[1]);
+    }
+
+    #[test]
+    fn frame_time_comes_from_context() {
+        // game_time, render_time, delta_game_time and exposure_scale
+        let opcodes = TfxBytecodeOp::parse_all(
+            &[
+                0x3c, 0x01, 0x00, 0x44, 0, 0x3c, 0x01, 0x01, 0x44, 1, 0x3c, 0x01, 0x02, 0x44, 2,
+                0x3c, 0x01, 0x04, 0x44, 3,
+            ],
+            Endian::Little,
+        )
+        .unwrap();
+        let context = TfxEvaluationContext {
+            time: 2.5,
+            delta_time: 0.5,
+            ..Default::default()
+        };
+
+        let mut buffer = [Vec4::splat(-1.0); 4];
+        TfxBytecodeInterpreter::new(opcodes)
+            .evaluate(&context, &TestExterns, &mut buffer, &[])
+            .unwrap();
+        assert_eq!(
+            buffer,
+            [
+                Vec4::splat(2.5),
+                Vec4::splat(2.5),
+                Vec4::splat(0.5),
+                Vec4::ZERO
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_ops_keep_placeholder_behaviour() {
+        // unk4c, unk4e, unk4f, unk50, unk52, unk53 and unk54 push one, or zero when their first operand is 97
+        let out = run(
+            &[0x50, 1, 0x44, 0, 0x53, 97, 0, 0x44, 1, 0x54, 2, 3, 0x44, 2],
+            &[],
+        );
+        assert_eq!(out[0], Vec4::ONE);
+        assert_eq!(out[1], Vec4::ZERO);
+        assert_eq!(out[2], Vec4::ONE);
+
+        let out = run(
+            &[
+                0x4c, 97, 0x44, 0, 0x4e, 1, 2, 3, 4, 0x44, 1, 0x4f, 97, 0x44, 2, 0x52, 5, 6, 0x44,
+                3,
+            ],
+            &[],
+        );
+        assert_eq!(out, [Vec4::ZERO, Vec4::ONE, Vec4::ZERO, Vec4::ONE]);
+
+        // The rest don't touch the stack
+        let c = [Vec4::new(1.0, 2.0, 3.0, 4.0)];
+        let out = run(
+            &[
+                0x34, 0, 0x14, 0x1b, 0x1c, 0x24, 0x25, 0x26, 0x2c, 0x2d, 0x38, 2, 0x39, 3, 0x3a, 4,
+                0x42, 0x49, 5, 0x51, 0x55, 0x56, 0x57, 0x58, 0x44, 0,
+            ],
+            &c,
+        );
+        assert_eq!(out[0], c[0]);
+    }
+}
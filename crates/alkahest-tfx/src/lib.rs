@@ -0,0 +1,9 @@
+#[macro_use]
+extern crate tracing;
+
+pub mod assembler;
+pub mod decompiler;
+pub mod externs;
+pub mod interpreter;
+pub mod opcodes;
+pub mod verifier;
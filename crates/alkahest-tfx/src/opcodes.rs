@@ -0,0 +1,656 @@
+use std::io::Cursor;
+
+use binrw::{binread, BinReaderExt, Endian};
+use glam::Vec4;
+
+use crate::externs::{TfxExtern, TfxExternType, TfxShaderStage};
+
+#[rustfmt::skip]
+#[binread]
+#[derive(Debug)]
+pub enum TfxBytecodeOp {
+    // Basic math ops
+    #[br(magic = 0x01_u8)] Add,
+    #[br(magic = 0x02_u8)] Subtract,
+    #[br(magic = 0x03_u8)] Multiply,
+    #[br(magic = 0x04_u8)] Divide,
+    #[br(magic = 0x05_u8)] Multiply2,
+    #[br(magic = 0x06_u8)] Add2,
+    #[br(magic = 0x07_u8)] IsZero,
+    #[br(magic = 0x08_u8)] Min,
+    #[br(magic = 0x09_u8)] Max,
+
+    #[br(magic = 0x0a_u8)] LessThan,
+    #[br(magic = 0x0b_u8)] Dot,
+    #[br(magic = 0x0c_u8)] Merge1_3,
+    #[br(magic = 0x0d_u8)] Merge2_2, // merge_2_2?
+    #[br(magic = 0x0e_u8)] Unk0e,
+    #[br(magic = 0x0f_u8)] Unk0f,
+    #[br(magic = 0x10_u8)] Lerp,
+    #[br(magic = 0x11_u8)] LerpSaturated,
+    #[br(magic = 0x12_u8)] MultiplyAdd,
+    #[br(magic = 0x13_u8)] Clamp,
+    #[br(magic = 0x14_u8)] Unk14,
+    #[br(magic = 0x15_u8)] Abs,
+    #[br(magic = 0x16_u8)] Signum,
+    #[br(magic = 0x17_u8)] Floor,
+    #[br(magic = 0x18_u8)] Ceil,
+    #[br(magic = 0x19_u8)] Round,
+    #[br(magic = 0x1a_u8)] Frac,
+    #[br(magic = 0x1b_u8)] Unk1b,
+    #[br(magic = 0x1c_u8)] Unk1c,
+    #[br(magic = 0x1d_u8)] Negate,
+    #[br(magic = 0x1e_u8)] VectorRotationsSin, // _trig_helper_vector_sin_rotations_estimate
+    #[br(magic = 0x1f_u8)] VectorRotationsCos, // _trig_helper_vector_cos_rotations_estimate
+    #[br(magic = 0x20_u8)] VectorRotationsSinCos, // _trig_helper_vector_sin_cos_rotations_estimate
+    #[br(magic = 0x21_u8)] PermuteExtendX, // Alias for permute(.xxxx)
+    #[br(magic = 0x22_u8)] Permute { fields: u8 }, // Permute/swizzle values
+    #[br(magic = 0x23_u8)] Saturate, // saturate?
+    #[br(magic = 0x24_u8)] Unk24,
+    #[br(magic = 0x25_u8)] Unk25,
+    #[br(magic = 0x26_u8)] Unk26,
+    #[br(magic = 0x27_u8)] Triangle,
+    #[br(magic = 0x28_u8)] Jitter, // jitter?
+    #[br(magic = 0x29_u8)] Wander,
+    #[br(magic = 0x2a_u8)] Rand, // rand?
+    #[br(magic = 0x2b_u8)] RandSmooth, // rand_smooth?
+    #[br(magic = 0x2c_u8)] Unk2c,
+    #[br(magic = 0x2d_u8)] Unk2d,
+    #[br(magic = 0x2e_u8)] TransformVec4,
+
+    /// Evaluates a 4-segment piecewise cubic spline stored in 5 constants (a, b, c, d, thresholds)
+    #[br(magic = 0x2f_u8)] Spline4Const { constant_start: u8 },
+    /// Evaluates an 8-segment piecewise cubic spline stored in 10 constants (two spline4 blocks)
+    #[br(magic = 0x30_u8)] Spline8Const { constant_start: u8 },
+    /// Same as spline8_const, but only overwrites the value on top of the stack when the input
+    /// (second to top) reaches the first threshold, allowing splines with more than 8 segments
+    #[br(magic = 0x31_u8)] Spline8ChainConst { constant_start: u8 },
+
+    // Constant-related
+    #[br(magic = 0x34_u8)] PushConstVec4 { constant_index: u8 }, // push_const_vec4?
+    #[br(magic = 0x35_u8)] LerpConstant { constant_start: u8 },
+    #[br(magic = 0x36_u8)] LerpConstantSaturated { constant_start: u8 },
+    #[br(magic = 0x37_u8)] Unk37 { constant_start: u8 }, // spline4_const?
+    #[br(magic = 0x38_u8)] Unk38 { unk1: u8 },
+    #[br(magic = 0x39_u8)] Unk39 { unk1: u8 },
+    #[br(magic = 0x3a_u8)] Unk3a { unk1: u8 },
+    #[br(magic = 0x3b_u8)] UnkLoadConstant { constant_index: u8 },
+    
+    // Externs
+    /// Pushes an extern float to the stack, extended to all 4 elements (value.xxxx)
+    /// Offset is in single floats (4 bytes)
+    #[br(magic = 0x3c_u8)] PushExternInputFloat { extern_: TfxExtern, offset: u8 },
+    /// Pushes an extern vec4 to the stack
+    /// Offset is in vec4s (16 bytes)
+    #[br(magic = 0x3d_u8)] PushExternInputVec4 { extern_: TfxExtern, offset: u8 },
+    /// Pushes an extern mat4 to the stack
+    /// Offset is in vec4s (16 bytes)
+    #[br(magic = 0x3e_u8)] PushExternInputMat4 { extern_: TfxExtern, offset: u8 },
+    /// Pushes an extern texture to the stack as a u64
+    /// Offset is in u64s (8 bytes)
+    #[br(magic = 0x3f_u8)] PushExternInputTexture { extern_: TfxExtern, offset: u8 },
+    /// Pushes an extern u32 to the stack as a u64
+    /// Offset is in u32s (4 bytes)
+    #[br(magic = 0x40_u8)] PushExternInputU32 { extern_: TfxExtern, offset: u8 },
+    // TODO(cohae): Carbon copy of 0x3f???
+    /// Pushes an extern UAV to the stack as a u64
+    /// Offset is in u64s (8 bytes)
+    #[br(magic = 0x41_u8)] PushExternInputUav { extern_: TfxExtern, offset: u8 },
+
+    // TODO(cohae): Loads a value from the interpreter state + 0x44a0
+    #[br(magic = 0x42_u8)] Unk42,
+    #[br(magic = 0x43_u8)] PushFromOutput { element: u8 },
+    #[br(magic = 0x44_u8)] PopOutput { element: u8 },
+    #[br(magic = 0x45_u8)] PopOutputMat4 { element: u8 },
+    #[br(magic = 0x46_u8)] PushTemp { slot: u8 },
+    #[br(magic = 0x47_u8)] PopTemp { slot: u8 },
+    #[br(magic = 0x48_u8)] SetShaderTexture {
+        value: u8,
+        #[br(try_calc(TfxShaderStage::from_tfx_value(value)))]
+        stage: TfxShaderStage,
+        #[br(calc(value & 0x1f))]
+        slot: u8
+    },
+    #[br(magic = 0x49_u8)] Unk49 { unk1: u8 },
+    #[br(magic = 0x4a_u8)] SetShaderSampler { 
+        value: u8,
+        #[br(try_calc(TfxShaderStage::from_tfx_value(value)))]
+        stage: TfxShaderStage,
+        #[br(calc(value & 0x1f))]
+        slot: u8 
+    },
+    #[br(magic = 0x4b_u8)] SetShaderUav { 
+        value: u8,
+        #[br(try_calc(TfxShaderStage::from_tfx_value(value)))]
+        stage: TfxShaderStage,
+        #[br(calc(value & 0x1f))]
+        slot: u8
+    },
+    #[br(magic = 0x4c_u8)] Unk4c { unk1: u8 },
+    /// Pushes a sampler on the stack from the technique sampler table
+    #[br(magic = 0x4d_u8)] PushSampler { index: u8 },
+    #[br(magic = 0x4e_u8)] Unk4e { unk1: u8, unk2: u8, unk3: u8, unk4: u8 },
+    #[br(magic = 0x4f_u8)] Unk4f { unk1: u8 },
+    #[br(magic = 0x50_u8)] Unk50 { unk1: u8 },
+    #[br(magic = 0x51_u8)] Unk51,
+    #[br(magic = 0x52_u8)] Unk52 { unk1: u8, unk2: u8 },
+    #[br(magic = 0x53_u8)] Unk53 { unk1: u8, unk2: u8 },
+    #[br(magic = 0x54_u8)] Unk54 { unk1: u8, unk2: u8 },
+    #[br(magic = 0x55_u8)] Unk55,
+    #[br(magic = 0x56_u8)] Unk56,
+    #[br(magic = 0x57_u8)] Unk57,
+    #[br(magic = 0x58_u8)] Unk58,
+}
+
+impl TfxBytecodeOp {
+    pub fn parse_all(data: &[u8], endian: Endian) -> binrw::BinResult<Vec<TfxBytecodeOp>> {
+        let mut cur = Cursor::new(data);
+        let mut opcodes = vec![];
+
+        while (cur.position() as usize) < data.len() {
+            let op = cur.read_type::<TfxBytecodeOp>(endian)?;
+            opcodes.push(op);
+        }
+
+        Ok(opcodes)
+    }
+
+    /// Parses opcodes until the first one that fails to decode.
+    /// Returns the successfully parsed opcodes, and the byte offset and error of the failing opcode.
+    pub fn parse_partial(
+        data: &[u8],
+        endian: Endian,
+    ) -> (Vec<TfxBytecodeOp>, Option<(usize, binrw::Error)>) {
+        let mut cur = Cursor::new(data);
+        let mut opcodes = vec![];
+
+        while (cur.position() as usize) < data.len() {
+            let offset = cur.position() as usize;
+            match cur.read_type::<TfxBytecodeOp>(endian) {
+                Ok(op) => opcodes.push(op),
+                Err(e) => return (opcodes, Some((offset, e))),
+            }
+        }
+
+        (opcodes, None)
+    }
+
+    /// Encodes opcodes back into their bytecode representation
+    pub fn write_all(opcodes: &[TfxBytecodeOp], endian: Endian) -> Vec<u8> {
+        let mut data = vec![];
+        for op in opcodes {
+            op.write(&mut data, endian);
+        }
+
+        data
+    }
+
+    /// Operands are all single bytes for now, so the encoding doesn't depend on the endianness yet
+    pub fn write(&self, out: &mut Vec<u8>, _endian: Endian) {
+        out.push(self.opcode());
+        match *self {
+            TfxBytecodeOp::Permute { fields: a }
+            | TfxBytecodeOp::PushConstVec4 { constant_index: a }
+            | TfxBytecodeOp::UnkLoadConstant { constant_index: a }
+            | TfxBytecodeOp::LerpConstant { constant_start: a }
+            | TfxBytecodeOp::LerpConstantSaturated { constant_start: a }
+            | TfxBytecodeOp::Spline4Const { constant_start: a }
+            | TfxBytecodeOp::Spline8Const { constant_start: a }
+            | TfxBytecodeOp::Spline8ChainConst { constant_start: a }
+            | TfxBytecodeOp::Unk37 { constant_start: a }
+            | TfxBytecodeOp::Unk38 { unk1: a }
+            | TfxBytecodeOp::Unk39 { unk1: a }
+            | TfxBytecodeOp::Unk3a { unk1: a }
+            | TfxBytecodeOp::PushFromOutput { element: a }
+            | TfxBytecodeOp::PopOutput { element: a }
+            | TfxBytecodeOp::PopOutputMat4 { element: a }
+            | TfxBytecodeOp::PushTemp { slot: a }
+            | TfxBytecodeOp::PopTemp { slot: a }
+            | TfxBytecodeOp::SetShaderTexture { value: a, .. }
+            | TfxBytecodeOp::SetShaderSampler { value: a, .. }
+            | TfxBytecodeOp::SetShaderUav { value: a, .. }
+            | TfxBytecodeOp::Unk49 { unk1: a }
+            | TfxBytecodeOp::Unk4c { unk1: a }
+            | TfxBytecodeOp::PushSampler { index: a }
+            | TfxBytecodeOp::Unk4f { unk1: a }
+            | TfxBytecodeOp::Unk50 { unk1: a } => out.push(a),
+            TfxBytecodeOp::PushExternInputFloat { extern_, offset }
+            | TfxBytecodeOp::PushExternInputVec4 { extern_, offset }
+            | TfxBytecodeOp::PushExternInputMat4 { extern_, offset }
+            | TfxBytecodeOp::PushExternInputTexture { extern_, offset }
+            | TfxBytecodeOp::PushExternInputU32 { extern_, offset }
+            | TfxBytecodeOp::PushExternInputUav { extern_, offset } => {
+                out.extend([extern_ as u8, offset])
+            }
+            TfxBytecodeOp::Unk52 { unk1: a, unk2: b }
+            | TfxBytecodeOp::Unk53 { unk1: a, unk2: b }
+            | TfxBytecodeOp::Unk54 { unk1: a, unk2: b } => out.extend([a, b]),
+            TfxBytecodeOp::Unk4e {
+                unk1,
+                unk2,
+                unk3,
+                unk4,
+            } => out.extend([unk1, unk2, unk3, unk4]),
+            _ => {}
+        }
+    }
+
+    /// Returns the number of stack elements popped and pushed by this opcode.
+    /// Ops that modify the top of the stack in place count as popping and pushing one element
+    pub fn stack_effect(&self) -> (usize, usize) {
+        match self {
+            TfxBytecodeOp::Add
+            | TfxBytecodeOp::Subtract
+            | TfxBytecodeOp::Multiply
+            | TfxBytecodeOp::Divide
+            | TfxBytecodeOp::Multiply2
+            | TfxBytecodeOp::Add2
+            | TfxBytecodeOp::Min
+            | TfxBytecodeOp::Max
+            | TfxBytecodeOp::LessThan
+            | TfxBytecodeOp::Dot
+            | TfxBytecodeOp::Merge1_3
+            | TfxBytecodeOp::Merge2_2
+            | TfxBytecodeOp::Unk0e
+            | TfxBytecodeOp::Unk0f => (2, 1),
+
+            TfxBytecodeOp::Lerp
+            | TfxBytecodeOp::LerpSaturated
+            | TfxBytecodeOp::MultiplyAdd
+            | TfxBytecodeOp::Clamp => (3, 1),
+
+            TfxBytecodeOp::TransformVec4 => (5, 1),
+
+            TfxBytecodeOp::Spline8ChainConst { .. } => (2, 2),
+
+            TfxBytecodeOp::PushConstVec4 { .. }
+            | TfxBytecodeOp::PushExternInputFloat { .. }
+            | TfxBytecodeOp::PushExternInputVec4 { .. }
+            | TfxBytecodeOp::PushExternInputTexture { .. }
+            | TfxBytecodeOp::PushExternInputU32 { .. }
+            | TfxBytecodeOp::PushExternInputUav { .. }
+            | TfxBytecodeOp::PushFromOutput { .. }
+            | TfxBytecodeOp::PushTemp { .. }
+            | TfxBytecodeOp::Unk4c { .. }
+            | TfxBytecodeOp::PushSampler { .. }
+            | TfxBytecodeOp::Unk4e { .. }
+            | TfxBytecodeOp::Unk4f { .. }
+            | TfxBytecodeOp::Unk50 { .. }
+            | TfxBytecodeOp::Unk52 { .. }
+            | TfxBytecodeOp::Unk53 { .. }
+            | TfxBytecodeOp::Unk54 { .. } => (0, 1),
+
+            TfxBytecodeOp::PushExternInputMat4 { .. } => (0, 4),
+
+            TfxBytecodeOp::PopOutput { .. }
+            | TfxBytecodeOp::PopTemp { .. }
+            | TfxBytecodeOp::SetShaderTexture { .. }
+            | TfxBytecodeOp::SetShaderSampler { .. }
+            | TfxBytecodeOp::SetShaderUav { .. } => (1, 0),
+
+            TfxBytecodeOp::PopOutputMat4 { .. } => (4, 0),
+
+            // Not implemented by the interpreter
+            TfxBytecodeOp::Unk14
+            | TfxBytecodeOp::Unk1b
+            | TfxBytecodeOp::Unk1c
+            | TfxBytecodeOp::Unk24
+            | TfxBytecodeOp::Unk25
+            | TfxBytecodeOp::Unk26
+            | TfxBytecodeOp::Unk2c
+            | TfxBytecodeOp::Unk2d
+            | TfxBytecodeOp::Unk38 { .. }
+            | TfxBytecodeOp::Unk39 { .. }
+            | TfxBytecodeOp::Unk3a { .. }
+            | TfxBytecodeOp::Unk42
+            | TfxBytecodeOp::Unk49 { .. }
+            | TfxBytecodeOp::Unk51
+            | TfxBytecodeOp::Unk55
+            | TfxBytecodeOp::Unk56
+            | TfxBytecodeOp::Unk57
+            | TfxBytecodeOp::Unk58 => (0, 0),
+
+            // Everything else operates on the top of the stack
+            _ => (1, 1),
+        }
+    }
+
+    #[rustfmt::skip]
+    pub fn opcode(&self) -> u8 {
+        match self {
+            TfxBytecodeOp::Add => 0x01,
+            TfxBytecodeOp::Subtract => 0x02,
+            TfxBytecodeOp::Multiply => 0x03,
+            TfxBytecodeOp::Divide => 0x04,
+            TfxBytecodeOp::Multiply2 => 0x05,
+            TfxBytecodeOp::Add2 => 0x06,
+            TfxBytecodeOp::IsZero => 0x07,
+            TfxBytecodeOp::Min => 0x08,
+            TfxBytecodeOp::Max => 0x09,
+            TfxBytecodeOp::LessThan => 0x0a,
+            TfxBytecodeOp::Dot => 0x0b,
+            TfxBytecodeOp::Merge1_3 => 0x0c,
+            TfxBytecodeOp::Merge2_2 => 0x0d,
+            TfxBytecodeOp::Unk0e => 0x0e,
+            TfxBytecodeOp::Unk0f => 0x0f,
+            TfxBytecodeOp::Lerp => 0x10,
+            TfxBytecodeOp::LerpSaturated => 0x11,
+            TfxBytecodeOp::MultiplyAdd => 0x12,
+            TfxBytecodeOp::Clamp => 0x13,
+            TfxBytecodeOp::Unk14 => 0x14,
+            TfxBytecodeOp::Abs => 0x15,
+            TfxBytecodeOp::Signum => 0x16,
+            TfxBytecodeOp::Floor => 0x17,
+            TfxBytecodeOp::Ceil => 0x18,
+            TfxBytecodeOp::Round => 0x19,
+            TfxBytecodeOp::Frac => 0x1a,
+            TfxBytecodeOp::Unk1b => 0x1b,
+            TfxBytecodeOp::Unk1c => 0x1c,
+            TfxBytecodeOp::Negate => 0x1d,
+            TfxBytecodeOp::VectorRotationsSin => 0x1e,
+            TfxBytecodeOp::VectorRotationsCos => 0x1f,
+            TfxBytecodeOp::VectorRotationsSinCos => 0x20,
+            TfxBytecodeOp::PermuteExtendX => 0x21,
+            TfxBytecodeOp::Permute { .. } => 0x22,
+            TfxBytecodeOp::Saturate => 0x23,
+            TfxBytecodeOp::Unk24 => 0x24,
+            TfxBytecodeOp::Unk25 => 0x25,
+            TfxBytecodeOp::Unk26 => 0x26,
+            TfxBytecodeOp::Triangle => 0x27,
+            TfxBytecodeOp::Jitter => 0x28,
+            TfxBytecodeOp::Wander => 0x29,
+            TfxBytecodeOp::Rand => 0x2a,
+            TfxBytecodeOp::RandSmooth => 0x2b,
+            TfxBytecodeOp::Unk2c => 0x2c,
+            TfxBytecodeOp::Unk2d => 0x2d,
+            TfxBytecodeOp::TransformVec4 => 0x2e,
+            TfxBytecodeOp::Spline4Const { .. } => 0x2f,
+            TfxBytecodeOp::Spline8Const { .. } => 0x30,
+            TfxBytecodeOp::Spline8ChainConst { .. } => 0x31,
+            TfxBytecodeOp::PushConstVec4 { .. } => 0x34,
+            TfxBytecodeOp::LerpConstant { .. } => 0x35,
+            TfxBytecodeOp::LerpConstantSaturated { .. } => 0x36,
+            TfxBytecodeOp::Unk37 { .. } => 0x37,
+            TfxBytecodeOp::Unk38 { .. } => 0x38,
+            TfxBytecodeOp::Unk39 { .. } => 0x39,
+            TfxBytecodeOp::Unk3a { .. } => 0x3a,
+            TfxBytecodeOp::UnkLoadConstant { .. } => 0x3b,
+            TfxBytecodeOp::PushExternInputFloat { .. } => 0x3c,
+            TfxBytecodeOp::PushExternInputVec4 { .. } => 0x3d,
+            TfxBytecodeOp::PushExternInputMat4 { .. } => 0x3e,
+            TfxBytecodeOp::PushExternInputTexture { .. } => 0x3f,
+            TfxBytecodeOp::PushExternInputU32 { .. } => 0x40,
+            TfxBytecodeOp::PushExternInputUav { .. } => 0x41,
+            TfxBytecodeOp::Unk42 => 0x42,
+            TfxBytecodeOp::PushFromOutput { .. } => 0x43,
+            TfxBytecodeOp::PopOutput { .. } => 0x44,
+            TfxBytecodeOp::PopOutputMat4 { .. } => 0x45,
+            TfxBytecodeOp::PushTemp { .. } => 0x46,
+            TfxBytecodeOp::PopTemp { .. } => 0x47,
+            TfxBytecodeOp::SetShaderTexture { .. } => 0x48,
+            TfxBytecodeOp::Unk49 { .. } => 0x49,
+            TfxBytecodeOp::SetShaderSampler { .. } => 0x4a,
+            TfxBytecodeOp::SetShaderUav { .. } => 0x4b,
+            TfxBytecodeOp::Unk4c { .. } => 0x4c,
+            TfxBytecodeOp::PushSampler { .. } => 0x4d,
+            TfxBytecodeOp::Unk4e { .. } => 0x4e,
+            TfxBytecodeOp::Unk4f { .. } => 0x4f,
+            TfxBytecodeOp::Unk50 { .. } => 0x50,
+            TfxBytecodeOp::Unk51 => 0x51,
+            TfxBytecodeOp::Unk52 { .. } => 0x52,
+            TfxBytecodeOp::Unk53 { .. } => 0x53,
+            TfxBytecodeOp::Unk54 { .. } => 0x54,
+            TfxBytecodeOp::Unk55 => 0x55,
+            TfxBytecodeOp::Unk56 => 0x56,
+            TfxBytecodeOp::Unk57 => 0x57,
+            TfxBytecodeOp::Unk58 => 0x58,
+        }
+    }
+
+    /// Formats the opcode to assembly-like output
+    pub fn disassemble(&self, constants: Option<&[Vec4]>) -> String {
+        match self {
+            TfxBytecodeOp::Add => "add".to_string(),
+            TfxBytecodeOp::Subtract => "subtract".to_string(),
+            TfxBytecodeOp::Multiply => "multiply".to_string(),
+            TfxBytecodeOp::Divide => "divide".to_string(),
+            TfxBytecodeOp::Multiply2 => "multiply2".to_string(),
+            TfxBytecodeOp::Add2 => "add2".to_string(),
+            TfxBytecodeOp::IsZero => "is_zero".to_string(),
+            TfxBytecodeOp::Min => "min".to_string(),
+            TfxBytecodeOp::Max => "max".to_string(),
+            TfxBytecodeOp::LessThan => "less_than".to_string(),
+            TfxBytecodeOp::Dot => "dot".to_string(),
+            TfxBytecodeOp::Merge1_3 => "merge_1_3".to_string(),
+            TfxBytecodeOp::Merge2_2 => "merge_2_2".to_string(),
+            TfxBytecodeOp::Unk0e => "unk0e".to_string(),
+            TfxBytecodeOp::Unk0f => "unk0f".to_string(),
+            TfxBytecodeOp::Lerp => "lerp".to_string(),
+            TfxBytecodeOp::LerpSaturated => "lerp_saturated".to_string(), // not really used in regular bytecode
+            TfxBytecodeOp::MultiplyAdd => "multiply_add".to_string(),
+            TfxBytecodeOp::Clamp => "clamp".to_string(),
+            TfxBytecodeOp::Unk14 => "unk14".to_string(),
+            TfxBytecodeOp::Abs => "abs".to_string(),
+            TfxBytecodeOp::Signum => "signum".to_string(),
+            TfxBytecodeOp::Floor => "floor".to_string(),
+            TfxBytecodeOp::Ceil => "ceil".to_string(),
+            TfxBytecodeOp::Round => "round".to_string(),
+            TfxBytecodeOp::Frac => "frac".to_string(),
+            TfxBytecodeOp::Unk1b => "unk1b".to_string(),
+            TfxBytecodeOp::Unk1c => "unk1c".to_string(),
+            TfxBytecodeOp::Negate => "negate".to_string(),
+            TfxBytecodeOp::VectorRotationsSin => "vector_rotations_sin".to_string(),
+            TfxBytecodeOp::VectorRotationsCos => "vector_rotations_cos".to_string(),
+            TfxBytecodeOp::VectorRotationsSinCos => "vector_rotations_sin_cos".to_string(),
+            TfxBytecodeOp::PermuteExtendX => "permute_extend_x // permute(.xxxx)".to_string(),
+            TfxBytecodeOp::Permute { fields } => {
+                format!("permute({})", decode_permute_param(*fields))
+            }
+            TfxBytecodeOp::Saturate => "saturate".to_string(),
+            TfxBytecodeOp::Unk24 => "unk24".to_string(),
+            TfxBytecodeOp::Unk25 => "unk25".to_string(),
+            TfxBytecodeOp::Unk26 => "unk26".to_string(),
+            TfxBytecodeOp::Triangle => "triangle".to_string(),
+            TfxBytecodeOp::Jitter => "jitter".to_string(),
+            TfxBytecodeOp::Wander => "wander".to_string(),
+            TfxBytecodeOp::Rand => "rand".to_string(),
+            TfxBytecodeOp::RandSmooth => "rand_smooth".to_string(),
+            TfxBytecodeOp::Unk2c => "unk2c".to_string(),
+            TfxBytecodeOp::Unk2d => "unk2d".to_string(),
+            TfxBytecodeOp::TransformVec4 => "transform_vec4".to_string(),
+            TfxBytecodeOp::Spline4Const { constant_start } => {
+                format!(
+                    "spline4_const({}..={})",
+                    constant_start,
+                    *constant_start as usize + 4
+                )
+            }
+            TfxBytecodeOp::Spline8Const { constant_start } => {
+                format!(
+                    "spline8_const({}..={})",
+                    constant_start,
+                    *constant_start as usize + 9
+                )
+            }
+            TfxBytecodeOp::Spline8ChainConst { constant_start } => {
+                format!(
+                    "spline8_chain_const({}..={})",
+                    constant_start,
+                    *constant_start as usize + 9
+                )
+            }
+            TfxBytecodeOp::PushConstVec4 { constant_index } => {
+                if let Some(constants) = constants {
+                    format!(
+                        "push_const_vec4({constant_index}) // {}",
+                        constants
+                            .get(*constant_index as usize)
+                            .map(Vec4::to_string)
+                            .unwrap_or("CONSTANT OUT OF RANGE".into())
+                    )
+                } else {
+                    format!("push_const_vec4({constant_index})")
+                }
+            }
+            TfxBytecodeOp::LerpConstant { constant_start } => {
+                if let Some(constants) = constants {
+                    format!(
+                        "lerp_constant({}, {}) // a={} b={}",
+                        constant_start,
+                        constant_start + 1,
+                        constants
+                            .get(*constant_start as usize)
+                            .map(Vec4::to_string)
+                            .unwrap_or("CONSTANT OUT OF RANGE".into()),
+                        constants
+                            .get(*constant_start as usize + 1)
+                            .map(Vec4::to_string)
+                            .unwrap_or("CONSTANT OUT OF RANGE".into())
+                    )
+                } else {
+                    format!("lerp_constant({}, {})", constant_start, constant_start + 1)
+                }
+            }
+            TfxBytecodeOp::LerpConstantSaturated { constant_start } => {
+                // not really used in regular bytecode
+                format!(
+                    "lerp_constant_saturated({}, {})",
+                    constant_start,
+                    constant_start + 1
+                )
+            }
+            TfxBytecodeOp::Unk37 { constant_start } => {
+                format!(
+                    "unk37({}..={})",
+                    constant_start,
+                    *constant_start as usize + 4
+                )
+            }
+            TfxBytecodeOp::Unk38 { unk1 } => format!("unk38 unk1={unk1}"),
+            TfxBytecodeOp::Unk39 { unk1 } => format!("unk39 unk1={unk1}"),
+            TfxBytecodeOp::Unk3a { unk1 } => format!("unk3a unk1={unk1}"),
+            TfxBytecodeOp::UnkLoadConstant { constant_index } => {
+                if let Some(constants) = constants {
+                    format!(
+                        "unk_load_constant constants[{constant_index}] // {}",
+                        constants
+                            .get(*constant_index as usize)
+                            .map(Vec4::to_string)
+                            .unwrap_or("CONSTANT OUT OF RANGE".into())
+                    )
+                } else {
+                    format!("unk_load_constant constants[{constant_index}]")
+                }
+            }
+            TfxBytecodeOp::PushExternInputFloat { extern_, offset } => {
+                format!(
+                    "push_extern_input_float ({})",
+                    extern_operand(*extern_, *offset as usize * 4, TfxExternType::Float)
+                )
+            }
+            TfxBytecodeOp::PushExternInputVec4 { extern_, offset } => {
+                format!(
+                    "push_extern_input_vec4 ({})",
+                    extern_operand(*extern_, *offset as usize * 16, TfxExternType::Vec4)
+                )
+            }
+            TfxBytecodeOp::PushExternInputMat4 { extern_, offset } => {
+                format!(
+                    "push_extern_input_mat4 ({})",
+                    extern_operand(*extern_, *offset as usize * 16, TfxExternType::Mat4)
+                )
+            }
+            TfxBytecodeOp::PushExternInputTexture { extern_, offset } => {
+                format!(
+                    "push_extern_input_tex ({})",
+                    extern_operand(*extern_, *offset as usize * 8, TfxExternType::Texture)
+                )
+            }
+            TfxBytecodeOp::PushExternInputU32 { extern_, offset } => {
+                format!(
+                    "push_extern_input_u32 ({})",
+                    extern_operand(*extern_, *offset as usize * 4, TfxExternType::U32)
+                )
+            }
+            TfxBytecodeOp::PushExternInputUav { extern_, offset } => {
+                format!(
+                    "push_extern_input_uav ({})",
+                    extern_operand(*extern_, *offset as usize * 8, TfxExternType::Uav)
+                )
+            }
+            TfxBytecodeOp::Unk42 => "unk42".to_string(),
+            TfxBytecodeOp::PushFromOutput { element } => {
+                format!("push_from_output({element})")
+            }
+            TfxBytecodeOp::PopOutput { element } => {
+                format!("pop_output({element})")
+            }
+            TfxBytecodeOp::PopOutputMat4 { element } => {
+                format!("pop_output_mat4({element})")
+            }
+            TfxBytecodeOp::PushTemp { slot } => {
+                format!("push_temp({slot})")
+            }
+            TfxBytecodeOp::PopTemp { slot } => {
+                format!("pop_temp({slot})")
+            }
+            TfxBytecodeOp::SetShaderTexture { stage, slot, .. } => {
+                format!("set_shader_texture stage={stage:?} slot={slot}")
+            }
+            TfxBytecodeOp::Unk49 { unk1 } => {
+                format!("unk49 unk1={unk1}")
+            }
+            TfxBytecodeOp::SetShaderSampler { stage, slot, .. } => {
+                format!("set_shader_sampler stage={stage:?} slot={slot}")
+            }
+            TfxBytecodeOp::SetShaderUav { stage, slot, .. } => {
+                format!("set_shader_uav stage={stage:?} slot={slot}")
+            }
+            TfxBytecodeOp::Unk4c { unk1 } => format!("unk4c unk1={unk1}"),
+            TfxBytecodeOp::PushSampler { index } => {
+                format!("push_sampler index={index}")
+            }
+            TfxBytecodeOp::Unk4e {
+                unk1,
+                unk2,
+                unk3,
+                unk4,
+            } => format!("unk4e unk1={unk1} unk2={unk2} unk3={unk3} unk4={unk4}"),
+            TfxBytecodeOp::Unk4f { unk1 } => format!("unk4f unk1={unk1}"),
+            TfxBytecodeOp::Unk50 { unk1 } => format!("unk50 unk1={unk1}"),
+            TfxBytecodeOp::Unk51 => "unk51".to_string(),
+            TfxBytecodeOp::Unk52 { unk1, unk2 } => format!("unk52 unk1={unk1} unk2={unk2}"),
+            TfxBytecodeOp::Unk53 { unk1, unk2 } => format!("unk53 unk1={unk1} unk2={unk2}"),
+            TfxBytecodeOp::Unk54 { unk1, unk2 } => format!("unk54 unk1={unk1} unk2={unk2}"),
+            TfxBytecodeOp::Unk55 => "unk55".to_string(),
+            TfxBytecodeOp::Unk56 => "unk56".to_string(),
+            TfxBytecodeOp::Unk57 => "unk57".to_string(),
+            TfxBytecodeOp::Unk58 => "unk58".to_string(),
+        }
+    }
+}
+
+/// Formats an extern read as a field path if the field is known, or as `Extern+0xOFFSET` otherwise
+fn extern_operand(extern_: TfxExtern, offset: usize, ty: TfxExternType) -> String {
+    extern_
+        .field_name(offset, ty)
+        .unwrap_or_else(|| format!("{extern_:?}+0x{offset:X}"))
+}
+
+pub fn component_name(component: u8) -> char {
+    const DIMS: [char; 4] = ['x', 'y', 'z', 'w'];
+    DIMS[(component & 0b11) as usize]
+}
+
+fn decode_permute_param(param: u8) -> String {
+    let s0 = (param >> 6) & 0b11;
+    let s1 = (param >> 4) & 0b11;
+    let s2 = (param >> 2) & 0b11;
+    let s3 = param & 0b11;
+    const DIMS: [char; 4] = ['x', 'y', 'z', 'w'];
+
+    format!(
+        ".{}{}{}{}",
+        DIMS[s0 as usize], DIMS[s1 as usize], DIMS[s2 as usize], DIMS[s3 as usize]
+    )
+}
@@ -0,0 +1,230 @@
+use std::fmt::Display;
+
+use crate::opcodes::TfxBytecodeOp;
+
+/// Size of the interpreter stack
+pub const TFX_STACK_SIZE: usize = 64;
+/// Number of temp slots available to a program
+pub const TFX_TEMP_SLOTS: usize = 16;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TfxDiagnosticSeverity {
+    Warning,
+    Error,
+}
+
+#[derive(Clone, Debug)]
+pub struct TfxDiagnostic {
+    /// Index of the opcode the diagnostic applies to
+    pub ip: usize,
+    pub severity: TfxDiagnosticSeverity,
+    pub message: String,
+}
+
+impl Display for TfxDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?} at IP {}: {}", self.severity, self.ip, self.message)
+    }
+}
+
+/// Checks bytecode programs without running them
+pub struct TfxBytecodeVerifier;
+
+impl TfxBytecodeVerifier {
+    /// `cbuffer_elements` is the size of the output cbuffer in vec4s, if known
+    pub fn verify(
+        opcodes: &[TfxBytecodeOp],
+        constants_len: usize,
+        cbuffer_elements: Option<usize>,
+    ) -> Vec<TfxDiagnostic> {
+        let mut diagnostics = vec![];
+        let mut depth = 0usize;
+        let mut temp_written = [false; TFX_TEMP_SLOTS];
+
+        macro_rules! report {
+            ($ip:expr, $severity:ident, $($arg:tt)*) => {
+                diagnostics.push(TfxDiagnostic {
+                    ip: $ip,
+                    severity: TfxDiagnosticSeverity::$severity,
+                    message: format!($($arg)*),
+                })
+            };
+        }
+
+        for (ip, op) in opcodes.iter().enumerate() {
+            if let Some((start, count)) = Self::constant_range(op) {
+                if start + count > constants_len {
+                    report!(
+                        ip,
+                        Error,
+                        "Constant range {start}..{} is out of bounds ({constants_len} constants)",
+                        start + count
+                    );
+                }
+            }
+
+            if let (Some((element, count)), Some(cbuffer_elements)) =
+                (Self::output_range(op), cbuffer_elements)
+            {
+                if element + count > cbuffer_elements {
+                    report!(
+                        ip,
+                        Error,
+                        "Output element(s) {element}..{} are out of bounds (cbuffer has {cbuffer_elements} elements)",
+                        element + count
+                    );
+                }
+            }
+
+            match *op {
+                TfxBytecodeOp::PushTemp { slot } => {
+                    let slot = slot as usize;
+                    if slot >= TFX_TEMP_SLOTS {
+                        report!(ip, Error, "Temp slot {slot} is out of range");
+                    } else if !temp_written[slot] {
+                        report!(ip, Warning, "Temp slot {slot} is read before it is written");
+                    }
+                }
+                TfxBytecodeOp::PopTemp { slot } => {
+                    let slot = slot as usize;
+                    if slot >= TFX_TEMP_SLOTS {
+                        report!(ip, Error, "Temp slot {slot} is out of range");
+                    } else {
+                        temp_written[slot] = true;
+                    }
+                }
+                _ => {}
+            }
+
+            let (pops, pushes) = op.stack_effect();
+            if depth < pops {
+                report!(
+                    ip,
+                    Error,
+                    "Stack underflow, {} needs {pops} element(s) but the stack holds {depth}",
+                    op.disassemble(None)
+                );
+                // Continue as if the stack was empty, so we don't cascade errors
+                depth = 0;
+            } else {
+                depth -= pops;
+            }
+
+            depth += pushes;
+            if depth > TFX_STACK_SIZE {
+                report!(
+                    ip,
+                    Error,
+                    "Stack overflow, {depth} elements exceeds the stack size of {TFX_STACK_SIZE}"
+                );
+                depth = TFX_STACK_SIZE;
+            }
+        }
+
+        if depth != 0 {
+            report!(
+                opcodes.len().saturating_sub(1),
+                Warning,
+                "Program ends with {depth} element(s) left on the stack"
+            );
+        }
+
+        diagnostics
+    }
+
+    /// Returns the range of bytecode constants read by the opcode as (start, count)
+    fn constant_range(op: &TfxBytecodeOp) -> Option<(usize, usize)> {
+        Some(match *op {
+            TfxBytecodeOp::PushConstVec4 { constant_index }
+            | TfxBytecodeOp::UnkLoadConstant { constant_index } => (constant_index as usize, 1),
+            TfxBytecodeOp::LerpConstant { constant_start }
+            | TfxBytecodeOp::LerpConstantSaturated { constant_start } => {
+                (constant_start as usize, 2)
+            }
+            TfxBytecodeOp::Spline4Const { constant_start }
+            | TfxBytecodeOp::Unk37 { constant_start } => (constant_start as usize, 5),
+            TfxBytecodeOp::Spline8Const { constant_start }
+            | TfxBytecodeOp::Spline8ChainConst { constant_start } => (constant_start as usize, 10),
+            _ => return None,
+        })
+    }
+
+    /// Returns the range of cbuffer elements accessed by the opcode as (start, count)
+    fn output_range(op: &TfxBytecodeOp) -> Option<(usize, usize)> {
+        Some(match *op {
+            TfxBytecodeOp::PushFromOutput { element } | TfxBytecodeOp::PopOutput { element } => {
+                (element as usize, 1)
+            }
+            TfxBytecodeOp::PopOutputMat4 { element } => (element as usize, 4),
+            _ => return None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn verify(opcodes: &[TfxBytecodeOp]) -> Vec<(usize, TfxDiagnosticSeverity)> {
+        TfxBytecodeVerifier::verify(opcodes, 2, Some(4))
+            .into_iter()
+            .map(|d| (d.ip, d.severity))
+            .collect()
+    }
+
+    #[test]
+    fn push_temp() {
+        use TfxBytecodeOp::*;
+
+        // Written before it's read
+        assert!(verify(&[
+            PushConstVec4 { constant_index: 0 },
+            PopTemp { slot: 3 },
+            PushTemp { slot: 3 },
+            PopOutput { element: 0 },
+        ])
+        .is_empty());
+
+        // Read before it's written
+        assert_eq!(
+            verify(&[
+                PushTemp { slot: 3 },
+                PopOutput { element: 0 },
+                PushConstVec4 { constant_index: 0 },
+                PopTemp { slot: 3 },
+            ]),
+            [(0, TfxDiagnosticSeverity::Warning)]
+        );
+
+        // Out of range, the push still counts towards the stack depth
+        assert_eq!(
+            verify(&[
+                PushTemp {
+                    slot: TFX_TEMP_SLOTS as u8
+                },
+                PopOutput { element: 0 },
+            ]),
+            [(0, TfxDiagnosticSeverity::Error)]
+        );
+    }
+
+    #[test]
+    fn lerp_saturated_stack_effect() {
+        use TfxBytecodeOp::*;
+
+        assert!(verify(&[
+            PushConstVec4 { constant_index: 0 },
+            PushConstVec4 { constant_index: 1 },
+            PushConstVec4 { constant_index: 0 },
+            LerpSaturated,
+            LerpConstantSaturated { constant_start: 0 },
+            PopOutput { element: 0 },
+        ])
+        .is_empty());
+
+        assert_eq!(
+            verify(&[LerpSaturated, PopOutput { element: 0 }]),
+            [(0, TfxDiagnosticSeverity::Error)]
+        );
+    }
+}
@@ -21,7 +21,7 @@ public unsafe class DestinyHavok
     private struct CShape
     {
         public CArray<Vector3> Vertices;
-        public CArray<ushort> Indices;
+        public CArray<uint> Indices;
     }
     
     [DllImport("destiny_havok.dll", EntryPoint = "destinyhavok_read_shape_collection")]
@@ -30,10 +30,13 @@ public unsafe class DestinyHavok
     [DllImport("destiny_havok.dll", EntryPoint = "destinyhavok_free_shape_collection")]
     private static extern void FreeShapeCollection(CArray<CShape>* p);
 
+    [DllImport("destiny_havok.dll", EntryPoint = "destinyhavok_last_error")]
+    private static extern IntPtr LastError();
+
     public struct HavokShape
     {
         public Vector3[] Vertices;
-        public ushort[] Indices;
+        public uint[] Indices;
     }
     
     public static HavokShape[] ReadShapeCollection(byte[] data)
@@ -42,8 +45,9 @@ public unsafe class DestinyHavok
         Marshal.Copy(data, 0, bufferPtr, data.Length);
 
         var shapeCollectionPtr = ReadShapeCollection(bufferPtr, (ulong)data.Length);
+        Marshal.FreeCoTaskMem(bufferPtr);
         if (shapeCollectionPtr == null)
-            throw new Exception("Failed to read shape collection, destiny-havok returned null.");
+            throw new Exception($"Failed to read shape collection: {Marshal.PtrToStringUTF8(LastError())}");
         
         var shapeCollection = *shapeCollectionPtr;
         
@@ -52,7 +56,7 @@ public unsafe class DestinyHavok
         {
             var shape = shapeCollection.Data[i];
             var vertices = new Vector3[shape.Vertices.Length];
-            var indices = new ushort[shape.Indices.Length];
+            var indices = new uint[shape.Indices.Length];
             
             for (ulong j = 0; j < shape.Vertices.Length; j++)
             {
@@ -0,0 +1,22 @@
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    // The checked-in header is only regenerated on request, so regular builds don't touch the source tree
+    #[cfg(feature = "header")]
+    generate_header();
+}
+
+/// Regenerates `include/destiny_havok.h`, run with `cargo build --features header`
+#[cfg(feature = "header")]
+fn generate_header() {
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let config = cbindgen::Config::from_file(format!("{crate_dir}/cbindgen.toml"))
+        .expect("Failed to read cbindgen.toml");
+
+    cbindgen::generate_with_config(&crate_dir, config)
+        .expect("Failed to generate C header")
+        .write_to_file(format!("{crate_dir}/include/destiny_havok.h"));
+}
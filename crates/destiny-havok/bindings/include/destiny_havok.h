@@ -0,0 +1,411 @@
+#ifndef DESTINY_HAVOK_H
+#define DESTINY_HAVOK_H
+
+/* Generated by cbindgen from the destiny-havok bindings, do not edit */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Version of the C API. Incremented whenever a breaking change is made to it
+ */
+#define DESTINYHAVOK_API_VERSION 1
+
+/**
+ * Result of an API call. Details about failures can be retrieved with `destinyhavok_last_error`
+ */
+typedef enum DhStatus {
+  DH_STATUS_OK = 0,
+  /**
+   * A null pointer or out of range index was passed
+   */
+  DH_STATUS_INVALID_ARGUMENT = 1,
+  DH_STATUS_IO = 2,
+  /**
+   * The file structure (sections, type information) is invalid
+   */
+  DH_STATUS_INVALID_FILE = 3,
+  /**
+   * An item is missing, out of bounds or contains invalid data
+   */
+  DH_STATUS_INVALID_ITEM = 4,
+  /**
+   * The file exceeds one of the parser limits (nesting depth, element counts)
+   */
+  DH_STATUS_LIMIT_EXCEEDED = 5,
+  DH_STATUS_UNHANDLED_SHAPE = 6,
+  DH_STATUS_NO_SHAPE_COLLECTION = 7,
+  /**
+   * The library panicked. This is a bug
+   */
+  DH_STATUS_PANIC = 8,
+} DhStatus;
+
+/**
+ * A node in a shape tree. Node pointers stay valid until the tree they belong to is freed
+ */
+typedef struct CShapeNode CShapeNode;
+
+/**
+ * Spatial queries against a shape tree. Queries don't reference the tree they were created from
+ */
+typedef struct CShapeQuery CShapeQuery;
+
+/**
+ * Shape tree read from a havok file. Owns all of its nodes
+ */
+typedef struct CShapeTree CShapeTree;
+
+typedef struct CVec3 {
+  float x;
+  float y;
+  float z;
+} CVec3;
+
+typedef struct CArray_CVec3 {
+  struct CVec3 *data;
+  size_t len;
+} CArray_CVec3;
+
+typedef struct CArray_u32 {
+  uint32_t *data;
+  size_t len;
+} CArray_u32;
+
+typedef struct CShape {
+  struct CArray_CVec3 vertices;
+  struct CArray_u32 indices;
+} CShape;
+
+typedef struct CArray_CShape {
+  struct CShape *data;
+  size_t len;
+} CArray_CShape;
+
+/**
+ * A node with geometry that queries are run against
+ */
+typedef struct CLeafInfo {
+  uint32_t item;
+  bool has_filter_info;
+  uint32_t filter_info;
+  bool has_user_data;
+  uint64_t user_data;
+  /**
+   * Bounds in the space of the root nodes' parent
+   */
+  struct CVec3 aabb_min;
+  struct CVec3 aabb_max;
+  /**
+   * Number of child indices leading from the root node to this leaf
+   */
+  size_t path_len;
+} CLeafInfo;
+
+typedef struct CHit {
+  /**
+   * Index of the leaf that was hit
+   */
+  size_t leaf;
+  struct CVec3 point;
+  struct CVec3 normal;
+  float distance;
+} CHit;
+
+typedef struct CNodeInfo {
+  /**
+   * Index of the item this shape was read from
+   */
+  uint32_t item;
+  /**
+   * Column-major transform relative to the parent node
+   */
+  float transform[16];
+  /**
+   * Bounds of this node and its children, in the space of this node. Zero if `has_aabb` is false
+   */
+  bool has_aabb;
+  struct CVec3 aabb_min;
+  struct CVec3 aabb_max;
+  bool has_filter_info;
+  uint32_t filter_info;
+  bool has_user_data;
+  uint64_t user_data;
+  /**
+   * Geometry of this node itself, without its children
+   */
+  size_t vertex_count;
+  size_t index_count;
+  size_t child_count;
+} CNodeInfo;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Returns `DESTINYHAVOK_API_VERSION` of the library that was loaded, to check it against the header that was compiled against
+ */
+uint32_t destinyhavok_api_version(void);
+
+/**
+ * Reads all shapes in the first shape collection, flattened into a single mesh per shape.
+ * Returns null on failure, see `destinyhavok_last_error`
+ *
+ * # Safety
+ * `data` must point to `len` readable bytes
+ */
+struct CArray_CShape *destinyhavok_read_shape_collection(uint8_t *data, size_t len);
+
+/**
+ * # Safety
+ * `array` must be null or a collection returned by `destinyhavok_read_shape_collection` that hasn't been freed yet
+ */
+void destinyhavok_free_shape_collection(struct CArray_CShape *array);
+
+/**
+ * Message describing the error of the last failed call on this thread, or null if the last call succeeded.
+ * Only calls that return a `DhStatus`, `destinyhavok_read_shape_collection` and the spatial queries set or clear
+ * the error, other calls leave it untouched. The string is valid until the next of those calls on this thread
+ */
+const char *destinyhavok_last_error(void);
+
+/**
+ * Builds a query for all root nodes of a tree. The query must be freed with `destinyhavok_query_free`
+ *
+ * # Safety
+ * `tree` must be null or a valid tree, `out` must be valid for writes
+ */
+enum DhStatus destinyhavok_query_new(const struct CShapeTree *tree,
+                                     struct CShapeQuery **out);
+
+/**
+ * Builds a query for a single node and its children. Positions are in the space of the node's parent
+ *
+ * # Safety
+ * `node` must be null or a valid node, `out` must be valid for writes
+ */
+enum DhStatus destinyhavok_query_new_node(const struct CShapeNode *node,
+                                          struct CShapeQuery **out);
+
+/**
+ * # Safety
+ * `query` must be null or a query that hasn't been freed yet
+ */
+void destinyhavok_query_free(struct CShapeQuery *query);
+
+/**
+ * Returns 0 if `query` is null
+ *
+ * # Safety
+ * `query` must be null or a valid query
+ */
+size_t destinyhavok_query_leaf_count(const struct CShapeQuery *query);
+
+/**
+ * # Safety
+ * `query` must be null or a valid query, `out` must be valid for writes
+ */
+enum DhStatus destinyhavok_query_leaf_info(const struct CShapeQuery *query,
+                                           size_t index,
+                                           struct CLeafInfo *out);
+
+/**
+ * Copies the child indices leading from the root node to the leaf into `buffer`, the first index being that of the root node.
+ * Returns the full length of the path, or 0 if `query` is null or `index` is out of range
+ *
+ * # Safety
+ * `query` must be null or a valid query, `buffer` must be null or valid for `buffer_len` elements of writes
+ */
+size_t destinyhavok_query_leaf_path(const struct CShapeQuery *query,
+                                    size_t index,
+                                    size_t *buffer,
+                                    size_t buffer_len);
+
+/**
+ * Copies the havok class name of the leaf into `buffer` as a null-terminated string, truncating it if needed.
+ * Returns the length of the full name without the null terminator, or 0 if `query` is null or `index` is out of range
+ *
+ * # Safety
+ * `query` must be null or a valid query, `buffer` must be null or valid for `buffer_len` bytes of writes
+ */
+size_t destinyhavok_query_leaf_type_name(const struct CShapeQuery *query,
+                                         size_t index,
+                                         char *buffer,
+                                         size_t buffer_len);
+
+/**
+ * Returns true if `point` is inside any of the closed shapes. Open meshes never contain any points.
+ * Returns false on failure, see `destinyhavok_last_error`
+ *
+ * # Safety
+ * `query` must be null or a valid query
+ */
+bool destinyhavok_query_contains_point(const struct CShapeQuery *query, struct CVec3 point);
+
+/**
+ * Finds the first surface hit by a ray. Returns false if nothing was hit or on failure, in which case `out_hit` is left untouched
+ *
+ * # Safety
+ * `query` must be null or a valid query, `out_hit` must be null or valid for writes
+ */
+bool destinyhavok_query_cast_ray(const struct CShapeQuery *query,
+                                 struct CVec3 origin,
+                                 struct CVec3 direction,
+                                 float max_distance,
+                                 struct CHit *out_hit);
+
+/**
+ * Sweeps a sphere along `direction` and finds the first surface it touches. Returns false if nothing was hit or on failure
+ *
+ * # Safety
+ * `query` must be null or a valid query, `out_hit` must be null or valid for writes
+ */
+bool destinyhavok_query_cast_sphere(const struct CShapeQuery *query,
+                                    struct CVec3 origin,
+                                    struct CVec3 direction,
+                                    float radius,
+                                    float max_distance,
+                                    struct CHit *out_hit);
+
+/**
+ * Finds the closest point on the surface of any of the shapes. Returns false if there are no shapes or on failure
+ *
+ * # Safety
+ * `query` must be null or a valid query, `out_hit` must be null or valid for writes
+ */
+bool destinyhavok_query_closest_point(const struct CShapeQuery *query,
+                                      struct CVec3 point,
+                                      struct CHit *out_hit);
+
+/**
+ * Copies the indices of all leaves that overlap the given box into `buffer`.
+ * Returns the total number of overlapping leaves, which may be more than `buffer_len`, or 0 on failure
+ *
+ * # Safety
+ * `query` must be null or a valid query, `buffer` must be null or valid for `buffer_len` elements of writes
+ */
+size_t destinyhavok_query_intersects_aabb(const struct CShapeQuery *query,
+                                          struct CVec3 min,
+                                          struct CVec3 max,
+                                          size_t *buffer,
+                                          size_t buffer_len);
+
+/**
+ * Reads the shape tree of the first shape collection in a havok file. The tree must be freed with `destinyhavok_tree_free`
+ *
+ * # Safety
+ * `data` must point to `len` readable bytes, `out` must be valid for writes
+ */
+enum DhStatus destinyhavok_tree_read(const uint8_t *data,
+                                     size_t len,
+                                     struct CShapeTree **out);
+
+/**
+ * # Safety
+ * `tree` must be null or a tree returned by `destinyhavok_tree_read` that hasn't been freed yet
+ */
+void destinyhavok_tree_free(struct CShapeTree *tree);
+
+/**
+ * Returns 0 if `tree` is null
+ *
+ * # Safety
+ * `tree` must be null or a valid tree
+ */
+size_t destinyhavok_tree_root_count(const struct CShapeTree *tree);
+
+/**
+ * Returns null if `tree` is null or `index` is out of range
+ *
+ * # Safety
+ * `tree` must be null or a valid tree
+ */
+const struct CShapeNode *destinyhavok_tree_root(const struct CShapeTree *tree, size_t index);
+
+/**
+ * Returns 0 if `node` is null
+ *
+ * # Safety
+ * `node` must be null or a valid node
+ */
+size_t destinyhavok_node_child_count(const struct CShapeNode *node);
+
+/**
+ * Returns null if `node` is null or `index` is out of range
+ *
+ * # Safety
+ * `node` must be null or a valid node
+ */
+const struct CShapeNode *destinyhavok_node_child(const struct CShapeNode *node, size_t index);
+
+/**
+ * # Safety
+ * `node` must be null or a valid node, `out` must be valid for writes
+ */
+enum DhStatus destinyhavok_node_info(const struct CShapeNode *node, struct CNodeInfo *out);
+
+/**
+ * Copies the havok class name of the node (eg. `hkpConvexVerticesShape`) into `buffer` as a null-terminated string, truncating it if needed.
+ * Returns the length of the full name without the null terminator, or 0 if `node` is null
+ *
+ * # Safety
+ * `node` must be null or a valid node, `buffer` must be null or valid for `buffer_len` bytes of writes
+ */
+size_t destinyhavok_node_type_name(const struct CShapeNode *node,
+                                   char *buffer,
+                                   size_t buffer_len);
+
+/**
+ * Vertices of the node itself, in the space of the node. The number of vertices is `CNodeInfo::vertex_count`
+ *
+ * # Safety
+ * `node` must be null or a valid node
+ */
+const struct CVec3 *destinyhavok_node_vertices(const struct CShapeNode *node);
+
+/**
+ * Triangle indices of the node itself. The number of indices is `CNodeInfo::index_count`
+ *
+ * # Safety
+ * `node` must be null or a valid node
+ */
+const uint32_t *destinyhavok_node_indices(const struct CShapeNode *node);
+
+/**
+ * Merges the geometry of the node and its children into a single shape, in the space of the node's parent.
+ * The shape must be freed with `destinyhavok_free_shape`
+ *
+ * # Safety
+ * `node` must be null or a valid node, `out` must be valid for writes
+ */
+enum DhStatus destinyhavok_node_flatten(const struct CShapeNode *node,
+                                        struct CShape **out);
+
+/**
+ * Calls `callback` for the node and all of its descendants, parents before their children.
+ * The transform passed to the callback is column-major and relative to the parent of the node the walk started at
+ *
+ * # Safety
+ * `node` must be null or a valid node
+ */
+enum DhStatus destinyhavok_node_walk(const struct CShapeNode *node,
+                                     void (*callback)(const struct CShapeNode *node,
+                                                      const float (*transform)[16],
+                                                      void *user_data),
+                                     void *user_data);
+
+/**
+ * # Safety
+ * `shape` must be null or a shape returned by `destinyhavok_node_flatten` that hasn't been freed yet
+ */
+void destinyhavok_free_shape(struct CShape *shape);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* DESTINY_HAVOK_H */
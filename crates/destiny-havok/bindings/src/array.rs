@@ -16,7 +16,7 @@ impl<T> CArray<T> {
 impl<T> Drop for CArray<T> {
     fn drop(&mut self) {
         unsafe {
-            let _ = Box::from_raw(self.data);
+            let _ = Box::from_raw(std::ptr::slice_from_raw_parts_mut(self.data, self.len));
         };
     }
 }
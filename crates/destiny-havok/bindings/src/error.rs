@@ -0,0 +1,135 @@
+use std::{cell::RefCell, ffi::CString, os::raw::c_char, panic::AssertUnwindSafe};
+
+use destiny_havok::error::HavokError;
+
+/// Result of an API call. Details about failures can be retrieved with `destinyhavok_last_error`
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DhStatus {
+    Ok = 0,
+    /// A null pointer or out of range index was passed
+    InvalidArgument = 1,
+    Io = 2,
+    /// The file structure (sections, type information) is invalid
+    InvalidFile = 3,
+    /// An item is missing, out of bounds or contains invalid data
+    InvalidItem = 4,
+    /// The file exceeds one of the parser limits (nesting depth, element counts)
+    LimitExceeded = 5,
+    UnhandledShape = 6,
+    NoShapeCollection = 7,
+    /// The library panicked. This is a bug
+    Panic = 8,
+}
+
+impl From<&HavokError> for DhStatus {
+    fn from(e: &HavokError) -> Self {
+        match e {
+            HavokError::Io(_) => DhStatus::Io,
+            HavokError::Section { .. }
+            | HavokError::SectionOutOfBounds { .. }
+            | HavokError::InvalidSection { .. } => DhStatus::InvalidFile,
+            HavokError::InvalidItem { .. }
+            | HavokError::Item { .. }
+            | HavokError::OutOfBounds { .. }
+            | HavokError::InvalidData { .. } => DhStatus::InvalidItem,
+            HavokError::TooManyElements { .. }
+            | HavokError::TooDeep { .. }
+            | HavokError::TooLarge { .. } => DhStatus::LimitExceeded,
+            HavokError::UnhandledShape { .. } => DhStatus::UnhandledShape,
+            HavokError::NoShapeCollection => DhStatus::NoShapeCollection,
+        }
+    }
+}
+
+pub struct DhError {
+    pub status: DhStatus,
+    pub message: String,
+}
+
+impl DhError {
+    pub fn invalid_argument(message: impl Into<String>) -> Self {
+        Self {
+            status: DhStatus::InvalidArgument,
+            message: message.into(),
+        }
+    }
+}
+
+/// Dereferences a pointer passed to the API, failing if it's null
+///
+/// # Safety
+/// `ptr` must be null or point to a valid `T` that outlives `'a`
+pub unsafe fn arg_ref<'a, T>(ptr: *const T, name: &str) -> Result<&'a T, DhError> {
+    ptr.as_ref()
+        .ok_or_else(|| DhError::invalid_argument(format!("{name} is null")))
+}
+
+/// Writes a value to an out pointer passed to the API, failing if it's null
+///
+/// # Safety
+/// `ptr` must be null or valid for writes
+pub unsafe fn arg_write<T>(ptr: *mut T, name: &str, value: T) -> Result<(), DhError> {
+    if ptr.is_null() {
+        return Err(DhError::invalid_argument(format!("{name} is null")));
+    }
+
+    ptr.write(value);
+    Ok(())
+}
+
+impl From<HavokError> for DhError {
+    fn from(e: HavokError) -> Self {
+        Self {
+            status: (&e).into(),
+            message: e.to_string(),
+        }
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: Option<String>) {
+    LAST_ERROR.with(|e| {
+        *e.borrow_mut() = message.map(|m| CString::new(m.replace('\0', " ")).unwrap_or_default())
+    });
+}
+
+/// Runs an API call, storing its error (if any) as the last error of this thread.
+/// Panics are caught so they don't unwind into the caller
+pub fn ffi_call(f: impl FnOnce() -> Result<(), DhError>) -> DhStatus {
+    match std::panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => {
+            set_last_error(None);
+            DhStatus::Ok
+        }
+        Ok(Err(e)) => {
+            set_last_error(Some(e.message));
+            e.status
+        }
+        Err(panic) => {
+            let message = panic
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "Unknown panic".to_string());
+            set_last_error(Some(format!("destiny-havok panicked: {message}")));
+            DhStatus::Panic
+        }
+    }
+}
+
+/// Message describing the error of the last failed call on this thread, or null if the last call succeeded.
+/// Only calls that return a `DhStatus`, `destinyhavok_read_shape_collection` and the spatial queries set or clear
+/// the error, other calls leave it untouched. The string is valid until the next of those calls on this thread
+#[no_mangle]
+pub extern "C" fn destinyhavok_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| {
+        e.borrow()
+            .as_ref()
+            .map(|s| s.as_ptr())
+            .unwrap_or(std::ptr::null())
+    })
+}
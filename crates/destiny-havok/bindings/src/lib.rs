@@ -1,44 +1,111 @@
+use std::os::raw::c_char;
+
+use error::{ffi_call, DhError};
+
 pub mod array;
+pub mod error;
+pub mod query;
+pub mod tree;
+
+/// Version of the C API. Incremented whenever a breaking change is made to it
+pub const DESTINYHAVOK_API_VERSION: u32 = 1;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct CVec3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+impl From<glam::Vec3> for CVec3 {
+    fn from(v: glam::Vec3) -> Self {
+        Self {
+            x: v.x,
+            y: v.y,
+            z: v.z,
+        }
+    }
+}
+
+impl From<CVec3> for glam::Vec3 {
+    fn from(v: CVec3) -> Self {
+        glam::Vec3::new(v.x, v.y, v.z)
+    }
+}
 
 #[repr(C)]
 pub struct CShape {
-    pub vertices: array::CArray<[f32; 3]>,
-    pub indices: array::CArray<u16>,
+    pub vertices: array::CArray<CVec3>,
+    pub indices: array::CArray<u32>,
 }
 
+impl From<destiny_havok::shape_collection::Shape> for CShape {
+    fn from(shape: destiny_havok::shape_collection::Shape) -> Self {
+        CShape {
+            vertices: array::CArray::new(
+                shape
+                    .vertices
+                    .into_iter()
+                    .map(CVec3::from)
+                    .collect::<Vec<_>>()
+                    .into_boxed_slice(),
+            ),
+            indices: array::CArray::new(shape.indices.into_boxed_slice()),
+        }
+    }
+}
+
+/// Returns `DESTINYHAVOK_API_VERSION` of the library that was loaded, to check it against the header that was compiled against
+#[no_mangle]
+pub extern "C" fn destinyhavok_api_version() -> u32 {
+    DESTINYHAVOK_API_VERSION
+}
+
+/// Reads all shapes in the first shape collection, flattened into a single mesh per shape.
+/// Returns null on failure, see `destinyhavok_last_error`
+///
+/// # Safety
+/// `data` must point to `len` readable bytes
 #[no_mangle]
-pub extern "C" fn destinyhavok_read_shape_collection(
+pub unsafe extern "C" fn destinyhavok_read_shape_collection(
     data: *mut u8,
     len: usize,
 ) -> *mut array::CArray<CShape> {
-    let data = unsafe { std::slice::from_raw_parts_mut(data, len) };
-    let mut cursor = std::io::Cursor::new(data);
-    let data = destiny_havok::shape_collection::read_shape_collection(&mut cursor);
-
-    if let Ok(data) = data {
-        let data_converted = data
-            .into_iter()
-            .map(|shape| CShape {
-                vertices: array::CArray::new(
-                    shape
-                        .vertices
-                        .into_iter()
-                        .map(|v| v.to_array())
-                        .collect::<Vec<[f32; 3]>>()
-                        .into_boxed_slice(),
-                ),
-                indices: array::CArray::new(shape.indices.into_boxed_slice()),
-            })
-            .collect::<Vec<_>>();
-
-        let result = array::CArray::new(data_converted.into_boxed_slice());
-        Box::into_raw(Box::new(result))
-    } else {
-        std::ptr::null_mut()
-    }
+    let mut result = std::ptr::null_mut();
+    ffi_call(|| {
+        if data.is_null() {
+            return Err(DhError::invalid_argument("data is null"));
+        }
+
+        let data = std::slice::from_raw_parts(data, len);
+        let mut cursor = std::io::Cursor::new(data);
+        let shapes = destiny_havok::shape_collection::read_shape_collection(&mut cursor)?;
+
+        let shapes = shapes.into_iter().map(CShape::from).collect::<Vec<_>>();
+        result = Box::into_raw(Box::new(array::CArray::new(shapes.into_boxed_slice())));
+        Ok(())
+    });
+
+    result
 }
 
+/// # Safety
+/// `array` must be null or a collection returned by `destinyhavok_read_shape_collection` that hasn't been freed yet
 #[no_mangle]
-pub extern "C" fn destinyhavok_free_shape_collection(array: *mut array::CArray<CShape>) {
-    let _ = unsafe { Box::from_raw(array) };
+pub unsafe extern "C" fn destinyhavok_free_shape_collection(array: *mut array::CArray<CShape>) {
+    if !array.is_null() {
+        drop(Box::from_raw(array));
+    }
+}
+
+/// Copies `s` into `buffer` as a null-terminated string, truncating it if needed. Returns the length of `s`
+unsafe fn copy_str(s: &str, buffer: *mut c_char, buffer_len: usize) -> usize {
+    if !buffer.is_null() && buffer_len > 0 {
+        let count = s.len().min(buffer_len - 1);
+        std::ptr::copy_nonoverlapping(s.as_ptr() as *const c_char, buffer, count);
+        buffer.add(count).write(0);
+    }
+
+    s.len()
 }
@@ -0,0 +1,309 @@
+use std::os::raw::c_char;
+
+use destiny_havok::query::{ShapeHit, ShapeQuery};
+
+use crate::{
+    copy_str,
+    error::{arg_ref, arg_write, ffi_call, DhError, DhStatus},
+    tree::{node_ref, CShapeNode, CShapeTree},
+    CVec3,
+};
+
+/// Spatial queries against a shape tree. Queries don't reference the tree they were created from
+pub struct CShapeQuery {
+    query: ShapeQuery,
+}
+
+#[repr(C)]
+pub struct CHit {
+    /// Index of the leaf that was hit
+    pub leaf: usize,
+    pub point: CVec3,
+    pub normal: CVec3,
+    pub distance: f32,
+}
+
+impl From<ShapeHit> for CHit {
+    fn from(hit: ShapeHit) -> Self {
+        Self {
+            leaf: hit.leaf,
+            point: hit.point.into(),
+            normal: hit.normal.into(),
+            distance: hit.distance,
+        }
+    }
+}
+
+/// A node with geometry that queries are run against
+#[repr(C)]
+pub struct CLeafInfo {
+    pub item: u32,
+    pub has_filter_info: bool,
+    pub filter_info: u32,
+    pub has_user_data: bool,
+    pub user_data: u64,
+    /// Bounds in the space of the root nodes' parent
+    pub aabb_min: CVec3,
+    pub aabb_max: CVec3,
+    /// Number of child indices leading from the root node to this leaf
+    pub path_len: usize,
+}
+
+unsafe fn query_ref<'a>(query: *const CShapeQuery) -> Result<&'a ShapeQuery, DhError> {
+    arg_ref(query, "query").map(|q| &q.query)
+}
+
+unsafe fn write_query(out: *mut *mut CShapeQuery, query: ShapeQuery) -> Result<(), DhError> {
+    let query = Box::into_raw(Box::new(CShapeQuery { query }));
+    arg_write(out, "out", query).inspect_err(|_| drop(Box::from_raw(query)))
+}
+
+/// Builds a query for all root nodes of a tree. The query must be freed with `destinyhavok_query_free`
+///
+/// # Safety
+/// `tree` must be null or a valid tree, `out` must be valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn destinyhavok_query_new(
+    tree: *const CShapeTree,
+    out: *mut *mut CShapeQuery,
+) -> DhStatus {
+    ffi_call(|| {
+        let tree = arg_ref(tree, "tree")?;
+        write_query(out, ShapeQuery::from_nodes(&tree.roots))
+    })
+}
+
+/// Builds a query for a single node and its children. Positions are in the space of the node's parent
+///
+/// # Safety
+/// `node` must be null or a valid node, `out` must be valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn destinyhavok_query_new_node(
+    node: *const CShapeNode,
+    out: *mut *mut CShapeQuery,
+) -> DhStatus {
+    ffi_call(|| {
+        let node = node_ref(node)?;
+        write_query(out, ShapeQuery::new(node))
+    })
+}
+
+/// # Safety
+/// `query` must be null or a query that hasn't been freed yet
+#[no_mangle]
+pub unsafe extern "C" fn destinyhavok_query_free(query: *mut CShapeQuery) {
+    if !query.is_null() {
+        drop(Box::from_raw(query));
+    }
+}
+
+/// Returns 0 if `query` is null
+///
+/// # Safety
+/// `query` must be null or a valid query
+#[no_mangle]
+pub unsafe extern "C" fn destinyhavok_query_leaf_count(query: *const CShapeQuery) -> usize {
+    query_ref(query).map(|q| q.leaves().len()).unwrap_or(0)
+}
+
+/// # Safety
+/// `query` must be null or a valid query, `out` must be valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn destinyhavok_query_leaf_info(
+    query: *const CShapeQuery,
+    index: usize,
+    out: *mut CLeafInfo,
+) -> DhStatus {
+    ffi_call(|| {
+        let query = query_ref(query)?;
+        let leaf = query.leaves().get(index).ok_or_else(|| {
+            DhError::invalid_argument(format!(
+                "Leaf index {index} is out of range ({} leaves)",
+                query.leaves().len()
+            ))
+        })?;
+
+        let (aabb_min, aabb_max) = leaf.aabb();
+        arg_write(
+            out,
+            "out",
+            CLeafInfo {
+                item: leaf.item,
+                has_filter_info: leaf.filter_info.is_some(),
+                filter_info: leaf.filter_info.unwrap_or_default(),
+                has_user_data: leaf.user_data.is_some(),
+                user_data: leaf.user_data.unwrap_or_default(),
+                aabb_min: aabb_min.into(),
+                aabb_max: aabb_max.into(),
+                path_len: leaf.path.len(),
+            },
+        )
+    })
+}
+
+/// Copies the child indices leading from the root node to the leaf into `buffer`, the first index being that of the root node.
+/// Returns the full length of the path, or 0 if `query` is null or `index` is out of range
+///
+/// # Safety
+/// `query` must be null or a valid query, `buffer` must be null or valid for `buffer_len` elements of writes
+#[no_mangle]
+pub unsafe extern "C" fn destinyhavok_query_leaf_path(
+    query: *const CShapeQuery,
+    index: usize,
+    buffer: *mut usize,
+    buffer_len: usize,
+) -> usize {
+    let Some(leaf) = query_ref(query).ok().and_then(|q| q.leaves().get(index)) else {
+        return 0;
+    };
+
+    copy_indices(&leaf.path, buffer, buffer_len)
+}
+
+/// Copies the havok class name of the leaf into `buffer` as a null-terminated string, truncating it if needed.
+/// Returns the length of the full name without the null terminator, or 0 if `query` is null or `index` is out of range
+///
+/// # Safety
+/// `query` must be null or a valid query, `buffer` must be null or valid for `buffer_len` bytes of writes
+#[no_mangle]
+pub unsafe extern "C" fn destinyhavok_query_leaf_type_name(
+    query: *const CShapeQuery,
+    index: usize,
+    buffer: *mut c_char,
+    buffer_len: usize,
+) -> usize {
+    query_ref(query)
+        .ok()
+        .and_then(|q| q.leaves().get(index))
+        .map(|l| copy_str(&l.type_name, buffer, buffer_len))
+        .unwrap_or(0)
+}
+
+/// Returns true if `point` is inside any of the closed shapes. Open meshes never contain any points.
+/// Returns false on failure, see `destinyhavok_last_error`
+///
+/// # Safety
+/// `query` must be null or a valid query
+#[no_mangle]
+pub unsafe extern "C" fn destinyhavok_query_contains_point(
+    query: *const CShapeQuery,
+    point: CVec3,
+) -> bool {
+    let mut result = false;
+    ffi_call(|| {
+        result = query_ref(query)?.contains_point(point.into());
+        Ok(())
+    });
+
+    result
+}
+
+/// Finds the first surface hit by a ray. Returns false if nothing was hit or on failure, in which case `out_hit` is left untouched
+///
+/// # Safety
+/// `query` must be null or a valid query, `out_hit` must be null or valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn destinyhavok_query_cast_ray(
+    query: *const CShapeQuery,
+    origin: CVec3,
+    direction: CVec3,
+    max_distance: f32,
+    out_hit: *mut CHit,
+) -> bool {
+    let mut result = false;
+    ffi_call(|| {
+        let hit = query_ref(query)?.cast_ray(origin.into(), direction.into(), max_distance);
+        result = write_hit(hit, out_hit);
+        Ok(())
+    });
+
+    result
+}
+
+/// Sweeps a sphere along `direction` and finds the first surface it touches. Returns false if nothing was hit or on failure
+///
+/// # Safety
+/// `query` must be null or a valid query, `out_hit` must be null or valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn destinyhavok_query_cast_sphere(
+    query: *const CShapeQuery,
+    origin: CVec3,
+    direction: CVec3,
+    radius: f32,
+    max_distance: f32,
+    out_hit: *mut CHit,
+) -> bool {
+    let mut result = false;
+    ffi_call(|| {
+        let hit =
+            query_ref(query)?.cast_sphere(origin.into(), direction.into(), radius, max_distance);
+        result = write_hit(hit, out_hit);
+        Ok(())
+    });
+
+    result
+}
+
+/// Finds the closest point on the surface of any of the shapes. Returns false if there are no shapes or on failure
+///
+/// # Safety
+/// `query` must be null or a valid query, `out_hit` must be null or valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn destinyhavok_query_closest_point(
+    query: *const CShapeQuery,
+    point: CVec3,
+    out_hit: *mut CHit,
+) -> bool {
+    let mut result = false;
+    ffi_call(|| {
+        let hit = query_ref(query)?.closest_point(point.into());
+        result = write_hit(hit, out_hit);
+        Ok(())
+    });
+
+    result
+}
+
+/// Copies the indices of all leaves that overlap the given box into `buffer`.
+/// Returns the total number of overlapping leaves, which may be more than `buffer_len`, or 0 on failure
+///
+/// # Safety
+/// `query` must be null or a valid query, `buffer` must be null or valid for `buffer_len` elements of writes
+#[no_mangle]
+pub unsafe extern "C" fn destinyhavok_query_intersects_aabb(
+    query: *const CShapeQuery,
+    min: CVec3,
+    max: CVec3,
+    buffer: *mut usize,
+    buffer_len: usize,
+) -> usize {
+    let mut result = 0;
+    ffi_call(|| {
+        let indices = query_ref(query)?.intersects_aabb(min.into(), max.into());
+        result = copy_indices(&indices, buffer, buffer_len);
+        Ok(())
+    });
+
+    result
+}
+
+unsafe fn write_hit(hit: Option<ShapeHit>, out: *mut CHit) -> bool {
+    match hit {
+        Some(hit) => {
+            if !out.is_null() {
+                out.write(hit.into());
+            }
+            true
+        }
+        None => false,
+    }
+}
+
+unsafe fn copy_indices(indices: &[usize], buffer: *mut usize, buffer_len: usize) -> usize {
+    if !buffer.is_null() {
+        let count = indices.len().min(buffer_len);
+        std::ptr::copy_nonoverlapping(indices.as_ptr(), buffer, count);
+    }
+
+    indices.len()
+}
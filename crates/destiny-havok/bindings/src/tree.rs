@@ -0,0 +1,246 @@
+use std::{ffi::c_void, io::Cursor, os::raw::c_char};
+
+use destiny_havok::shape_collection::{read_shape_tree, ShapeNode};
+
+use crate::{
+    copy_str,
+    error::{arg_ref, arg_write, ffi_call, DhError, DhStatus},
+    CShape, CVec3,
+};
+
+/// Shape tree read from a havok file. Owns all of its nodes
+pub struct CShapeTree {
+    pub(crate) roots: Vec<ShapeNode>,
+}
+
+/// A node in a shape tree. Node pointers stay valid until the tree they belong to is freed
+pub struct CShapeNode {
+    _private: [u8; 0],
+}
+
+// Vertices are handed out as CVec3 without copying
+const _: () = assert!(std::mem::size_of::<glam::Vec3>() == std::mem::size_of::<CVec3>());
+
+fn node_ptr(node: &ShapeNode) -> *const CShapeNode {
+    node as *const ShapeNode as *const CShapeNode
+}
+
+pub(crate) unsafe fn node_ref<'a>(node: *const CShapeNode) -> Result<&'a ShapeNode, DhError> {
+    arg_ref(node as *const ShapeNode, "node")
+}
+
+#[repr(C)]
+pub struct CNodeInfo {
+    /// Index of the item this shape was read from
+    pub item: u32,
+    /// Column-major transform relative to the parent node
+    pub transform: [f32; 16],
+    /// Bounds of this node and its children, in the space of this node. Zero if `has_aabb` is false
+    pub has_aabb: bool,
+    pub aabb_min: CVec3,
+    pub aabb_max: CVec3,
+    pub has_filter_info: bool,
+    pub filter_info: u32,
+    pub has_user_data: bool,
+    pub user_data: u64,
+    /// Geometry of this node itself, without its children
+    pub vertex_count: usize,
+    pub index_count: usize,
+    pub child_count: usize,
+}
+
+/// Reads the shape tree of the first shape collection in a havok file. The tree must be freed with `destinyhavok_tree_free`
+///
+/// # Safety
+/// `data` must point to `len` readable bytes, `out` must be valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn destinyhavok_tree_read(
+    data: *const u8,
+    len: usize,
+    out: *mut *mut CShapeTree,
+) -> DhStatus {
+    ffi_call(|| {
+        if data.is_null() {
+            return Err(DhError::invalid_argument("data is null"));
+        }
+
+        let data = std::slice::from_raw_parts(data, len);
+        let roots = read_shape_tree(&mut Cursor::new(data))?;
+        let tree = Box::into_raw(Box::new(CShapeTree { roots }));
+        arg_write(out, "out", tree).inspect_err(|_| drop(Box::from_raw(tree)))
+    })
+}
+
+/// # Safety
+/// `tree` must be null or a tree returned by `destinyhavok_tree_read` that hasn't been freed yet
+#[no_mangle]
+pub unsafe extern "C" fn destinyhavok_tree_free(tree: *mut CShapeTree) {
+    if !tree.is_null() {
+        drop(Box::from_raw(tree));
+    }
+}
+
+/// Returns 0 if `tree` is null
+///
+/// # Safety
+/// `tree` must be null or a valid tree
+#[no_mangle]
+pub unsafe extern "C" fn destinyhavok_tree_root_count(tree: *const CShapeTree) -> usize {
+    tree.as_ref().map(|t| t.roots.len()).unwrap_or(0)
+}
+
+/// Returns null if `tree` is null or `index` is out of range
+///
+/// # Safety
+/// `tree` must be null or a valid tree
+#[no_mangle]
+pub unsafe extern "C" fn destinyhavok_tree_root(
+    tree: *const CShapeTree,
+    index: usize,
+) -> *const CShapeNode {
+    tree.as_ref()
+        .and_then(|t| t.roots.get(index))
+        .map(node_ptr)
+        .unwrap_or(std::ptr::null())
+}
+
+/// Returns 0 if `node` is null
+///
+/// # Safety
+/// `node` must be null or a valid node
+#[no_mangle]
+pub unsafe extern "C" fn destinyhavok_node_child_count(node: *const CShapeNode) -> usize {
+    node_ref(node).map(|n| n.children.len()).unwrap_or(0)
+}
+
+/// Returns null if `node` is null or `index` is out of range
+///
+/// # Safety
+/// `node` must be null or a valid node
+#[no_mangle]
+pub unsafe extern "C" fn destinyhavok_node_child(
+    node: *const CShapeNode,
+    index: usize,
+) -> *const CShapeNode {
+    node_ref(node)
+        .ok()
+        .and_then(|n| n.children.get(index))
+        .map(node_ptr)
+        .unwrap_or(std::ptr::null())
+}
+
+/// # Safety
+/// `node` must be null or a valid node, `out` must be valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn destinyhavok_node_info(
+    node: *const CShapeNode,
+    out: *mut CNodeInfo,
+) -> DhStatus {
+    ffi_call(|| {
+        let node = node_ref(node)?;
+        arg_write(
+            out,
+            "out",
+            CNodeInfo {
+                item: node.item,
+                transform: node.transform.to_cols_array(),
+                has_aabb: node.aabb.is_some(),
+                aabb_min: node.aabb.unwrap_or_default().0.into(),
+                aabb_max: node.aabb.unwrap_or_default().1.into(),
+                has_filter_info: node.filter_info.is_some(),
+                filter_info: node.filter_info.unwrap_or_default(),
+                has_user_data: node.user_data.is_some(),
+                user_data: node.user_data.unwrap_or_default(),
+                vertex_count: node.shape.vertices.len(),
+                index_count: node.shape.indices.len(),
+                child_count: node.children.len(),
+            },
+        )
+    })
+}
+
+/// Copies the havok class name of the node (eg. `hkpConvexVerticesShape`) into `buffer` as a null-terminated string, truncating it if needed.
+/// Returns the length of the full name without the null terminator, or 0 if `node` is null
+///
+/// # Safety
+/// `node` must be null or a valid node, `buffer` must be null or valid for `buffer_len` bytes of writes
+#[no_mangle]
+pub unsafe extern "C" fn destinyhavok_node_type_name(
+    node: *const CShapeNode,
+    buffer: *mut c_char,
+    buffer_len: usize,
+) -> usize {
+    node_ref(node)
+        .map(|n| copy_str(&n.type_name, buffer, buffer_len))
+        .unwrap_or(0)
+}
+
+/// Vertices of the node itself, in the space of the node. The number of vertices is `CNodeInfo::vertex_count`
+///
+/// # Safety
+/// `node` must be null or a valid node
+#[no_mangle]
+pub unsafe extern "C" fn destinyhavok_node_vertices(node: *const CShapeNode) -> *const CVec3 {
+    node_ref(node)
+        .map(|n| n.shape.vertices.as_ptr() as *const CVec3)
+        .unwrap_or(std::ptr::null())
+}
+
+/// Triangle indices of the node itself. The number of indices is `CNodeInfo::index_count`
+///
+/// # Safety
+/// `node` must be null or a valid node
+#[no_mangle]
+pub unsafe extern "C" fn destinyhavok_node_indices(node: *const CShapeNode) -> *const u32 {
+    node_ref(node)
+        .map(|n| n.shape.indices.as_ptr())
+        .unwrap_or(std::ptr::null())
+}
+
+/// Merges the geometry of the node and its children into a single shape, in the space of the node's parent.
+/// The shape must be freed with `destinyhavok_free_shape`
+///
+/// # Safety
+/// `node` must be null or a valid node, `out` must be valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn destinyhavok_node_flatten(
+    node: *const CShapeNode,
+    out: *mut *mut CShape,
+) -> DhStatus {
+    ffi_call(|| {
+        let node = node_ref(node)?;
+        let shape = Box::into_raw(Box::new(CShape::from(node.flatten())));
+        arg_write(out, "out", shape).inspect_err(|_| drop(Box::from_raw(shape)))
+    })
+}
+
+/// Calls `callback` for the node and all of its descendants, parents before their children.
+/// The transform passed to the callback is column-major and relative to the parent of the node the walk started at
+///
+/// # Safety
+/// `node` must be null or a valid node
+#[no_mangle]
+pub unsafe extern "C" fn destinyhavok_node_walk(
+    node: *const CShapeNode,
+    callback: Option<
+        extern "C" fn(node: *const CShapeNode, transform: *const [f32; 16], user_data: *mut c_void),
+    >,
+    user_data: *mut c_void,
+) -> DhStatus {
+    ffi_call(|| {
+        let node = node_ref(node)?;
+        let callback = callback.ok_or_else(|| DhError::invalid_argument("callback is null"))?;
+        node.walk(&mut |n, transform| callback(node_ptr(n), &transform.to_cols_array(), user_data));
+
+        Ok(())
+    })
+}
+
+/// # Safety
+/// `shape` must be null or a shape returned by `destinyhavok_node_flatten` that hasn't been freed yet
+#[no_mangle]
+pub unsafe extern "C" fn destinyhavok_free_shape(shape: *mut CShape) {
+    if !shape.is_null() {
+        drop(Box::from_raw(shape));
+    }
+}
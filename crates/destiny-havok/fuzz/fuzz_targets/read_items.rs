@@ -0,0 +1,15 @@
+#![no_main]
+
+use std::io::Cursor;
+
+use destiny_havok::tagfile::Tagfile;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(tagfile) = Tagfile::read(&mut Cursor::new(data)) {
+        // Every item can produce a lot of values, so only look at the first few to keep runs fast
+        for i in 0..(tagfile.items.len() as u32).min(64) {
+            let _ = tagfile.read_item(i);
+        }
+    }
+});
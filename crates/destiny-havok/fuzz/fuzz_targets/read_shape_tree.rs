@@ -0,0 +1,13 @@
+#![no_main]
+
+use std::io::Cursor;
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(shapes) = destiny_havok::shape_collection::read_shape_tree(&mut Cursor::new(data)) {
+        for shape in &shapes {
+            shape.flatten();
+        }
+    }
+});
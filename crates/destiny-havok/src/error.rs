@@ -0,0 +1,70 @@
+use crate::section::TagSectionSignature;
+
+#[derive(Debug, thiserror::Error)]
+pub enum HavokError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Failed to read {section:?} section at 0x{offset:x}: {source}")]
+    Section {
+        section: TagSectionSignature,
+        offset: u64,
+        source: binrw::Error,
+    },
+
+    #[error(
+        "{section:?} section (0x{size:x} bytes at 0x{offset:x}) extends past the end of its parent"
+    )]
+    SectionOutOfBounds {
+        section: TagSectionSignature,
+        offset: u64,
+        size: u64,
+    },
+
+    #[error("Invalid {section:?} section data at +0x{offset:x}: {message}")]
+    InvalidSection {
+        section: TagSectionSignature,
+        /// Offset relative to the start of the section data
+        offset: u64,
+        message: String,
+    },
+
+    #[error("Invalid item index {index}")]
+    InvalidItem { index: u32 },
+
+    #[error("Failed to read item {item} at 0x{offset:x}: {source}")]
+    Item {
+        item: u32,
+        offset: u64,
+        source: binrw::Error,
+    },
+
+    #[error("Item {item} (0x{size:x} bytes at 0x{offset:x}) is out of bounds of the DATA section")]
+    OutOfBounds { item: u32, offset: u64, size: u64 },
+
+    #[error("Invalid data in item {item} at 0x{offset:x}: {message}")]
+    InvalidData {
+        item: u32,
+        offset: u64,
+        message: String,
+    },
+
+    #[error("Item {item} has {count} elements, more than the limit of {limit}")]
+    TooManyElements { item: u32, count: u64, limit: u64 },
+
+    #[error("Item {item} is nested more than {limit} levels deep")]
+    TooDeep { item: u32, limit: usize },
+
+    #[error("Item {item} exceeds the limit of {limit} {what}")]
+    TooLarge {
+        item: u32,
+        what: &'static str,
+        limit: usize,
+    },
+
+    #[error("Unhandled shape type {type_name} (item {item})")]
+    UnhandledShape { item: u32, type_name: String },
+
+    #[error("No shape collections found in the given havok file")]
+    NoShapeCollection,
+}
@@ -0,0 +1,277 @@
+//! Exporters for shape collections, for inspecting collision in external tools
+
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use glam::Vec3;
+
+use crate::shape_collection::{Shape, ShapeNode};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum ExportFormat {
+    Obj,
+    /// One file per shape, since PLY files only hold a single mesh
+    Ply,
+    /// Binary glTF (.glb)
+    Gltf,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Obj => "obj",
+            ExportFormat::Ply => "ply",
+            ExportFormat::Gltf => "glb",
+        }
+    }
+}
+
+/// Flattens every shape in a collection into a mesh named after its shape index and type, eg. `3_hkpConvexVerticesShape`
+pub fn named_meshes(shapes: &[ShapeNode]) -> Vec<(String, Shape)> {
+    shapes
+        .iter()
+        .enumerate()
+        .map(|(i, node)| {
+            let type_name = if node.type_name.is_empty() {
+                "unknown"
+            } else {
+                &node.type_name
+            };
+
+            (format!("{i}_{type_name}"), node.flatten())
+        })
+        .collect()
+}
+
+/// Writes the meshes to `path` in the given format, returning the paths of all files that were written.
+/// For PLY, the mesh name is appended to the file stem of `path`
+pub fn export_meshes(
+    path: &Path,
+    format: ExportFormat,
+    meshes: &[(String, Shape)],
+) -> anyhow::Result<Vec<PathBuf>> {
+    match format {
+        ExportFormat::Obj => {
+            let mut f = std::io::BufWriter::new(std::fs::File::create(path)?);
+            write_obj(&mut f, meshes)?;
+            f.flush()?;
+            Ok(vec![path.to_path_buf()])
+        }
+        ExportFormat::Ply => {
+            let stem = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+
+            let mut paths = vec![];
+            for (name, shape) in meshes {
+                let path = path.with_file_name(format!("{stem}_{name}.ply"));
+                let mut f = std::io::BufWriter::new(std::fs::File::create(&path)?);
+                write_ply(&mut f, shape)?;
+                f.flush()?;
+                paths.push(path);
+            }
+
+            Ok(paths)
+        }
+        ExportFormat::Gltf => {
+            let mut f = std::io::BufWriter::new(std::fs::File::create(path)?);
+            write_glb(&mut f, meshes)?;
+            f.flush()?;
+            Ok(vec![path.to_path_buf()])
+        }
+    }
+}
+
+/// Writes all meshes as separate objects in a single OBJ file, in Destiny's (Z-up) coordinate space
+pub fn write_obj(w: &mut impl Write, meshes: &[(String, Shape)]) -> std::io::Result<()> {
+    let mut vertex_offset = 1;
+    for (name, shape) in meshes {
+        writeln!(w, "o {name}")?;
+        for v in &shape.vertices {
+            writeln!(w, "v {} {} {}", v.x, v.y, v.z)?;
+        }
+
+        for t in shape.indices.chunks_exact(3) {
+            writeln!(
+                w,
+                "f {} {} {}",
+                t[0] as usize + vertex_offset,
+                t[1] as usize + vertex_offset,
+                t[2] as usize + vertex_offset
+            )?;
+        }
+
+        vertex_offset += shape.vertices.len();
+    }
+
+    Ok(())
+}
+
+/// Writes a single mesh as a binary PLY file, in Destiny's (Z-up) coordinate space
+pub fn write_ply(w: &mut impl Write, shape: &Shape) -> std::io::Result<()> {
+    let triangles = shape.indices.chunks_exact(3);
+
+    writeln!(w, "ply")?;
+    writeln!(w, "format binary_little_endian 1.0")?;
+    writeln!(w, "element vertex {}", shape.vertices.len())?;
+    writeln!(w, "property float x")?;
+    writeln!(w, "property float y")?;
+    writeln!(w, "property float z")?;
+    writeln!(w, "element face {}", triangles.len())?;
+    writeln!(w, "property list uchar uint vertex_indices")?;
+    writeln!(w, "end_header")?;
+
+    for v in &shape.vertices {
+        for c in v.to_array() {
+            w.write_all(&c.to_le_bytes())?;
+        }
+    }
+
+    for t in triangles {
+        w.write_all(&[3])?;
+        for i in t {
+            w.write_all(&i.to_le_bytes())?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes all meshes to a binary glTF file, with one node per mesh.
+/// Vertices are converted to glTF's Y-up space, so importers that convert back to Z-up (eg. Blender) end up with the original coordinates
+pub fn write_glb(w: &mut impl Write, meshes: &[(String, Shape)]) -> std::io::Result<()> {
+    const ARRAY_BUFFER: u32 = 34962;
+    const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+    const FLOAT: u32 = 5126;
+    const UNSIGNED_INT: u32 = 5125;
+
+    let mut bin: Vec<u8> = vec![];
+    let mut buffer_views = vec![];
+    let mut accessors = vec![];
+    let mut gltf_meshes = vec![];
+    let mut nodes = vec![];
+
+    // Empty meshes aren't valid in glTF
+    for (name, shape) in meshes.iter().filter(|(_, s)| !s.indices.is_empty()) {
+        let vertices = shape
+            .vertices
+            .iter()
+            .map(|v| Vec3::new(v.x, v.z, -v.y))
+            .collect::<Vec<_>>();
+        let (min, max) = vertices.iter().fold(
+            (Vec3::splat(f32::INFINITY), Vec3::splat(f32::NEG_INFINITY)),
+            |(min, max), v| (min.min(*v), max.max(*v)),
+        );
+
+        let vertex_offset = bin.len();
+        for v in &vertices {
+            for c in v.to_array() {
+                bin.extend_from_slice(&c.to_le_bytes());
+            }
+        }
+        buffer_views.push(format!(
+            r#"{{"buffer":0,"byteOffset":{vertex_offset},"byteLength":{},"target":{ARRAY_BUFFER}}}"#,
+            bin.len() - vertex_offset
+        ));
+        accessors.push(format!(
+            r#"{{"bufferView":{},"componentType":{FLOAT},"count":{},"type":"VEC3","min":[{},{},{}],"max":[{},{},{}]}}"#,
+            buffer_views.len() - 1,
+            vertices.len(),
+            min.x,
+            min.y,
+            min.z,
+            max.x,
+            max.y,
+            max.z
+        ));
+
+        let index_offset = bin.len();
+        for i in &shape.indices {
+            bin.extend_from_slice(&i.to_le_bytes());
+        }
+        buffer_views.push(format!(
+            r#"{{"buffer":0,"byteOffset":{index_offset},"byteLength":{},"target":{ELEMENT_ARRAY_BUFFER}}}"#,
+            bin.len() - index_offset
+        ));
+        accessors.push(format!(
+            r#"{{"bufferView":{},"componentType":{UNSIGNED_INT},"count":{},"type":"SCALAR"}}"#,
+            buffer_views.len() - 1,
+            shape.indices.len()
+        ));
+
+        let name = json_string(name);
+        gltf_meshes.push(format!(
+            r#"{{"name":{name},"primitives":[{{"attributes":{{"POSITION":{}}},"indices":{}}}]}}"#,
+            accessors.len() - 2,
+            accessors.len() - 1
+        ));
+        nodes.push(format!(
+            r#"{{"name":{name},"mesh":{}}}"#,
+            gltf_meshes.len() - 1
+        ));
+    }
+
+    // glTF doesn't allow empty arrays, so a file without meshes only has the asset info
+    let mut json = r#"{"asset":{"version":"2.0","generator":"destiny-havok"}"#.to_string();
+    if !nodes.is_empty() {
+        let scene_nodes = (0..nodes.len())
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        json.push_str(&format!(
+            r#","scene":0,"scenes":[{{"nodes":[{scene_nodes}]}}],"nodes":[{}],"meshes":[{}],"accessors":[{}],"bufferViews":[{}],"buffers":[{{"byteLength":{}}}]"#,
+            nodes.join(","),
+            gltf_meshes.join(","),
+            accessors.join(","),
+            buffer_views.join(","),
+            bin.len()
+        ));
+    }
+    json.push('}');
+
+    // Chunks need to be 4-byte aligned
+    let mut json = json.into_bytes();
+    json.resize(json.len().next_multiple_of(4), b' ');
+    bin.resize(bin.len().next_multiple_of(4), 0);
+
+    let mut length = 12 + 8 + json.len();
+    if !bin.is_empty() {
+        length += 8 + bin.len();
+    }
+
+    w.write_all(b"glTF")?;
+    w.write_all(&2u32.to_le_bytes())?;
+    w.write_all(&(length as u32).to_le_bytes())?;
+
+    w.write_all(&(json.len() as u32).to_le_bytes())?;
+    w.write_all(b"JSON")?;
+    w.write_all(&json)?;
+
+    if !bin.is_empty() {
+        w.write_all(&(bin.len() as u32).to_le_bytes())?;
+        w.write_all(b"BIN\0")?;
+        w.write_all(&bin)?;
+    }
+
+    Ok(())
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+
+    out
+}
@@ -0,0 +1,458 @@
+//! Struct layouts of the classes read by the shape reader, which differ between Havok SDK versions
+
+use std::fmt::Display;
+
+use glam::Vec4;
+
+use crate::{
+    error::HavokError,
+    reflection::{TypeKind, TypeTable},
+    tagfile::{Tagfile, DESTINY_TYPE_NAMES, MAX_ARRAY_COUNT},
+};
+
+/// Havok SDK version, as stored in the SDKV section (eg. `20160200` for 2016.2.0)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SdkVersion {
+    pub year: u16,
+    pub release: u8,
+    pub patch: u8,
+}
+
+impl SdkVersion {
+    pub const fn new(year: u16, release: u8, patch: u8) -> Self {
+        Self {
+            year,
+            release,
+            patch,
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim_end_matches('\0').trim();
+        if s.len() != 8 || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
+        Some(Self {
+            year: s[0..4].parse().ok()?,
+            release: s[4..6].parse().ok()?,
+            patch: s[6..8].parse().ok()?,
+        })
+    }
+}
+
+impl Display for SdkVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.year, self.release, self.patch)
+    }
+}
+
+/// Byte offsets of the fields read from each class, relative to the start of the object
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layout {
+    pub name: &'static str,
+    /// Type names by type index, for files without type information
+    pub type_names: &'static [(u32, &'static str)],
+    /// Size of pointers and array references
+    pub pointer_size: u32,
+
+    /// `s_hkpShape_array`, an array of pointers to the shapes in the collection
+    pub shape_array: u32,
+
+    /// `hkpConvexShape::radius`, shared by all convex shapes
+    pub convex_radius: u32,
+    pub box_half_extents: u32,
+    /// Start of the two capsule vertices
+    pub capsule_vertices: u32,
+    /// Start of the three triangle vertices
+    pub triangle_vertices: u32,
+    pub convex_vertices_rotated_vertices: u32,
+    pub convex_vertices_plane_equations: u32,
+    /// Pointer to a `hkpConvexVerticesConnectivity`
+    pub convex_vertices_connectivity: u32,
+    pub connectivity: ConnectivityLayout,
+
+    pub list_children: u32,
+    pub list_child: ListChildLayout,
+
+    pub compound_instances: u32,
+    pub compound_instance: InstanceLayout,
+
+    pub mesh: CompressedMeshLayout,
+    pub mesh_section: MeshSectionLayout,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectivityLayout {
+    pub vertex_indices: u32,
+    pub num_vertices_per_face: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ListChildLayout {
+    pub size: u32,
+    pub shape: u32,
+    pub collision_filter_info: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InstanceLayout {
+    pub size: u32,
+    /// `hkQsTransform` (translation, rotation, scale)
+    pub transform: u32,
+    pub shape: u32,
+    pub filter_info: u32,
+    pub user_data: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompressedMeshLayout {
+    pub domain_min: u32,
+    pub domain_max: u32,
+    pub sections: u32,
+    pub primitives: u32,
+    pub shared_vertices_index: u32,
+    pub packed_vertices: u32,
+    pub shared_vertices: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MeshSectionLayout {
+    pub size: u32,
+    pub codec_parms: u32,
+    pub first_packed_vertex: u32,
+    pub shared_vertices: u32,
+    pub primitives: u32,
+    pub num_packed_vertices: u32,
+    pub num_shared_indices: u32,
+}
+
+/// Destiny 2, see the structs in [`crate::types`].
+/// This is the only layout known for files without type information, other games and versions need reflection data
+pub const DESTINY2: Layout = Layout {
+    name: "Destiny 2",
+    type_names: DESTINY_TYPE_NAMES,
+    pointer_size: 8,
+
+    shape_array: 0x0,
+
+    convex_radius: 0x28,
+    box_half_extents: 0x30,
+    capsule_vertices: 0x30,
+    triangle_vertices: 0x30,
+    convex_vertices_rotated_vertices: 0x50,
+    convex_vertices_plane_equations: 0x68,
+    // The connectivity offsets are unconfirmed, `read_convex_faces` only uses them if the data holds up
+    convex_vertices_connectivity: 0x70,
+    connectivity: ConnectivityLayout {
+        vertex_indices: 0x10,
+        num_vertices_per_face: 0x18,
+    },
+
+    list_children: 0x38,
+    list_child: ListChildLayout {
+        size: 0x20,
+        shape: 0x0,
+        collision_filter_info: 0x8,
+    },
+
+    compound_instances: 0x40,
+    compound_instance: InstanceLayout {
+        size: 0x50,
+        transform: 0x0,
+        shape: 0x30,
+        filter_info: 0x38,
+        user_data: 0x40,
+    },
+
+    mesh: CompressedMeshLayout {
+        domain_min: 0x80,
+        domain_max: 0x90,
+        sections: 0xb0,
+        primitives: 0xc0,
+        shared_vertices_index: 0xd0,
+        packed_vertices: 0xe0,
+        shared_vertices: 0xf0,
+    },
+    mesh_section: MeshSectionLayout {
+        size: 0x60,
+        codec_parms: 0x30,
+        first_packed_vertex: 0x48,
+        shared_vertices: 0x4c,
+        primitives: 0x50,
+        num_packed_vertices: 0x58,
+        num_shared_indices: 0x59,
+    },
+};
+
+impl Layout {
+    /// Replaces offsets with the ones from the reflection data of a file, where the members can be found
+    pub fn with_types(&self, types: &TypeTable) -> Layout {
+        let mut layout = self.clone();
+        let t = TypeLookup(types);
+
+        // Pointers in the DATA section are as wide as on the platform the file was made for
+        if let Some(size) = [
+            t.element_type("hkpListShape", &["childInfo"]),
+            t.element_type("hkpStaticCompoundShape", &["instances"]),
+        ]
+        .into_iter()
+        .flatten()
+        .filter_map(|typ| t.member_path(typ, &["shape"]))
+        .map(|(_, typ)| t.0.byte_size(typ))
+        .find(|size| *size == 4 || *size == 8)
+        {
+            layout.pointer_size = size;
+        }
+
+        t.set(&mut layout.convex_radius, "hkpConvexShape", &["radius"]);
+        t.set(
+            &mut layout.box_half_extents,
+            "hkpBoxShape",
+            &["halfExtents"],
+        );
+        t.set(
+            &mut layout.capsule_vertices,
+            "hkpCapsuleShape",
+            &["vertexA"],
+        );
+        t.set(
+            &mut layout.triangle_vertices,
+            "hkpTriangleShape",
+            &["vertexA"],
+        );
+        t.set(
+            &mut layout.convex_vertices_rotated_vertices,
+            "hkpConvexVerticesShape",
+            &["rotatedVertices"],
+        );
+        t.set(
+            &mut layout.convex_vertices_plane_equations,
+            "hkpConvexVerticesShape",
+            &["planeEquations"],
+        );
+        t.set(
+            &mut layout.convex_vertices_connectivity,
+            "hkpConvexVerticesShape",
+            &["connectivity"],
+        );
+        let c = &mut layout.connectivity;
+        t.set(
+            &mut c.vertex_indices,
+            "hkpConvexVerticesConnectivity",
+            &["vertexIndices"],
+        );
+        t.set(
+            &mut c.num_vertices_per_face,
+            "hkpConvexVerticesConnectivity",
+            &["numVerticesPerFace"],
+        );
+
+        t.set(&mut layout.list_children, "hkpListShape", &["childInfo"]);
+        if let Some(child) = t.element_type("hkpListShape", &["childInfo"]) {
+            let l = &mut layout.list_child;
+            t.set_size(&mut l.size, child);
+            t.set_in(&mut l.shape, child, &["shape"]);
+            t.set_in(
+                &mut l.collision_filter_info,
+                child,
+                &["collisionFilterInfo"],
+            );
+        }
+
+        t.set(
+            &mut layout.compound_instances,
+            "hkpStaticCompoundShape",
+            &["instances"],
+        );
+        if let Some(instance) = t.element_type("hkpStaticCompoundShape", &["instances"]) {
+            let l = &mut layout.compound_instance;
+            t.set_size(&mut l.size, instance);
+            t.set_in(&mut l.transform, instance, &["transform"]);
+            t.set_in(&mut l.shape, instance, &["shape"]);
+            t.set_in(&mut l.filter_info, instance, &["filterInfo"]);
+            t.set_in(&mut l.user_data, instance, &["userData"]);
+        }
+
+        let mesh = "hkpBvCompressedMeshShape";
+        let m = &mut layout.mesh;
+        t.set(&mut m.domain_min, mesh, &["tree", "domain", "min"]);
+        t.set(&mut m.domain_max, mesh, &["tree", "domain", "max"]);
+        t.set(&mut m.sections, mesh, &["tree", "sections"]);
+        t.set(&mut m.primitives, mesh, &["tree", "primitives"]);
+        t.set(
+            &mut m.shared_vertices_index,
+            mesh,
+            &["tree", "sharedVerticesIndex"],
+        );
+        t.set(&mut m.packed_vertices, mesh, &["tree", "packedVertices"]);
+        t.set(&mut m.shared_vertices, mesh, &["tree", "sharedVertices"]);
+
+        if let Some(section) = t.element_type(mesh, &["tree", "sections"]) {
+            let l = &mut layout.mesh_section;
+            t.set_size(&mut l.size, section);
+            t.set_in(&mut l.codec_parms, section, &["codecParms"]);
+            t.set_in(&mut l.first_packed_vertex, section, &["firstPackedVertex"]);
+            t.set_in(&mut l.shared_vertices, section, &["sharedVertices"]);
+            t.set_in(&mut l.primitives, section, &["primitives"]);
+            t.set_in(&mut l.num_packed_vertices, section, &["numPackedVertices"]);
+            t.set_in(&mut l.num_shared_indices, section, &["numSharedIndices"]);
+        }
+
+        layout
+    }
+}
+
+/// Helpers for finding member offsets in reflection data
+struct TypeLookup<'a>(&'a TypeTable);
+
+impl TypeLookup<'_> {
+    /// Follows a path of members, returning the offset of the last member and its type
+    fn member_path(&self, mut typ: u32, path: &[&str]) -> Option<(u32, u32)> {
+        let mut offset = 0;
+        for name in path {
+            let member = self.0.member(typ, name)?;
+            offset += member.offset;
+            typ = member.typ;
+        }
+
+        Some((offset, typ))
+    }
+
+    fn member_type(&self, class: &str, path: &[&str]) -> Option<(u32, u32)> {
+        self.member_path(self.0.find(class)?, path)
+    }
+
+    /// Element type of an array member
+    fn element_type(&self, class: &str, path: &[&str]) -> Option<u32> {
+        let (_, typ) = self.member_type(class, path)?;
+        (self.0.kind(typ) == TypeKind::Array).then(|| self.0.pointer(typ))
+    }
+
+    fn set(&self, field: &mut u32, class: &str, path: &[&str]) {
+        if let Some((offset, _)) = self.member_type(class, path) {
+            *field = offset;
+        }
+    }
+
+    fn set_in(&self, field: &mut u32, typ: u32, path: &[&str]) {
+        if let Some((offset, _)) = self.member_path(typ, path) {
+            *field = offset;
+        }
+    }
+
+    fn set_size(&self, field: &mut u32, typ: u32) {
+        match self.0.byte_size(typ) {
+            0 => {}
+            size => *field = size,
+        }
+    }
+}
+
+/// Reads fields of an object in the DATA section, at offsets from a [`Layout`]
+pub struct FieldReader<'a> {
+    tagfile: &'a Tagfile,
+    /// Item the object belongs to, for error reporting
+    item: u32,
+    /// Offset of the object in the DATA section
+    base: usize,
+}
+
+impl<'a> FieldReader<'a> {
+    pub fn new(tagfile: &'a Tagfile, item: u32, base: usize) -> Self {
+        Self {
+            tagfile,
+            item,
+            base,
+        }
+    }
+
+    /// Reader for the start of an item
+    pub fn item(tagfile: &'a Tagfile, item: u32) -> Result<Self, HavokError> {
+        Ok(Self::new(
+            tagfile,
+            item,
+            tagfile.item(item)?.offset as usize,
+        ))
+    }
+
+    /// Readers for every element of an array item. A null index produces an empty array
+    pub fn array(
+        tagfile: &'a Tagfile,
+        index: u32,
+        element_size: u32,
+    ) -> Result<Vec<Self>, HavokError> {
+        if index == 0 {
+            return Ok(vec![]);
+        }
+
+        let item = tagfile.item(index)?;
+        if item.count as u64 > MAX_ARRAY_COUNT {
+            return Err(HavokError::TooManyElements {
+                item: index,
+                count: item.count as u64,
+                limit: MAX_ARRAY_COUNT,
+            });
+        }
+
+        let size = item.count as u64 * element_size.max(1) as u64;
+        if item.offset as u64 + size > tagfile.data.len() as u64 {
+            return Err(HavokError::OutOfBounds {
+                item: index,
+                offset: item.offset as u64,
+                size,
+            });
+        }
+
+        Ok((0..item.count as usize)
+            .map(|i| {
+                Self::new(
+                    tagfile,
+                    index,
+                    item.offset as usize + i * element_size as usize,
+                )
+            })
+            .collect())
+    }
+
+    /// Reader for a nested object at `offset`
+    pub fn at(&self, offset: u32) -> Self {
+        Self::new(self.tagfile, self.item, self.base + offset as usize)
+    }
+
+    pub fn u8(&self, offset: u32) -> Result<u8, HavokError> {
+        Ok(self.uint(offset, 1)? as u8)
+    }
+
+    pub fn u32(&self, offset: u32) -> Result<u32, HavokError> {
+        Ok(self.uint(offset, 4)? as u32)
+    }
+
+    pub fn u64(&self, offset: u32) -> Result<u64, HavokError> {
+        self.uint(offset, 8)
+    }
+
+    pub fn f32(&self, offset: u32) -> Result<f32, HavokError> {
+        Ok(f32::from_bits(self.u32(offset)?))
+    }
+
+    pub fn vec4(&self, offset: u32) -> Result<Vec4, HavokError> {
+        Ok(Vec4::new(
+            self.f32(offset)?,
+            self.f32(offset + 4)?,
+            self.f32(offset + 8)?,
+            self.f32(offset + 12)?,
+        ))
+    }
+
+    /// Reads a pointer or array, which store the index of the item they reference
+    pub fn reference(&self, offset: u32) -> Result<u32, HavokError> {
+        let pointer_size = self.tagfile.layout.pointer_size as usize;
+        Ok(self.uint(offset, pointer_size)? as u32)
+    }
+
+    fn uint(&self, offset: u32, size: usize) -> Result<u64, HavokError> {
+        self.tagfile
+            .read_uint(self.item, self.base + offset as usize, size)
+    }
+}
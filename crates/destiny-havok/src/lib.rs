@@ -1,5 +1,11 @@
+pub mod error;
+pub mod export;
 pub mod index;
+pub mod layout;
+pub mod query;
+pub mod reflection;
 pub mod section;
+pub mod tagfile;
 pub mod types;
 
 pub mod shape_collection;
@@ -0,0 +1,77 @@
+use std::{io::Cursor, path::PathBuf, sync::Arc};
+
+use alkahest_pm::{package_manager, PACKAGE_MANAGER};
+use anyhow::Context;
+use clap::Parser;
+use destiny_havok::{
+    export::{export_meshes, named_meshes, ExportFormat},
+    shape_collection::read_shape_tree,
+};
+use destiny_pkg::{PackageManager, PackageVersion, TagHash};
+
+/// Exports the shapes in a Havok shape collection
+#[derive(clap::Parser, Debug)]
+#[command(author, version, about, long_about = None, disable_version_flag(true))]
+struct Args {
+    /// Path to a havok file, or a tag hash when `--packages` is given
+    input: String,
+
+    /// Path to packages directory, for reading havok tags from packages
+    #[arg(short, long)]
+    packages: Option<String>,
+
+    #[arg(short, long, value_enum, default_value_t = ExportFormat::Obj)]
+    format: ExportFormat,
+
+    /// Output path. Defaults to the name of the input file or tag in the current directory
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+
+    let (name, data) = if let Some(packages) = &args.packages {
+        let pm = PackageManager::new(packages, PackageVersion::Destiny2Lightfall)?;
+        *PACKAGE_MANAGER.write() = Some(Arc::new(pm));
+
+        let hash = TagHash(u32::from_be(
+            u32::from_str_radix(args.input.trim(), 16).context("Invalid hash format")?,
+        ));
+        let data = package_manager()
+            .read_tag(hash)
+            .with_context(|| format!("Failed to read tag {hash}"))?;
+
+        (hash.to_string(), data)
+    } else {
+        let path = PathBuf::from(&args.input);
+        let data =
+            std::fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+
+        (
+            path.file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_else(|| "havok".to_string()),
+            data,
+        )
+    };
+
+    let shapes = read_shape_tree(&mut Cursor::new(&data))?;
+    let meshes = named_meshes(&shapes);
+    for (name, shape) in &meshes {
+        println!(
+            "{name}: {} vertices, {} triangles",
+            shape.vertices.len(),
+            shape.indices.len() / 3
+        );
+    }
+
+    let output = args
+        .output
+        .unwrap_or_else(|| PathBuf::from(format!("{name}.{}", args.format.extension())));
+    for path in export_meshes(&output, args.format, &meshes)? {
+        println!("Wrote {}", path.display());
+    }
+
+    Ok(())
+}
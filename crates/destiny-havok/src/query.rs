@@ -0,0 +1,393 @@
+//! Point, ray and sweep queries against shape trees
+
+use std::collections::HashSet;
+
+use glam::{Vec3, Vec4};
+use parry3d::{
+    math::{Isometry, Point, Vector},
+    query::{self, PointQuery, Ray, RayCast},
+    shape::{Ball, Cuboid, TriMesh, TriMeshFlags},
+};
+
+use crate::shape_collection::ShapeNode;
+
+/// A node with geometry, with its transform baked into a collision mesh
+pub struct QueryLeaf {
+    /// Child indices leading from the root node to this node
+    pub path: Vec<usize>,
+    pub type_name: String,
+    pub item: u32,
+    pub filter_info: Option<u32>,
+    pub user_data: Option<u64>,
+
+    mesh: TriMesh,
+    /// Bounding planes of convex shapes, for exact containment tests
+    planes: Vec<Vec4>,
+    /// Whether every edge of the mesh is shared by exactly one other, oppositely wound triangle
+    closed: bool,
+}
+
+impl QueryLeaf {
+    pub fn aabb(&self) -> (Vec3, Vec3) {
+        let aabb = self.mesh.local_aabb();
+        (to_vec3(aabb.mins), to_vec3(aabb.maxs))
+    }
+
+    /// Whether the leaf has an inside, see [`Self::contains_point`]
+    pub fn is_closed(&self) -> bool {
+        !self.planes.is_empty() || self.closed
+    }
+
+    /// Convex shapes are tested against their planes, other meshes by their pseudo-normals.
+    /// Open meshes (eg. terrain sections) have no inside and never contain any points
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        if !self.planes.is_empty() {
+            self.planes
+                .iter()
+                .all(|p| p.truncate().dot(point) + p.w <= PLANE_EPSILON)
+        } else if self.closed {
+            self.mesh
+                .contains_point(&Isometry::identity(), &to_point(point))
+        } else {
+            false
+        }
+    }
+}
+
+/// Distance outside of a plane at which points are still considered inside of it
+const PLANE_EPSILON: f32 = 1e-4;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShapeHit {
+    /// Index of the leaf that was hit, see [`ShapeQuery::leaves`]
+    pub leaf: usize,
+    pub point: Vec3,
+    pub normal: Vec3,
+    pub distance: f32,
+}
+
+/// Spatial queries against one or more shape trees. All positions are in the space of the root nodes' parent
+pub struct ShapeQuery {
+    leaves: Vec<QueryLeaf>,
+}
+
+impl ShapeQuery {
+    pub fn new(root: &ShapeNode) -> Self {
+        Self::from_nodes(std::slice::from_ref(root))
+    }
+
+    pub fn from_nodes(roots: &[ShapeNode]) -> Self {
+        let mut leaves = vec![];
+        for (i, root) in roots.iter().enumerate() {
+            Self::collect_leaves(root, root.transform, &mut vec![i], &mut leaves);
+        }
+
+        Self { leaves }
+    }
+
+    fn collect_leaves(
+        node: &ShapeNode,
+        transform: glam::Mat4,
+        path: &mut Vec<usize>,
+        leaves: &mut Vec<QueryLeaf>,
+    ) {
+        if !node.shape.indices.is_empty() {
+            let mut shape = node.shape.clone();
+            shape.apply_transform(transform);
+
+            // Planes transform with the inverse transpose, which keeps them perpendicular under non-uniform scale
+            let plane_transform = transform.inverse().transpose();
+            let planes = node
+                .planes
+                .iter()
+                .map(|p| {
+                    let p = plane_transform * *p;
+                    p / p.truncate().length()
+                })
+                .collect();
+
+            leaves.push(QueryLeaf {
+                path: path.clone(),
+                type_name: node.type_name.clone(),
+                item: node.item,
+                filter_info: node.filter_info,
+                user_data: node.user_data,
+                mesh: TriMesh::with_flags(
+                    shape.vertices.iter().map(|v| to_point(*v)).collect(),
+                    shape
+                        .indices
+                        .chunks_exact(3)
+                        .map(|t| [t[0], t[1], t[2]])
+                        .collect(),
+                    TriMeshFlags::ORIENTED | TriMeshFlags::DELETE_DEGENERATE_TRIANGLES,
+                ),
+                planes,
+                closed: is_closed(&shape.indices),
+            });
+        }
+
+        for (i, child) in node.children.iter().enumerate() {
+            path.push(i);
+            Self::collect_leaves(child, transform * child.transform, path, leaves);
+            path.pop();
+        }
+    }
+
+    pub fn leaves(&self) -> &[QueryLeaf] {
+        &self.leaves
+    }
+
+    /// Returns true if `point` is inside any of the closed shapes, see [`QueryLeaf::contains_point`]
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        self.leaves.iter().any(|l| l.contains_point(point))
+    }
+
+    /// Returns the indices of all leaves that contain `point`
+    pub fn leaves_containing(&self, point: Vec3) -> Vec<usize> {
+        self.leaves
+            .iter()
+            .enumerate()
+            .filter(|(_, l)| l.contains_point(point))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Finds the first surface hit by a ray. `direction` doesn't need to be normalized
+    pub fn cast_ray(&self, origin: Vec3, direction: Vec3, max_distance: f32) -> Option<ShapeHit> {
+        let direction = direction.try_normalize()?;
+        let ray = Ray::new(to_point(origin), to_vector(direction));
+
+        self.leaves
+            .iter()
+            .enumerate()
+            .filter_map(|(i, l)| {
+                let hit = l.mesh.cast_ray_and_get_normal(
+                    &Isometry::identity(),
+                    &ray,
+                    max_distance,
+                    true,
+                )?;
+
+                Some(ShapeHit {
+                    leaf: i,
+                    point: origin + direction * hit.toi,
+                    normal: to_vec3(hit.normal),
+                    distance: hit.toi,
+                })
+            })
+            .min_by(|a, b| a.distance.total_cmp(&b.distance))
+    }
+
+    /// Sweeps a sphere along `direction` and finds the first surface it touches
+    pub fn cast_sphere(
+        &self,
+        origin: Vec3,
+        direction: Vec3,
+        radius: f32,
+        max_distance: f32,
+    ) -> Option<ShapeHit> {
+        let direction = direction.try_normalize()?;
+        let ball = Ball::new(radius);
+        let ball_pos = Isometry::translation(origin.x, origin.y, origin.z);
+
+        self.leaves
+            .iter()
+            .enumerate()
+            .filter_map(|(i, l)| {
+                let toi = query::time_of_impact(
+                    &ball_pos,
+                    &to_vector(direction),
+                    &ball,
+                    &Isometry::identity(),
+                    &Vector::zeros(),
+                    &l.mesh,
+                    max_distance,
+                    true,
+                )
+                .ok()??;
+
+                Some(ShapeHit {
+                    leaf: i,
+                    point: to_vec3(toi.witness2.coords),
+                    normal: to_vec3(toi.normal2.into_inner()),
+                    distance: toi.toi,
+                })
+            })
+            .min_by(|a, b| a.distance.total_cmp(&b.distance))
+    }
+
+    /// Finds the closest point on the surface of any of the shapes
+    pub fn closest_point(&self, point: Vec3) -> Option<ShapeHit> {
+        let p = to_point(point);
+
+        self.leaves
+            .iter()
+            .enumerate()
+            .map(|(i, l)| {
+                let projection = l.mesh.project_point(&Isometry::identity(), &p, false);
+                let closest = to_vec3(projection.point.coords);
+
+                ShapeHit {
+                    leaf: i,
+                    point: closest,
+                    normal: (point - closest).normalize_or_zero(),
+                    distance: closest.distance(point),
+                }
+            })
+            .min_by(|a, b| a.distance.total_cmp(&b.distance))
+    }
+
+    /// Returns the indices of all leaves that overlap the given box, including closed leaves that fully contain it
+    pub fn intersects_aabb(&self, min: Vec3, max: Vec3) -> Vec<usize> {
+        let center = (min + max) / 2.0;
+        let cuboid = Cuboid::new(to_vector((max - min) / 2.0));
+        let cuboid_pos = Isometry::translation(center.x, center.y, center.z);
+
+        self.leaves
+            .iter()
+            .enumerate()
+            .filter(|(_, l)| {
+                query::intersection_test(&Isometry::identity(), &l.mesh, &cuboid_pos, &cuboid)
+                    .unwrap_or_else(|_| {
+                        // Fall back to a bounds check if parry can't test this pair
+                        let (lmin, lmax) = l.aabb();
+                        lmin.cmple(max).all() && lmax.cmpge(min).all()
+                    })
+                    // The test only looks at the surface, which misses boxes that lie completely inside of a leaf
+                    || l.contains_point(center)
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+/// Checks that every directed edge has exactly one matching edge going the other way,
+/// which means the mesh is closed and consistently wound
+fn is_closed(indices: &[u32]) -> bool {
+    let mut edges = HashSet::new();
+    for t in indices.chunks_exact(3) {
+        for (a, b) in [(t[0], t[1]), (t[1], t[2]), (t[2], t[0])] {
+            if !edges.insert((a, b)) {
+                return false;
+            }
+        }
+    }
+
+    !edges.is_empty() && edges.iter().all(|&(a, b)| edges.contains(&(b, a)))
+}
+
+fn to_point(v: Vec3) -> Point<f32> {
+    Point::new(v.x, v.y, v.z)
+}
+
+fn to_vector(v: Vec3) -> Vector<f32> {
+    Vector::new(v.x, v.y, v.z)
+}
+
+fn to_vec3(v: impl Into<[f32; 3]>) -> Vec3 {
+    Vec3::from_array(v.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Mat4;
+
+    use super::*;
+    use crate::shape_collection::Shape;
+
+    fn box_query() -> ShapeQuery {
+        ShapeQuery::new(&ShapeNode::new(
+            "hkpBoxShape",
+            1,
+            Shape::cuboid(Vec3::ONE),
+            vec![],
+        ))
+    }
+
+    #[test]
+    fn cast_ray_hits_box() {
+        let query = box_query();
+
+        let hit = query
+            .cast_ray(Vec3::new(-5.0, 0.0, 0.0), Vec3::X * 2.0, 100.0)
+            .unwrap();
+        assert_eq!(hit.leaf, 0);
+        assert!((hit.distance - 4.0).abs() < 1e-4);
+        assert!(hit.point.distance(Vec3::new(-1.0, 0.0, 0.0)) < 1e-4);
+        assert!(hit.normal.distance(Vec3::NEG_X) < 1e-4);
+
+        assert!(query
+            .cast_ray(Vec3::new(-5.0, 0.0, 0.0), Vec3::X, 3.0)
+            .is_none());
+        assert!(query
+            .cast_ray(Vec3::new(-5.0, 0.0, 0.0), Vec3::Y, 100.0)
+            .is_none());
+        assert!(query.cast_ray(Vec3::ZERO, Vec3::ZERO, 100.0).is_none());
+    }
+
+    #[test]
+    fn contains_point_in_box() {
+        let query = box_query();
+        assert!(query.leaves()[0].is_closed());
+
+        assert!(query.contains_point(Vec3::ZERO));
+        assert!(query.contains_point(Vec3::splat(0.9)));
+        assert!(!query.contains_point(Vec3::new(2.0, 0.0, 0.0)));
+        assert!(!query.contains_point(Vec3::new(0.0, -1.5, 0.5)));
+        assert_eq!(query.leaves_containing(Vec3::ZERO), vec![0]);
+    }
+
+    #[test]
+    fn contains_point_follows_transform() {
+        let mut child = ShapeNode::new("hkpBoxShape", 2, Shape::cuboid(Vec3::ONE), vec![]);
+        child.transform = Mat4::from_translation(Vec3::new(10.0, 0.0, 0.0));
+        let query = ShapeQuery::new(&ShapeNode::new(
+            "hkpStaticCompoundShape",
+            1,
+            Shape::default(),
+            vec![child],
+        ));
+
+        assert_eq!(query.leaves()[0].path, vec![0, 0]);
+        assert!(query.contains_point(Vec3::new(10.5, 0.0, 0.0)));
+        assert!(!query.contains_point(Vec3::ZERO));
+    }
+
+    #[test]
+    fn open_mesh_contains_nothing() {
+        let mut shape = Shape::cuboid(Vec3::ONE);
+        // Remove the +X face
+        shape.indices.truncate(shape.indices.len() - 6);
+        let query = ShapeQuery::new(&ShapeNode::new(
+            "hkpBvCompressedMeshShape",
+            1,
+            shape,
+            vec![],
+        ));
+
+        assert!(!query.leaves()[0].is_closed());
+        assert!(!query.contains_point(Vec3::ZERO));
+        // Surface queries still work on open meshes
+        assert!(query
+            .cast_ray(Vec3::new(-5.0, 0.0, 0.0), Vec3::X, 100.0)
+            .is_some());
+    }
+
+    #[test]
+    fn intersects_aabb_with_box() {
+        let query = box_query();
+
+        assert_eq!(
+            query.intersects_aabb(Vec3::splat(0.5), Vec3::splat(3.0)),
+            vec![0]
+        );
+        // Fully inside of the box, without touching its surface
+        assert_eq!(
+            query.intersects_aabb(Vec3::splat(-0.5), Vec3::splat(0.5)),
+            vec![0]
+        );
+        assert!(query
+            .intersects_aabb(Vec3::splat(2.0), Vec3::splat(3.0))
+            .is_empty());
+    }
+}
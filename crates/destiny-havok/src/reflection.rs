@@ -0,0 +1,396 @@
+//! Type reflection data from the TYPE section of a tagfile
+
+use bitflags::bitflags;
+
+use crate::{error::HavokError, section::TagSectionSignature};
+
+bitflags! {
+    /// Properties present in a type body
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub struct TypeBodyFlags: u32 {
+        const SUB_TYPE = 0x1;
+        const POINTER = 0x2;
+        const VERSION = 0x4;
+        const BYTE_SIZE = 0x8;
+        const ABSTRACT_VALUE = 0x10;
+        const MEMBERS = 0x20;
+        const INTERFACES = 0x40;
+        const ATTRIBUTE = 0x80;
+    }
+}
+
+/// Storage format of a type, decoded from its sub type flags
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeKind {
+    Void,
+    Opaque,
+    Bool,
+    String,
+    Int {
+        signed: bool,
+    },
+    Float,
+    Pointer,
+    Record,
+    Array,
+    /// Fixed-size inline array
+    Tuple(u32),
+}
+
+impl TypeKind {
+    pub fn from_sub_type(sub_type: u32) -> Self {
+        match sub_type & 0x1f {
+            0 => Self::Void,
+            2 => Self::Bool,
+            3 => Self::String,
+            4 => Self::Int {
+                signed: (sub_type & 0x200) != 0,
+            },
+            5 => Self::Float,
+            6 => Self::Pointer,
+            7 => Self::Record,
+            8 if (sub_type & 0x20) != 0 => Self::Tuple(sub_type >> 8),
+            8 => Self::Array,
+            _ => Self::Opaque,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TypeTemplate {
+    /// Parameter name, prefixed with `t` for type parameters and `v` for value parameters
+    pub name: String,
+    /// Type index for type parameters, the value itself for value parameters
+    pub value: u64,
+}
+
+impl TypeTemplate {
+    pub fn is_type(&self) -> bool {
+        self.name.starts_with('t')
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TypeMember {
+    pub name: String,
+    pub flags: u32,
+    pub offset: u32,
+    pub typ: u32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TypeInfo {
+    pub name: String,
+    pub templates: Vec<TypeTemplate>,
+
+    pub parent: u32,
+    pub flags: TypeBodyFlags,
+    pub sub_type: u32,
+    /// Pointee/element type for pointers, arrays and tuples
+    pub pointer: u32,
+    pub version: u32,
+    pub byte_size: u32,
+    pub alignment: u32,
+    pub abstract_value: u32,
+    /// Members declared by this type, without inherited members
+    pub members: Vec<TypeMember>,
+    pub interfaces: Vec<(u32, u32)>,
+    pub attribute: Option<String>,
+}
+
+/// Type table of a tagfile. Index 0 is the null type
+#[derive(Debug, Clone, Default)]
+pub struct TypeTable {
+    pub types: Vec<TypeInfo>,
+}
+
+impl TypeTable {
+    /// Parses the type table from the TSTR/TST1, TNAM/TNA1, FSTR/FST1 and TBOD/TBDY sections
+    pub fn read(
+        type_strings: &[u8],
+        type_names: &[u8],
+        field_strings: &[u8],
+        type_bodies: &[u8],
+    ) -> Result<Self, HavokError> {
+        let type_strings = split_strings(type_strings);
+        let field_strings = split_strings(field_strings);
+
+        // Reads a type string index and looks it up
+        let type_string = |reader: &mut PackedReader| -> Result<String, HavokError> {
+            let index = reader.read()?;
+            type_strings
+                .get(index as usize)
+                .cloned()
+                .ok_or_else(|| reader.error(format!("Type string index {index} is out of range")))
+        };
+
+        let mut reader = PackedReader::new(TagSectionSignature::TypeNames, type_names);
+        let count = reader.read()? as usize;
+        if count > type_names.len() {
+            return Err(reader.error(format!("Type name table claims {count} types")));
+        }
+
+        let mut types = vec![TypeInfo::default(); count];
+        for t in types.iter_mut().skip(1) {
+            t.name = type_string(&mut reader)?;
+            let template_count = reader.read()?;
+            for _ in 0..template_count {
+                t.templates.push(TypeTemplate {
+                    name: type_string(&mut reader)?,
+                    value: reader.read()?,
+                });
+            }
+        }
+
+        let mut reader = PackedReader::new(TagSectionSignature::TypeBodies, type_bodies);
+        while !reader.is_empty() {
+            let index = reader.read()? as usize;
+            if index == 0 {
+                continue;
+            }
+
+            let t = types.get_mut(index).ok_or_else(|| {
+                reader.error(format!("Type body references invalid type {index}"))
+            })?;
+
+            t.parent = reader.read()? as u32;
+            t.flags = TypeBodyFlags::from_bits_retain(reader.read()? as u32);
+
+            if t.flags.contains(TypeBodyFlags::SUB_TYPE) {
+                t.sub_type = reader.read()? as u32;
+            }
+
+            if t.flags.contains(TypeBodyFlags::POINTER) {
+                t.pointer = reader.read()? as u32;
+            }
+
+            if t.flags.contains(TypeBodyFlags::VERSION) {
+                t.version = reader.read()? as u32;
+            }
+
+            if t.flags.contains(TypeBodyFlags::BYTE_SIZE) {
+                t.byte_size = reader.read()? as u32;
+                t.alignment = reader.read()? as u32;
+            }
+
+            if t.flags.contains(TypeBodyFlags::ABSTRACT_VALUE) {
+                t.abstract_value = reader.read()? as u32;
+            }
+
+            if t.flags.contains(TypeBodyFlags::MEMBERS) {
+                let member_count = reader.read()?;
+                for _ in 0..member_count {
+                    let name = reader.read()?;
+                    t.members.push(TypeMember {
+                        name: field_strings.get(name as usize).cloned().ok_or_else(|| {
+                            reader.error(format!("Field string index {name} is out of range"))
+                        })?,
+                        flags: reader.read()? as u32,
+                        offset: reader.read()? as u32,
+                        typ: reader.read()? as u32,
+                    });
+                }
+            }
+
+            if t.flags.contains(TypeBodyFlags::INTERFACES) {
+                let interface_count = reader.read()?;
+                for _ in 0..interface_count {
+                    t.interfaces
+                        .push((reader.read()? as u32, reader.read()? as u32));
+                }
+            }
+
+            if t.flags.contains(TypeBodyFlags::ATTRIBUTE) {
+                t.attribute = Some(type_string(&mut reader)?);
+            }
+        }
+
+        Ok(Self { types })
+    }
+
+    /// Builds a table that only has type names, for files without type information
+    pub fn from_names(names: &[(u32, &str)]) -> Self {
+        let count = names
+            .iter()
+            .map(|(i, _)| *i as usize + 1)
+            .max()
+            .unwrap_or_default();
+
+        let mut types = vec![TypeInfo::default(); count];
+        for (i, name) in names {
+            types[*i as usize].name = name.to_string();
+        }
+
+        Self { types }
+    }
+
+    pub fn get(&self, index: u32) -> Option<&TypeInfo> {
+        if index == 0 {
+            return None;
+        }
+
+        self.types.get(index as usize)
+    }
+
+    pub fn name(&self, index: u32) -> Option<&str> {
+        self.get(index)
+            .map(|t| t.name.as_str())
+            .filter(|n| !n.is_empty())
+    }
+
+    /// Finds the index of the first type with the given name
+    pub fn find(&self, name: &str) -> Option<u32> {
+        self.types
+            .iter()
+            .position(|t| t.name == name)
+            .map(|i| i as u32)
+    }
+
+    /// Iterates over a type and its parents, starting with the type itself
+    pub fn hierarchy(&self, index: u32) -> impl Iterator<Item = (u32, &TypeInfo)> {
+        let mut current = index;
+        // Guard against cyclic parent chains
+        let mut remaining = self.types.len();
+        std::iter::from_fn(move || {
+            let t = self.get(current)?;
+            if remaining == 0 {
+                return None;
+            }
+            remaining -= 1;
+
+            let index = current;
+            current = t.parent;
+            Some((index, t))
+        })
+    }
+
+    /// Does `index` have `name` as its own name or as the name of one of its parents?
+    pub fn is_a(&self, index: u32, name: &str) -> bool {
+        self.hierarchy(index).any(|(_, t)| t.name == name)
+    }
+
+    /// Finds the first type in the hierarchy of `index` that has `flag` set
+    fn resolve(&self, index: u32, flag: TypeBodyFlags) -> Option<&TypeInfo> {
+        self.hierarchy(index)
+            .map(|(_, t)| t)
+            .find(|t| t.flags.contains(flag))
+    }
+
+    pub fn sub_type(&self, index: u32) -> u32 {
+        self.resolve(index, TypeBodyFlags::SUB_TYPE)
+            .map(|t| t.sub_type)
+            .unwrap_or_default()
+    }
+
+    pub fn kind(&self, index: u32) -> TypeKind {
+        TypeKind::from_sub_type(self.sub_type(index))
+    }
+
+    /// Pointee/element type of a pointer, array or tuple
+    pub fn pointer(&self, index: u32) -> u32 {
+        self.resolve(index, TypeBodyFlags::POINTER)
+            .map(|t| t.pointer)
+            .unwrap_or_default()
+    }
+
+    pub fn byte_size(&self, index: u32) -> u32 {
+        self.resolve(index, TypeBodyFlags::BYTE_SIZE)
+            .map(|t| t.byte_size)
+            .unwrap_or_default()
+    }
+
+    /// All members of a type, including inherited members. Parent members come first
+    pub fn all_members(&self, index: u32) -> Vec<&TypeMember> {
+        let mut hierarchy = self.hierarchy(index).map(|(_, t)| t).collect::<Vec<_>>();
+        hierarchy.reverse();
+
+        hierarchy.into_iter().flat_map(|t| &t.members).collect()
+    }
+
+    /// Looks up a member by name, including inherited members
+    pub fn member(&self, index: u32, name: &str) -> Option<&TypeMember> {
+        self.hierarchy(index)
+            .flat_map(|(_, t)| &t.members)
+            .find(|m| m.name == name)
+    }
+}
+
+fn split_strings(data: &[u8]) -> Vec<String> {
+    let mut strings = data
+        .split(|&b| b == 0)
+        .map(|s| String::from_utf8_lossy(s).to_string())
+        .collect::<Vec<_>>();
+
+    // The section ends with a terminator (and possibly padding), which isn't a string
+    while strings.last().map(|s| s.is_empty()).unwrap_or(false) {
+        strings.pop();
+    }
+
+    strings
+}
+
+/// Reads the variable-length integers used by the type sections
+pub struct PackedReader<'a> {
+    /// Section the data was read from, for error reporting
+    section: TagSectionSignature,
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> PackedReader<'a> {
+    pub fn new(section: TagSectionSignature, data: &'a [u8]) -> Self {
+        Self {
+            section,
+            data,
+            pos: 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    /// Creates an error at the current position
+    pub fn error(&self, message: String) -> HavokError {
+        HavokError::InvalidSection {
+            section: self.section,
+            offset: self.pos as u64,
+            message,
+        }
+    }
+
+    fn read_u8(&mut self) -> Result<u8, HavokError> {
+        let v = *self
+            .data
+            .get(self.pos)
+            .ok_or_else(|| self.error("Unexpected end of packed integer data".to_string()))?;
+        self.pos += 1;
+        Ok(v)
+    }
+
+    /// Reads `count` more bytes and appends them to `v`, big endian
+    fn read_be(&mut self, mut v: u64, count: usize) -> Result<u64, HavokError> {
+        for _ in 0..count {
+            v = (v << 8) | self.read_u8()? as u64;
+        }
+
+        Ok(v)
+    }
+
+    pub fn read(&mut self) -> Result<u64, HavokError> {
+        let b = self.read_u8()? as u64;
+        if (b & 0x80) == 0 {
+            return Ok(b);
+        }
+
+        Ok(match b >> 3 {
+            0x10..=0x17 => self.read_be(b, 1)? & 0x3fff,
+            0x18..=0x1b => self.read_be(b, 2)? & 0x1fffff,
+            0x1c => self.read_be(b, 3)? & 0x7ffffff,
+            0x1d => self.read_be(b, 4)? & 0x7ffffffff,
+            0x1e => self.read_be(b, 7)? & 0x7ffffffffffffff,
+            0x1f if (b & 0x7) == 0 => self.read_be(0, 8)?,
+            _ => return Err(self.error(format!("Invalid packed integer prefix 0x{b:x}"))),
+        })
+    }
+}
@@ -3,6 +3,7 @@ use binrw::binread;
 #[derive(Debug)]
 #[binread(big)]
 pub struct TagSection {
+    #[br(assert(flags_and_size & 0x3fffffff >= 8, "Section size 0x{:x} is smaller than its header", flags_and_size & 0x3fffffff))]
     pub flags_and_size: u32,
 
     #[br(calc((flags_and_size as usize & 0x3fffffff) - 8))]
@@ -24,7 +25,7 @@ impl TagSection {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[binread(big)]
 pub enum TagSectionSignature {
     #[br(magic = b"TAG0")]
@@ -47,4 +48,46 @@ pub enum TagSectionSignature {
 
     #[br(magic = b"TCRF")]
     Tcrf,
+
+    #[br(magic = b"TYPE")]
+    Type,
+
+    #[br(magic = b"TPTR")]
+    TypePointers,
+
+    #[br(magic = b"TSTR")]
+    TypeStrings,
+
+    /// Newer variant of TSTR
+    #[br(magic = b"TST1")]
+    TypeStrings1,
+
+    #[br(magic = b"TNAM")]
+    TypeNames,
+
+    /// Newer variant of TNAM
+    #[br(magic = b"TNA1")]
+    TypeNames1,
+
+    #[br(magic = b"FSTR")]
+    FieldStrings,
+
+    /// Newer variant of FSTR
+    #[br(magic = b"FST1")]
+    FieldStrings1,
+
+    #[br(magic = b"TBOD")]
+    TypeBodies,
+
+    /// Newer variant of TBOD
+    #[br(magic = b"TBDY")]
+    TypeBodies1,
+
+    #[br(magic = b"THSH")]
+    TypeHashes,
+
+    #[br(magic = b"TPAD")]
+    TypePadding,
+
+    Unknown([u8; 4]),
 }
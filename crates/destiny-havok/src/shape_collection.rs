@@ -1,18 +1,24 @@
-use std::io::{Read, Seek, SeekFrom};
+use std::{
+    f32::consts::PI,
+    io::{Read, Seek},
+};
 
-use anyhow::Context;
-use binrw::{binread, BinReaderExt, Endian, VecArgs};
-use glam::Vec3;
+use binrw::binread;
+use glam::{Mat4, Quat, Vec3, Vec4};
 use parry3d::na::Point3;
 
 use crate::{
-    index::IndexItem,
-    section::{TagSection, TagSectionSignature},
+    error::HavokError,
+    layout::FieldReader,
+    tagfile::Tagfile,
     types::{
-        compound_shape::{hkpStaticCompoundShape, hkpStaticCompoundShapeInstance},
-        convex_vertices::{hkFourTransposedPoints, hkpConvexVerticesShape},
+        compound_shape::hkQsTransform,
+        compressed_mesh::{
+            decode_shared_vertex, hkcdStaticMeshTreeBasePrimitive, hkcdStaticMeshTreeBaseSection,
+            PrimitiveType,
+        },
+        convex_vertices::hkFourTransposedPoints,
         hkArrayIndex, hkPointerIndex,
-        unknown::{Unk81, Unk84},
     },
 };
 
@@ -31,7 +37,7 @@ pub struct UnkShapeArrayEntry {
 #[derive(Default, Clone)]
 pub struct Shape {
     pub vertices: Vec<Vec3>,
-    pub indices: Vec<u16>,
+    pub indices: Vec<u32>,
 }
 
 impl Shape {
@@ -39,7 +45,51 @@ impl Shape {
         let offset = self.vertices.len();
         self.vertices.extend(other.vertices.iter().cloned());
         self.indices
-            .extend(other.indices.iter().map(|i| i + offset as u16));
+            .extend(other.indices.iter().map(|i| i + offset as u32));
+    }
+
+    /// Converts the indices to 16-bit, for consumers that can't handle 32-bit indices.
+    /// Fails if the shape has more vertices than 16-bit indices can address, see [`Self::split_u16`]
+    pub fn indices_u16(&self) -> anyhow::Result<Vec<u16>> {
+        anyhow::ensure!(
+            self.vertices.len() <= u16::MAX as usize + 1,
+            "Shape has {} vertices, which can't be addressed by 16-bit indices",
+            self.vertices.len()
+        );
+
+        Ok(self.indices.iter().map(|&i| i as u16).collect())
+    }
+
+    /// Splits the shape into parts with 16-bit indices
+    pub fn split_u16(&self) -> Vec<(Vec<Vec3>, Vec<u16>)> {
+        let mut parts = vec![];
+        let mut vertices = vec![];
+        let mut indices = vec![];
+        // Index of each source vertex in the current part, u16::MAX if it hasn't been added yet
+        let mut remap = vec![u16::MAX; self.vertices.len()];
+
+        for triangle in self.indices.chunks_exact(3) {
+            if vertices.len() + 3 > u16::MAX as usize {
+                parts.push((std::mem::take(&mut vertices), std::mem::take(&mut indices)));
+                remap.fill(u16::MAX);
+            }
+
+            for &i in triangle {
+                let slot = &mut remap[i as usize];
+                if *slot == u16::MAX {
+                    *slot = vertices.len() as u16;
+                    vertices.push(self.vertices[i as usize]);
+                }
+
+                indices.push(*slot);
+            }
+        }
+
+        if !indices.is_empty() {
+            parts.push((vertices, indices));
+        }
+
+        parts
     }
 
     pub fn apply_transform(&mut self, transform: glam::Mat4) {
@@ -48,6 +98,15 @@ impl Shape {
         }
     }
 
+    /// Returns the bounds of the vertices, or `None` if there are none
+    pub fn aabb(&self) -> Option<(Vec3, Vec3)> {
+        if self.vertices.is_empty() {
+            None
+        } else {
+            Some(self.min_max())
+        }
+    }
+
     pub fn min_max(&self) -> (Vec3, Vec3) {
         let mut min = Vec3::splat(f32::MAX);
         let mut max = Vec3::splat(f32::MIN);
@@ -64,248 +123,738 @@ impl Shape {
         let (min, max) = self.min_max();
         (min + max) / 2.0
     }
-}
 
-pub fn read_shape_collection(f: &mut (impl Read + Seek)) -> anyhow::Result<Vec<Shape>> {
-    // Destiny's havok files have 16 bytes of padding (?) at the start
-    if f.read_be::<u32>()? == 0 {
-        f.seek(SeekFrom::Start(0x10))?;
-    } else {
-        f.seek(SeekFrom::Start(0x0))?;
+    pub fn cuboid(half_extents: Vec3) -> Self {
+        let vertices = (0..8)
+            .map(|i| {
+                half_extents
+                    * Vec3::new(
+                        if i & 1 != 0 { 1.0 } else { -1.0 },
+                        if i & 2 != 0 { 1.0 } else { -1.0 },
+                        if i & 4 != 0 { 1.0 } else { -1.0 },
+                    )
+            })
+            .collect();
+
+        #[rustfmt::skip]
+        let indices = vec![
+            0, 2, 1, 1, 2, 3, // -Z
+            4, 5, 6, 5, 7, 6, // +Z
+            0, 1, 4, 1, 5, 4, // -Y
+            2, 6, 3, 3, 6, 7, // +Y
+            0, 4, 2, 2, 4, 6, // -X
+            1, 3, 5, 3, 7, 5, // +X
+        ];
+
+        Self { vertices, indices }
+    }
+
+    pub fn sphere(radius: f32) -> Self {
+        Self::capsule(Vec3::ZERO, Vec3::ZERO, radius)
     }
 
-    let tag0: TagSection = f.read_be()?;
-    anyhow::ensure!(
-        tag0.signature == TagSectionSignature::Tag0,
-        "First tag must be TAG0",
-    );
-
-    f.seek(SeekFrom::Start(tag0.offset))?;
-
-    let mut data_offset = 0_u64;
-    while f.stream_position()? < tag0.end() {
-        match f.read_be::<TagSection>() {
-            Ok(section) => {
-                match section.signature {
-                    TagSectionSignature::Index => {
-                        f.seek(SeekFrom::Start(section.offset))?;
-                        let item = f.read_be::<TagSection>()?;
-                        let endian = if item.is_le {
-                            Endian::Little
-                        } else {
-                            Endian::Big
-                        };
-                        f.seek(SeekFrom::Start(item.offset))?;
-
-                        let mut items = vec![];
-                        while f.stream_position()? < item.end() {
-                            let mut it: IndexItem = f.read_type(endian)?;
-                            it.offset += data_offset as u32;
-                            items.push(it);
-                        }
-
-                        for item in items.iter().skip(1) {
-                            if item.typ == 0x74 {
-                                f.seek(SeekFrom::Start(item.offset as u64))?;
-                                let shape_array_index: hkArrayIndex = f.read_type(endian)?;
-                                let shape_array_item = items
-                                    .get(shape_array_index as usize)
-                                    .context("Shape array parent references invalid index")?;
-
-                                let shape_indices: Vec<UnkShapeArrayEntry> = f.save_pos_seek(
-                                    SeekFrom::Start(shape_array_item.offset as u64),
-                                    |f| {
-                                        f.read_type_args(
-                                            endian,
-                                            VecArgs {
-                                                count: shape_array_item.count as _,
-                                                inner: (),
-                                            },
-                                        )
-                                        .context("Failed to read shape array")
-                                    },
-                                )?;
-
-                                let mut shapes = vec![];
-                                for s in shape_indices {
-                                    let shape = read_shape(&items, f, s.shape, endian)?;
-                                    shapes.push(shape);
-                                }
-
-                                return Ok(shapes);
-                            }
-                        }
-                    }
-                    // TagSectionSignature::SdkVersion => {
-                    //     f.seek(SeekFrom::Start(section.offset))?;
-                    //     let mut data = vec![0u8; section.size];
-                    //     f.read_exact(&mut data)?;
-                    //     let version = String::from_utf8_lossy(&data);
-                    // }
-                    TagSectionSignature::Data => {
-                        data_offset = section.offset;
-                    }
-                    _ => {}
-                }
+    /// Builds a capsule with hemispheres around `a` and `b`
+    pub fn capsule(a: Vec3, b: Vec3, radius: f32) -> Self {
+        const STACKS: usize = 8;
+        const SLICES: usize = 12;
+
+        let axis = b - a;
+        let length = axis.length();
+        let rotation = if length > f32::EPSILON {
+            Quat::from_rotation_arc(Vec3::Y, axis / length)
+        } else {
+            Quat::IDENTITY
+        };
+
+        // Rings from the top pole to the bottom pole. The equator is emitted twice, once for each hemisphere,
+        // which forms the cylinder between them
+        let mut vertices = vec![];
+        for ring in 0..=STACKS + 1 {
+            let (stack, y_offset) = if ring <= STACKS / 2 {
+                (ring, length)
+            } else {
+                (ring - 1, 0.0)
+            };
 
-                f.seek(SeekFrom::Start(section.offset + section.size as u64))?;
+            let theta = stack as f32 / STACKS as f32 * PI;
+            for slice in 0..SLICES {
+                let phi = slice as f32 / SLICES as f32 * 2.0 * PI;
+                let local = Vec3::new(
+                    theta.sin() * phi.cos() * radius,
+                    theta.cos() * radius + y_offset,
+                    theta.sin() * phi.sin() * radius,
+                );
+
+                vertices.push(a + rotation * local);
             }
-            Err(e) => {
-                anyhow::bail!("Failed to read section: {}", e);
+        }
+
+        let mut indices = vec![];
+        for ring in 0..STACKS + 1 {
+            for slice in 0..SLICES {
+                let i0 = (ring * SLICES + slice) as u32;
+                let i1 = (ring * SLICES + (slice + 1) % SLICES) as u32;
+                let i2 = i0 + SLICES as u32;
+                let i3 = i1 + SLICES as u32;
+
+                indices.extend([i0, i1, i2, i1, i3, i2]);
             }
         }
+
+        Self { vertices, indices }
     }
 
-    Err(anyhow::anyhow!(
-        "No shape collections found in the given havok file"
-    ))
+    pub fn triangle(a: Vec3, b: Vec3, c: Vec3) -> Self {
+        Self {
+            vertices: vec![a, b, c],
+            indices: vec![0, 1, 2],
+        }
+    }
 }
 
-pub fn read_shape(
-    items: &[IndexItem],
-    f: &mut (impl Read + Seek),
-    item: hkPointerIndex,
-    endian: Endian,
-) -> anyhow::Result<Shape> {
-    let item = items
-        .get(item as usize)
-        .context("Shape references invalid index")?;
-
-    match item.typ {
-        // Some alternative kind of static compound shape?
-        0x81 => {
-            f.seek(SeekFrom::Start(item.offset as u64))?;
-
-            let unk81: Unk81 = f.read_type(endian)?;
-
-            let unk84_item = items
-                .get(unk81.unk38 as usize)
-                .context("unk81 references invalid index")?;
-
-            let unk84: Vec<Unk84> =
-                f.save_pos_seek(SeekFrom::Start(unk84_item.offset as u64), |f| {
-                    f.read_type_args(
-                        endian,
-                        VecArgs {
-                            count: unk84_item.count as _,
-                            inner: (),
-                        },
-                    )
-                    .context("Failed to read compound shape instances array")
-                })?;
+/// A shape and the shapes it contains
+#[derive(Clone)]
+pub struct ShapeNode {
+    /// Havok class name, eg. `hkpConvexVerticesShape`
+    pub type_name: String,
+    /// Index of the item this shape was read from
+    pub item: u32,
+    /// Transform relative to the parent node
+    pub transform: Mat4,
+    /// Bounds of this node and its children, in the space of this node. `None` if neither have any vertices.
+    /// Children contribute their transformed bounds, so this can be looser than the geometry itself
+    pub aabb: Option<(Vec3, Vec3)>,
+    /// Collision filter info assigned by the parent shape, if any
+    pub filter_info: Option<u32>,
+    /// User data assigned by the parent shape, if any
+    pub user_data: Option<u64>,
+    /// Geometry of this node itself, without its children
+    pub shape: Shape,
+    /// Outward facing planes (xyz = normal, w = distance) bounding convex shapes, in the space of this node.
+    /// Empty for other shapes
+    pub planes: Vec<Vec4>,
+    pub children: Vec<ShapeNode>,
+}
 
-            let mut shape = Shape::default();
-            for v in unk84 {
-                let s = read_shape(items, f, v.shape, endian)?;
-                shape.combine(&s);
-            }
+impl ShapeNode {
+    pub fn new(type_name: &str, item: u32, shape: Shape, children: Vec<ShapeNode>) -> Self {
+        let mut node = Self {
+            type_name: type_name.to_string(),
+            item,
+            transform: Mat4::IDENTITY,
+            aabb: None,
+            filter_info: None,
+            user_data: None,
+            shape,
+            planes: vec![],
+            children,
+        };
+        node.aabb = node
+            .children
+            .iter()
+            .filter_map(|c| Some(transform_aabb(c.aabb?, c.transform)))
+            .chain(node.shape.aabb())
+            .reduce(|(amin, amax), (bmin, bmax)| (amin.min(bmin), amax.max(bmax)));
+
+        node
+    }
+
+    /// Merges the geometry of this node and its children, in the space of the parent node
+    pub fn flatten(&self) -> Shape {
+        let mut shape = self.flatten_local();
+        shape.apply_transform(self.transform);
+        shape
+    }
 
-            Ok(shape)
+    fn flatten_local(&self) -> Shape {
+        let mut shape = self.shape.clone();
+        for child in &self.children {
+            shape.combine(&child.flatten());
         }
-        0x88 => {
-            f.seek(SeekFrom::Start(item.offset as u64))?;
-
-            let convex_shape: hkpConvexVerticesShape = f.read_type(endian)?;
-
-            let vertices_item = items
-                .get(convex_shape.rotated_vertices as usize)
-                .context("convex shape references invalid index")?;
-
-            let vertices: Vec<hkFourTransposedPoints> =
-                f.save_pos_seek(SeekFrom::Start(vertices_item.offset as u64), |f| {
-                    f.read_type_args(
-                        endian,
-                        VecArgs {
-                            count: vertices_item.count as _,
-                            inner: (),
-                        },
-                    )
-                    .context("Failed to read compound shape instances array")
-                })?;
 
-            let vertices_corrected = vertices
-                .iter()
-                .flat_map(|v| v.transpose())
-                .collect::<Vec<_>>();
+        shape
+    }
 
-            let points_na: Vec<Point3<f32>> = vertices_corrected
-                .iter()
-                .map(|v| v.to_array().into())
-                .collect();
+    /// Calls `f` for this node and all of its descendants, along with their transform relative to this node's parent
+    pub fn walk(&self, f: &mut impl FnMut(&ShapeNode, Mat4)) {
+        self.walk_inner(Mat4::IDENTITY, f)
+    }
+
+    fn walk_inner(&self, parent_transform: Mat4, f: &mut impl FnMut(&ShapeNode, Mat4)) {
+        let transform = parent_transform * self.transform;
+        f(self, transform);
+        for child in &self.children {
+            child.walk_inner(transform, f);
+        }
+    }
+}
+
+/// Returns the bounds of the corners of `aabb` after transforming them
+fn transform_aabb((min, max): (Vec3, Vec3), transform: Mat4) -> (Vec3, Vec3) {
+    (0..8)
+        .map(|i| {
+            transform.transform_point3(Vec3::new(
+                if i & 1 != 0 { max.x } else { min.x },
+                if i & 2 != 0 { max.y } else { min.y },
+                if i & 4 != 0 { max.z } else { min.z },
+            ))
+        })
+        .fold(
+            (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+            |(min, max), v| (min.min(v), max.max(v)),
+        )
+}
+
+/// Maximum nesting depth of shapes, which also guards against shapes that (indirectly) contain themselves
+pub const MAX_SHAPE_DEPTH: usize = 32;
+/// Maximum number of vertices read for a shape tree. Shapes that are referenced multiple times count every time
+pub const MAX_SHAPE_VERTICES: usize = 1 << 24;
+/// Maximum number of nodes read for a shape tree, which keeps shapes that reference the same children
+/// over and over from blowing up exponentially. Shapes that are referenced multiple times count every time
+pub const MAX_SHAPE_NODES: usize = 1 << 20;
+
+/// Reads all shapes in the collection, flattened into a single mesh per shape
+pub fn read_shape_collection(f: &mut (impl Read + Seek)) -> Result<Vec<Shape>, HavokError> {
+    Ok(read_shape_tree(f)?.iter().map(ShapeNode::flatten).collect())
+}
+
+/// Reads all shapes in the collection, keeping the hierarchy of compound shapes
+pub fn read_shape_tree(f: &mut (impl Read + Seek)) -> Result<Vec<ShapeNode>, HavokError> {
+    let tagfile = Tagfile::read(f)?;
+
+    let root = *tagfile
+        .items_by_type("s_hkpShape_array")
+        .first()
+        .ok_or(HavokError::NoShapeCollection)?;
+
+    let layout = &tagfile.layout;
+    let shapes = FieldReader::item(&tagfile, root)?.reference(layout.shape_array)?;
+    let entries = FieldReader::array(&tagfile, shapes, layout.pointer_size)?;
+
+    let mut reader = ShapeReader::new(&tagfile);
+    entries
+        .iter()
+        .map(|e| reader.read_shape(e.reference(0)?, 0))
+        .collect()
+}
+
+pub fn read_shape(tagfile: &Tagfile, item_index: hkPointerIndex) -> Result<ShapeNode, HavokError> {
+    ShapeReader::new(tagfile).read_shape(item_index as u32, 0)
+}
+
+struct ShapeReader<'a> {
+    tagfile: &'a Tagfile,
+    /// Vertices that can still be read before hitting [`MAX_SHAPE_VERTICES`]
+    vertex_budget: usize,
+    /// Nodes that can still be read before hitting [`MAX_SHAPE_NODES`]
+    node_budget: usize,
+}
+
+impl<'a> ShapeReader<'a> {
+    fn new(tagfile: &'a Tagfile) -> Self {
+        Self {
+            tagfile,
+            vertex_budget: MAX_SHAPE_VERTICES,
+            node_budget: MAX_SHAPE_NODES,
+        }
+    }
+
+    fn read_shape(&mut self, index: u32, depth: usize) -> Result<ShapeNode, HavokError> {
+        if depth >= MAX_SHAPE_DEPTH {
+            return Err(HavokError::TooDeep {
+                item: index,
+                limit: MAX_SHAPE_DEPTH,
+            });
+        }
 
-            let (vertices, indices) = parry3d::transformation::convex_hull(&points_na);
+        // Every node pays before reading its children, so the budget also holds for trees that never finish
+        self.node_budget = self
+            .node_budget
+            .checked_sub(1)
+            .ok_or(HavokError::TooLarge {
+                item: index,
+                what: "nodes",
+                limit: MAX_SHAPE_NODES,
+            })?;
+
+        let tagfile = self.tagfile;
+        let item = tagfile.item(index)?;
+        let type_name = tagfile.types.name(item.typ);
+        let node = |shape: Shape, children: Vec<ShapeNode>| {
+            ShapeNode::new(type_name.unwrap_or_default(), index, shape, children)
+        };
+
+        let layout = &tagfile.layout;
+        let fields = FieldReader::item(tagfile, index)?;
+        let node = match type_name {
+            Some("hkpListShape") => {
+                let l = layout.list_child;
+                let mut children = vec![];
+                for child_info in
+                    FieldReader::array(tagfile, fields.reference(layout.list_children)?, l.size)?
+                {
+                    let mut child = self.read_shape(child_info.reference(l.shape)?, depth + 1)?;
+                    child.filter_info = Some(child_info.u32(l.collision_filter_info)?);
+                    children.push(child);
+                }
 
-            let shape = Shape {
-                vertices: vertices
+                node(Shape::default(), children)
+            }
+            Some("hkpConvexVerticesShape") => {
+                let vertices: Vec<Vec3> = tagfile
+                    .read_array::<hkFourTransposedPoints>(
+                        fields.reference(layout.convex_vertices_rotated_vertices)?,
+                    )?
+                    .iter()
+                    .flat_map(|v| v.transpose())
+                    .collect();
+                let planes: Vec<Vec4> = tagfile
+                    .read_array::<[f32; 4]>(
+                        fields.reference(layout.convex_vertices_plane_equations)?,
+                    )?
                     .into_iter()
-                    .map(|v| Vec3::from_array(v.into()))
-                    .collect(),
-                indices: indices.into_iter().flatten().map(|i| i as u16).collect(),
+                    .map(Vec4::from_array)
+                    .collect();
+
+                let shape = match read_convex_faces(tagfile, &fields, &vertices, &planes)? {
+                    Some(shape) => shape,
+                    None => convex_hull(&vertices).map_err(|message| HavokError::InvalidData {
+                        item: index,
+                        offset: item.offset as u64,
+                        message,
+                    })?,
+                };
+
+                let mut node = node(shape, vec![]);
+                node.planes = planes;
+                node
+            }
+            Some("hkpStaticCompoundShape") => {
+                let l = layout.compound_instance;
+                let mut children = vec![];
+                for instance in FieldReader::array(
+                    tagfile,
+                    fields.reference(layout.compound_instances)?,
+                    l.size,
+                )? {
+                    let transform = hkQsTransform {
+                        translation: instance.vec4(l.transform)?,
+                        rotation: instance.vec4(l.transform + 0x10)?,
+                        scale: instance.vec4(l.transform + 0x20)?,
+                    };
+
+                    let mut child = self.read_shape(instance.reference(l.shape)?, depth + 1)?;
+                    child.transform = transform.to_mat4();
+                    child.filter_info = Some(instance.u32(l.filter_info)?);
+                    child.user_data = Some(instance.u64(l.user_data)?);
+                    children.push(child);
+                }
+
+                node(Shape::default(), children)
+            }
+            Some("hkpBoxShape") => {
+                let half_extents = fields.vec4(layout.box_half_extents)?.truncate();
+                let radius = fields.f32(layout.convex_radius)?;
+
+                node(Shape::cuboid(half_extents + radius), vec![])
+            }
+            Some("hkpSphereShape") => {
+                node(Shape::sphere(fields.f32(layout.convex_radius)?), vec![])
+            }
+            Some("hkpCapsuleShape") => {
+                let v = layout.capsule_vertices;
+
+                node(
+                    Shape::capsule(
+                        fields.vec4(v)?.truncate(),
+                        fields.vec4(v + 0x10)?.truncate(),
+                        fields.f32(layout.convex_radius)?,
+                    ),
+                    vec![],
+                )
+            }
+            Some("hkpTriangleShape") => {
+                let v = layout.triangle_vertices;
+
+                node(
+                    Shape::triangle(
+                        fields.vec4(v)?.truncate(),
+                        fields.vec4(v + 0x10)?.truncate(),
+                        fields.vec4(v + 0x20)?.truncate(),
+                    ),
+                    vec![],
+                )
+            }
+            Some("hkpBvCompressedMeshShape") => {
+                node(read_compressed_mesh(tagfile, index, &fields)?, vec![])
+            }
+            Some(name) => {
+                return Err(HavokError::UnhandledShape {
+                    item: index,
+                    type_name: name.to_string(),
+                })
+            }
+            None => {
+                return Err(HavokError::UnhandledShape {
+                    item: index,
+                    type_name: format!("0x{:x}", item.typ),
+                })
+            }
+        };
+
+        self.vertex_budget = self
+            .vertex_budget
+            .checked_sub(node.shape.vertices.len())
+            .ok_or(HavokError::TooLarge {
+                item: index,
+                what: "vertices",
+                limit: MAX_SHAPE_VERTICES,
+            })?;
+
+        Ok(node)
+    }
+}
+
+/// Builds the original faces of a convex vertices shape from its connectivity.
+/// Returns `None` when the shape has no connectivity, in which case the faces have to be rebuilt from the vertices
+fn read_convex_faces(
+    tagfile: &Tagfile,
+    fields: &FieldReader,
+    vertices: &[Vec3],
+    planes: &[Vec4],
+) -> Result<Option<Shape>, HavokError> {
+    let layout = &tagfile.layout;
+    let index = fields.reference(layout.convex_vertices_connectivity)?;
+    if index == 0 {
+        return Ok(None);
+    }
+
+    // The connectivity offset is a guess for files without type information, so anything that isn't
+    // known to be a connectivity object only gets used if it holds up
+    let confirmed = match tagfile.item(index).map(|i| tagfile.types.name(i.typ)) {
+        Ok(Some("hkpConvexVerticesConnectivity")) => true,
+        Ok(None) => false,
+        _ => return Ok(None),
+    };
+
+    let faces = (|| {
+        let connectivity = FieldReader::item(tagfile, index)?;
+        let c = layout.connectivity;
+        let indices: Vec<u16> = tagfile.read_array(connectivity.reference(c.vertex_indices)?)?;
+        let counts: Vec<u8> =
+            tagfile.read_array(connectivity.reference(c.num_vertices_per_face)?)?;
+
+        convex_faces(vertices, planes, &indices, &counts).map_err(|message| {
+            HavokError::InvalidData {
+                item: index,
+                offset: tagfile.item(index).map(|i| i.offset as u64).unwrap_or(0),
+                message,
+            }
+        })
+    })();
+
+    match faces {
+        Ok(shape) => Ok(Some(shape)),
+        Err(_) if !confirmed => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Triangulates the faces described by a `hkpConvexVerticesConnectivity`.
+/// Faces are wound to face away from the shape, using the plane they lie on
+fn convex_faces(
+    vertices: &[Vec3],
+    planes: &[Vec4],
+    indices: &[u16],
+    counts: &[u8],
+) -> Result<Shape, String> {
+    let total: usize = counts.iter().map(|&c| c as usize).sum();
+    if total != indices.len() {
+        return Err(format!(
+            "Faces use {total} vertex indices, but there are {}",
+            indices.len()
+        ));
+    }
+
+    if let Some(i) = indices.iter().find(|&&i| i as usize >= vertices.len()) {
+        return Err(format!(
+            "Vertex index {i} is out of range ({} vertices)",
+            vertices.len()
+        ));
+    }
+
+    // The last block of transposed points is padded with copies of the last vertex
+    let used = indices.iter().max().map(|&i| i as usize + 1).unwrap_or(0);
+    let vertices = &vertices[..used];
+    let center = vertices.iter().sum::<Vec3>() / used.max(1) as f32;
+
+    let mut shape = Shape {
+        vertices: vertices.to_vec(),
+        indices: vec![],
+    };
+
+    let mut start = 0;
+    for &count in counts {
+        let face = &indices[start..start + count as usize];
+        start += count as usize;
+
+        // Faces with less than 3 vertices don't enclose anything
+        if face.len() < 3 {
+            continue;
+        }
+
+        let points: Vec<Vec3> = face.iter().map(|&i| vertices[i as usize]).collect();
+
+        // Newell's method, which works for any winding of a planar polygon
+        let mut normal = Vec3::ZERO;
+        for (i, a) in points.iter().enumerate() {
+            let b = points[(i + 1) % points.len()];
+            normal += Vec3::new(
+                (a.y - b.y) * (a.z + b.z),
+                (a.z - b.z) * (a.x + b.x),
+                (a.x - b.x) * (a.y + b.y),
+            );
+        }
+
+        let outward = planes
+            .iter()
+            .map(|p| {
+                let distance = points
+                    .iter()
+                    .map(|v| (p.truncate().dot(*v) + p.w).abs())
+                    .fold(0.0, f32::max);
+                (p.truncate(), distance)
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(n, _)| n)
+            .unwrap_or_else(|| points.iter().sum::<Vec3>() / points.len() as f32 - center);
+
+        let flip = normal.dot(outward) < 0.0;
+        for i in 1..face.len() - 1 {
+            let (b, c) = if flip {
+                (face[i + 1], face[i])
+            } else {
+                (face[i], face[i + 1])
             };
+            shape.indices.extend([face[0] as u32, b as u32, c as u32]);
+        }
+    }
+
+    Ok(shape)
+}
 
-            Ok(shape)
+fn convex_hull(vertices: &[Vec3]) -> Result<Shape, String> {
+    let points: Vec<Point3<f32>> = vertices.iter().map(|v| v.to_array().into()).collect();
+    let (vertices, indices) = parry3d::transformation::try_convex_hull(&points)
+        .map_err(|e| format!("Failed to build convex hull: {e}"))?;
+
+    Ok(Shape {
+        vertices: vertices
+            .into_iter()
+            .map(|v| Vec3::from_array(v.into()))
+            .collect(),
+        indices: indices.into_iter().flatten().collect(),
+    })
+}
+
+fn read_compressed_mesh(
+    tagfile: &Tagfile,
+    index: u32,
+    fields: &FieldReader,
+) -> Result<Shape, HavokError> {
+    let m = tagfile.layout.mesh;
+    let l = tagfile.layout.mesh_section;
+
+    let domain_min = fields.vec4(m.domain_min)?.truncate();
+    let domain_max = fields.vec4(m.domain_max)?.truncate();
+    let sections = FieldReader::array(tagfile, fields.reference(m.sections)?, l.size)?
+        .into_iter()
+        .map(|s| {
+            let mut codec_parms = [0.0; 6];
+            for (i, p) in codec_parms.iter_mut().enumerate() {
+                *p = s.f32(l.codec_parms + i as u32 * 4)?;
+            }
+
+            Ok(hkcdStaticMeshTreeBaseSection {
+                codec_parms,
+                first_packed_vertex: s.u32(l.first_packed_vertex)?,
+                shared_vertices: s.u32(l.shared_vertices)?,
+                primitives: s.u32(l.primitives)?,
+                num_packed_vertices: s.u8(l.num_packed_vertices)?,
+                num_shared_indices: s.u8(l.num_shared_indices)?,
+                ..Default::default()
+            })
+        })
+        .collect::<Result<Vec<_>, HavokError>>()?;
+    let primitives: Vec<hkcdStaticMeshTreeBasePrimitive> =
+        tagfile.read_array(fields.reference(m.primitives)?)?;
+    let shared_vertices_index: Vec<u16> =
+        tagfile.read_array(fields.reference(m.shared_vertices_index)?)?;
+    let packed_vertices: Vec<u32> = tagfile.read_array(fields.reference(m.packed_vertices)?)?;
+    let shared_vertices: Vec<u64> = tagfile.read_array(fields.reference(m.shared_vertices)?)?;
+
+    let invalid = |message: String| HavokError::InvalidData {
+        item: index,
+        offset: tagfile.items[index as usize].offset as u64,
+        message,
+    };
+
+    let mut shape = Shape::default();
+    for (i, section) in sections.iter().enumerate() {
+        // Primitive indices address the packed vertices of the section, followed by its shared vertices
+        let first_packed = section.first_packed_vertex as usize;
+        let mut vertices = packed_vertices
+            .get(first_packed..first_packed + section.num_packed_vertices as usize)
+            .ok_or_else(|| invalid(format!("Section {i} packed vertices are out of range")))?
+            .iter()
+            .map(|v| section.decode_packed_vertex(*v))
+            .collect::<Vec<_>>();
+
+        let first_shared = section.shared_vertices_offset();
+        for shared_index in shared_vertices_index
+            .get(first_shared..first_shared + section.num_shared_indices as usize)
+            .ok_or_else(|| {
+                invalid(format!(
+                    "Section {i} shared vertex indices are out of range"
+                ))
+            })?
+        {
+            let v = shared_vertices
+                .get(*shared_index as usize)
+                .ok_or_else(|| invalid(format!("Section {i} references invalid shared vertex")))?;
+            vertices.push(decode_shared_vertex(domain_min, domain_max, *v));
         }
-        0xaf => {
-            f.seek(SeekFrom::Start(item.offset as u64))?;
-
-            let compound_shape: hkpStaticCompoundShape = f.read_type(endian)?;
-
-            let instances_item = items
-                .get(compound_shape.instances as usize)
-                .context("compound shape references invalid index")?;
-
-            let instances: Vec<hkpStaticCompoundShapeInstance> =
-                f.save_pos_seek(SeekFrom::Start(instances_item.offset as u64), |f| {
-                    f.read_type_args(
-                        endian,
-                        VecArgs {
-                            count: instances_item.count as _,
-                            inner: (),
-                        },
-                    )
-                    .context("Failed to read compound shape instances array")
-                })?;
-
-            let mut shape = Shape::default();
-            for instance in instances {
-                let mut s = read_shape(items, f, instance.shape, endian)?;
-                s.apply_transform(instance.transform.to_mat4());
-                shape.combine(&s);
+
+        let mut section_shape = Shape {
+            vertices,
+            indices: vec![],
+        };
+
+        for primitive in primitives
+            .get(section.primitives_range())
+            .ok_or_else(|| invalid(format!("Section {i} primitives are out of range")))?
+        {
+            let indices = match primitive.primitive_type() {
+                PrimitiveType::Triangle([a, b, c]) => vec![a, b, c],
+                PrimitiveType::Quad([a, b, c, d]) => vec![a, b, c, a, c, d],
+                // Custom primitives reference convex shapes, which aren't decoded yet
+                PrimitiveType::Custom => {
+                    return Err(HavokError::UnhandledShape {
+                        item: index,
+                        type_name: "hkpBvCompressedMeshShape (custom primitive)".to_string(),
+                    })
+                }
+            };
+
+            if indices
+                .iter()
+                .any(|i| (*i as usize) >= section_shape.vertices.len())
+            {
+                return Err(invalid(format!(
+                    "Section {i} primitive references invalid vertex"
+                )));
             }
 
-            Ok(shape)
+            section_shape
+                .indices
+                .extend(indices.into_iter().map(|i| i as u32));
         }
-        u => anyhow::bail!("read_shape: Unhandled shape type 0x{u:x}"),
+
+        shape.combine(&section_shape);
     }
+
+    Ok(shape)
 }
 
-trait SeekSaveExt: Seek {
-    fn save_pos<F, T>(&mut self, inner: F) -> anyhow::Result<T>
-    where
-        F: FnOnce(&mut Self) -> anyhow::Result<T>,
-    {
-        let pos = self.stream_position()?;
+#[cfg(test)]
+mod tests {
+    use binrw::Endian;
+
+    use super::*;
+    use crate::{
+        index::{IndexItem, ItemFlags},
+        layout::DESTINY2,
+        reflection::TypeTable,
+        tagfile::DESTINY_TYPE_NAMES,
+    };
+
+    fn item(typ: u32, offset: usize, count: u32) -> IndexItem {
+        IndexItem {
+            type_and_flags: typ,
+            typ,
+            flags: ItemFlags::empty(),
+            offset: offset as u32,
+            count,
+        }
+    }
 
-        let r = inner(self)?;
+    /// A chain of list shapes where every list references the next one twice
+    fn repeated_children_tagfile(levels: u32) -> Tagfile {
+        let layout = DESTINY2.clone();
+        let list_size = layout.list_children as usize + 8;
+        let children_size = layout.list_child.size as usize * 2;
+
+        let mut data = vec![];
+        let mut items = vec![item(0, 0, 0)];
+        for level in 0..levels {
+            let list_index = items.len() as u32;
+            let children_index = list_index + 1;
+            // The last list has no children
+            let next_list = if level + 1 < levels {
+                children_index + 1
+            } else {
+                0
+            };
 
-        self.seek(SeekFrom::Start(pos))?;
+            let list_offset = data.len();
+            data.resize(list_offset + list_size, 0);
+            if next_list != 0 {
+                data[list_offset + layout.list_children as usize..][..8]
+                    .copy_from_slice(&(children_index as u64).to_le_bytes());
+            }
 
-        Ok(r)
+            let children_offset = data.len();
+            data.resize(children_offset + children_size, 0);
+            for child in 0..2 {
+                let offset = children_offset
+                    + child * layout.list_child.size as usize
+                    + layout.list_child.shape as usize;
+                data[offset..][..8].copy_from_slice(&(next_list as u64).to_le_bytes());
+            }
+
+            items.push(item(0x81, list_offset, 1));
+            items.push(item(0, children_offset, 2));
+        }
+
+        Tagfile {
+            sdk_version: None,
+            version: None,
+            layout,
+            endian: Endian::Little,
+            data,
+            items,
+            types: TypeTable::from_names(DESTINY_TYPE_NAMES),
+        }
     }
 
-    fn save_pos_seek<F, T>(&mut self, seek: SeekFrom, inner: F) -> anyhow::Result<T>
-    where
-        F: FnOnce(&mut Self) -> anyhow::Result<T>,
-    {
-        let pos = self.stream_position()?;
+    #[test]
+    fn repeated_children_are_read() {
+        let tagfile = repeated_children_tagfile(4);
+        let node = read_shape(&tagfile, 1).unwrap();
 
-        self.seek(seek)?;
-        let r = inner(self)?;
+        assert_eq!(node.type_name, "hkpListShape");
+        assert_eq!(node.children.len(), 2);
+        assert_eq!(node.children[1].children[0].children.len(), 2);
+        assert!(node.children[1].children[0].children[1].children.is_empty());
+    }
 
-        self.seek(SeekFrom::Start(pos))?;
+    #[test]
+    fn repeated_children_hit_node_budget() {
+        let tagfile = repeated_children_tagfile(MAX_SHAPE_DEPTH as u32 - 1);
 
-        Ok(r)
+        assert!(matches!(
+            read_shape(&tagfile, 1),
+            Err(HavokError::TooLarge { what: "nodes", .. })
+        ));
     }
 }
-
-impl<T: Read + Seek> SeekSaveExt for T {}
@@ -0,0 +1,571 @@
+//! Generic reader for Havok tagfiles (TAG0)
+
+use std::io::{Cursor, Read, Seek, SeekFrom};
+
+use binrw::{BinRead, BinReaderExt, Endian, VecArgs};
+
+use crate::{
+    error::HavokError,
+    index::{IndexItem, ItemFlags},
+    layout::{Layout, SdkVersion, DESTINY2},
+    reflection::{TypeKind, TypeTable},
+    section::{TagSection, TagSectionSignature},
+};
+
+/// Type names for Destiny files that don't carry their own type information, by the type indices Destiny 2 uses.
+/// Only applied to files with Destiny's padded header, reflection data always takes precedence
+pub const DESTINY_TYPE_NAMES: &[(u32, &str)] = &[
+    (0x20, "hkFourTransposedPoints"),
+    (0x3f, "s_physics_component_havok_data"),
+    (0x48, "s_hkpShape_array_data"),
+    (0x74, "s_hkpShape_array"),
+    // Named by the original info example
+    (0x7d, "hkpBoxShape"),
+    // Read as a compound with its children at 0x38 by the original shape reader, the name itself is unconfirmed
+    (0x81, "hkpListShape"),
+    (0x88, "hkpConvexVerticesShape"),
+    // Named by the original info example
+    (0x8b, "hkpBvCompressedMeshShape"),
+    (0x99, "hkpBvCompressedMeshShapeTreeDataRun"),
+    (0x9c, "hkcdStaticMeshTreeBaseSection"),
+    (0x9e, "hkcdStaticMeshTreeBasePrimitive"),
+    (0xac, "hkcdStaticTreeCodec3Axis4"),
+    (0xaf, "hkpStaticCompoundShape"),
+    (0xb3, "hkpStaticCompoundShapeInstance"),
+];
+
+/// Maximum nesting depth of values read by [`Tagfile::read_item`]
+pub const MAX_VALUE_DEPTH: usize = 64;
+/// Maximum number of values read by a single [`Tagfile::read_item`] call
+pub const MAX_VALUE_COUNT: usize = 1 << 22;
+/// Maximum element count of array items
+pub const MAX_ARRAY_COUNT: u64 = 1 << 24;
+
+pub struct Tagfile {
+    /// Contents of the SDKV section, eg. `20160200`
+    pub sdk_version: Option<String>,
+    /// Parsed SDK version, if the SDKV section is present and valid
+    pub version: Option<SdkVersion>,
+    /// Struct layouts used by the shape reader, taken from the type information if the file has it
+    pub layout: Layout,
+    pub endian: Endian,
+    /// Contents of the DATA section. Item offsets are relative to the start of this
+    pub data: Vec<u8>,
+    pub items: Vec<IndexItem>,
+    pub types: TypeTable,
+}
+
+impl Tagfile {
+    pub fn read(f: &mut (impl Read + Seek)) -> Result<Self, HavokError> {
+        let file_end = f.seek(SeekFrom::End(0))?;
+        f.seek(SeekFrom::Start(0))?;
+
+        // Destiny's havok files have 16 bytes of padding (?) at the start
+        let start = match f.read_be::<u32>() {
+            Ok(0) => 0x10,
+            _ => 0x0,
+        };
+        let is_destiny = start == 0x10;
+
+        // Only the TAG0 section is read, anything past its declared size is ignored
+        let tag0 = read_section(f, TagSectionSignature::Tag0, start, file_end)?;
+        if tag0.signature != TagSectionSignature::Tag0 {
+            return Err(HavokError::InvalidSection {
+                section: tag0.signature,
+                offset: 0,
+                message: "First tag must be TAG0".to_string(),
+            });
+        }
+
+        let mut sdk_version = None;
+        let mut data = vec![];
+        let mut items = vec![];
+        let mut endian = Endian::Little;
+
+        let mut type_strings = None;
+        let mut type_names = None;
+        let mut field_strings = None;
+        let mut type_bodies = None;
+
+        for section in read_sections(f, tag0.signature, tag0.offset, tag0.end())? {
+            match section.signature {
+                TagSectionSignature::SdkVersion => {
+                    let version = read_section_data(f, &section)?;
+                    sdk_version = Some(
+                        String::from_utf8_lossy(&version)
+                            .trim_end_matches('\0')
+                            .to_string(),
+                    );
+                }
+                TagSectionSignature::Data => {
+                    data = read_section_data(f, &section)?;
+                }
+                TagSectionSignature::Type => {
+                    for section in
+                        read_sections(f, section.signature, section.offset, section.end())?
+                    {
+                        let target =
+                            match section.signature {
+                                TagSectionSignature::TypeStrings
+                                | TagSectionSignature::TypeStrings1 => &mut type_strings,
+                                TagSectionSignature::TypeNames
+                                | TagSectionSignature::TypeNames1 => &mut type_names,
+                                TagSectionSignature::FieldStrings
+                                | TagSectionSignature::FieldStrings1 => &mut field_strings,
+                                TagSectionSignature::TypeBodies
+                                | TagSectionSignature::TypeBodies1 => &mut type_bodies,
+                                _ => continue,
+                            };
+
+                        *target = Some(read_section_data(f, &section)?);
+                    }
+                }
+                TagSectionSignature::Index => {
+                    for section in
+                        read_sections(f, section.signature, section.offset, section.end())?
+                    {
+                        if section.signature != TagSectionSignature::IndexItem {
+                            continue;
+                        }
+
+                        endian = if section.is_le {
+                            Endian::Little
+                        } else {
+                            Endian::Big
+                        };
+
+                        f.seek(SeekFrom::Start(section.offset))?;
+                        while f.stream_position()? < section.end() {
+                            let offset = f.stream_position()?;
+                            items.push(f.read_type(endian).map_err(|source| {
+                                HavokError::Section {
+                                    section: section.signature,
+                                    offset,
+                                    source,
+                                }
+                            })?);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let version = sdk_version.as_deref().and_then(SdkVersion::parse);
+        // Destiny 2 is the only layout we know, other versions only get correct offsets from their type information
+        let base_layout = &DESTINY2;
+        let (types, layout) = match (type_strings, type_names, field_strings, type_bodies) {
+            (Some(type_strings), Some(type_names), Some(field_strings), Some(type_bodies)) => {
+                let types =
+                    TypeTable::read(&type_strings, &type_names, &field_strings, &type_bodies)?;
+                let layout = base_layout.with_types(&types);
+                (types, layout)
+            }
+            // Type indices are only known for Destiny's own files
+            _ if is_destiny => (
+                TypeTable::from_names(base_layout.type_names),
+                base_layout.clone(),
+            ),
+            _ => (TypeTable::from_names(&[]), base_layout.clone()),
+        };
+
+        Ok(Self {
+            sdk_version,
+            version,
+            layout,
+            endian,
+            data,
+            items,
+            types,
+        })
+    }
+
+    pub fn item(&self, index: u32) -> Result<&IndexItem, HavokError> {
+        self.items
+            .get(index as usize)
+            .ok_or(HavokError::InvalidItem { index })
+    }
+
+    pub fn item_type_name(&self, index: u32) -> Option<&str> {
+        self.types.name(self.items.get(index as usize)?.typ)
+    }
+
+    /// Indices of all items with the given type name, in file order
+    pub fn items_by_type(&self, name: &str) -> Vec<u32> {
+        (0..self.items.len() as u32)
+            .filter(|&i| self.item_type_name(i) == Some(name))
+            .collect()
+    }
+
+    /// Cursor over the DATA section, for reading items with binrw
+    pub fn cursor(&self) -> Cursor<&[u8]> {
+        Cursor::new(&self.data)
+    }
+
+    /// Reads a single struct from the start of an item
+    pub fn read_struct<T>(&self, index: u32) -> Result<T, HavokError>
+    where
+        for<'a> T: BinRead<Args<'a> = ()>,
+    {
+        let item = self.item(index)?;
+        let mut f = self.cursor();
+        f.seek(SeekFrom::Start(item.offset as u64))?;
+
+        f.read_type(self.endian).map_err(|source| HavokError::Item {
+            item: index,
+            offset: item.offset as u64,
+            source,
+        })
+    }
+
+    /// Reads all elements of an array item. A null index produces an empty array
+    pub fn read_array<T>(&self, index: u32) -> Result<Vec<T>, HavokError>
+    where
+        for<'a> T: BinRead<Args<'a> = ()> + 'static,
+    {
+        if index == 0 {
+            return Ok(vec![]);
+        }
+
+        // Every element takes up at least a byte, which keeps bogus counts from causing huge allocations
+        let item = self.item(index)?;
+        let remaining = (self.data.len() as u64).saturating_sub(item.offset as u64);
+        let limit = MAX_ARRAY_COUNT.min(remaining);
+        if item.count as u64 > limit {
+            return Err(HavokError::TooManyElements {
+                item: index,
+                count: item.count as u64,
+                limit,
+            });
+        }
+
+        let mut f = self.cursor();
+        f.seek(SeekFrom::Start(item.offset as u64))?;
+
+        f.read_type_args(
+            self.endian,
+            VecArgs {
+                count: item.count as _,
+                inner: (),
+            },
+        )
+        .map_err(|source| HavokError::Item {
+            item: index,
+            offset: item.offset as u64,
+            source,
+        })
+    }
+
+    /// Reads an item as a dynamic object tree.
+    /// Array items produce a [`Value::Array`], pointers to other items are not followed
+    pub fn read_item(&self, index: u32) -> Result<Value, HavokError> {
+        let mut budget = MAX_VALUE_COUNT;
+        self.read_item_inner(index, 0, &mut budget)
+    }
+
+    fn read_item_inner(
+        &self,
+        index: u32,
+        depth: usize,
+        budget: &mut usize,
+    ) -> Result<Value, HavokError> {
+        let item = self.item(index)?;
+        let element_size = self.types.byte_size(item.typ) as u64;
+
+        if item.flags.contains(ItemFlags::ARRAY) || item.count != 1 {
+            let size = element_size.max(1) * item.count as u64;
+            if item.offset as u64 + size > self.data.len() as u64 {
+                return Err(HavokError::OutOfBounds {
+                    item: index,
+                    offset: item.offset as u64,
+                    size,
+                });
+            }
+
+            let elements = (0..item.count as u64)
+                .map(|i| {
+                    self.read_value(
+                        index,
+                        item.typ,
+                        (item.offset as u64 + i * element_size) as usize,
+                        depth,
+                        budget,
+                    )
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(Value::Array(elements))
+        } else {
+            self.read_value(index, item.typ, item.offset as usize, depth, budget)
+        }
+    }
+
+    /// Reads a value of type `typ` at `offset` in the DATA section, as part of `item`
+    fn read_value(
+        &self,
+        item: u32,
+        typ: u32,
+        offset: usize,
+        depth: usize,
+        budget: &mut usize,
+    ) -> Result<Value, HavokError> {
+        if depth >= MAX_VALUE_DEPTH {
+            return Err(HavokError::TooDeep {
+                item,
+                limit: MAX_VALUE_DEPTH,
+            });
+        }
+
+        *budget = budget.checked_sub(1).ok_or(HavokError::TooLarge {
+            item,
+            what: "values",
+            limit: MAX_VALUE_COUNT,
+        })?;
+
+        let size = self.types.byte_size(typ) as usize;
+        Ok(match self.types.kind(typ) {
+            TypeKind::Void => Value::Null,
+            TypeKind::Opaque => Value::Opaque(self.bytes(item, offset, size)?.to_vec()),
+            TypeKind::Bool => Value::Bool(self.read_uint(item, offset, size)? != 0),
+            TypeKind::Int { signed } => {
+                let v = self.read_uint(item, offset, size)?;
+                Value::Int(if signed && (1..8).contains(&size) {
+                    // Sign extend
+                    let shift = 64 - size * 8;
+                    ((v << shift) as i64) >> shift
+                } else {
+                    v as i64
+                })
+            }
+            TypeKind::Float => match size {
+                4 => Value::Float(f32::from_bits(self.read_uint(item, offset, 4)? as u32) as f64),
+                8 => Value::Float(f64::from_bits(self.read_uint(item, offset, 8)?)),
+                _ => Value::Opaque(self.bytes(item, offset, size)?.to_vec()),
+            },
+            TypeKind::Pointer => Value::Pointer(self.read_item_index(item, offset, size)?),
+            TypeKind::String => match self.read_item_index(item, offset, size)? {
+                0 => Value::Null,
+                index => {
+                    let string_item = self.item(index)?;
+                    let bytes = self.bytes(
+                        index,
+                        string_item.offset as usize,
+                        string_item.count as usize,
+                    )?;
+                    Value::String(
+                        String::from_utf8_lossy(bytes)
+                            .trim_end_matches('\0')
+                            .to_string(),
+                    )
+                }
+            },
+            TypeKind::Array => match self.read_item_index(item, offset, size)? {
+                0 => Value::Array(vec![]),
+                index => self.read_item_inner(index, depth + 1, budget)?,
+            },
+            TypeKind::Tuple(count) => {
+                let element = self.types.pointer(typ);
+                let element_size = self.types.byte_size(element) as usize;
+                Value::Array(
+                    (0..count as usize)
+                        .map(|i| {
+                            self.read_value(
+                                item,
+                                element,
+                                offset + i * element_size,
+                                depth + 1,
+                                budget,
+                            )
+                        })
+                        .collect::<Result<Vec<_>, _>>()?,
+                )
+            }
+            TypeKind::Record => Value::Object {
+                typ: self.types.name(typ).unwrap_or_default().to_string(),
+                members: self
+                    .types
+                    .all_members(typ)
+                    .into_iter()
+                    .map(|m| {
+                        Ok((
+                            m.name.clone(),
+                            self.read_value(
+                                item,
+                                m.typ,
+                                offset + m.offset as usize,
+                                depth + 1,
+                                budget,
+                            )?,
+                        ))
+                    })
+                    .collect::<Result<Vec<_>, HavokError>>()?,
+            },
+        })
+    }
+
+    fn bytes(&self, item: u32, offset: usize, size: usize) -> Result<&[u8], HavokError> {
+        offset
+            .checked_add(size)
+            .and_then(|end| self.data.get(offset..end))
+            .ok_or(HavokError::OutOfBounds {
+                item,
+                offset: offset as u64,
+                size: size as u64,
+            })
+    }
+
+    pub(crate) fn read_uint(
+        &self,
+        item: u32,
+        offset: usize,
+        size: usize,
+    ) -> Result<u64, HavokError> {
+        if size > 8 {
+            return Err(HavokError::InvalidData {
+                item,
+                offset: offset as u64,
+                message: format!("Integer size {size} is too large"),
+            });
+        }
+
+        let mut bytes = [0u8; 8];
+        let data = self.bytes(item, offset, size)?;
+        match self.endian {
+            Endian::Little => bytes[..size].copy_from_slice(data),
+            Endian::Big => bytes[8 - size..].copy_from_slice(data),
+        }
+
+        Ok(match self.endian {
+            Endian::Little => u64::from_le_bytes(bytes),
+            Endian::Big => u64::from_be_bytes(bytes),
+        })
+    }
+
+    /// Pointers and arrays store the index of the item they reference
+    fn read_item_index(&self, item: u32, offset: usize, size: usize) -> Result<u32, HavokError> {
+        Ok(self.read_uint(item, offset, size.min(8))? as u32)
+    }
+}
+
+/// Dynamic value read using the reflection data of a tagfile
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+    /// Index of the referenced item, 0 for null pointers
+    Pointer(u32),
+    Array(Vec<Value>),
+    Object {
+        typ: String,
+        members: Vec<(String, Value)>,
+    },
+    /// Data without a known layout
+    Opaque(Vec<u8>),
+}
+
+impl Value {
+    /// Looks up a member of an object
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        match self {
+            Value::Object { members, .. } => {
+                members.iter().find(|(n, _)| n == name).map(|(_, v)| v)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn type_name(&self) -> Option<&str> {
+        match self {
+            Value::Object { typ, .. } => Some(typ),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_pointer(&self) -> Option<u32> {
+        match self {
+            Value::Pointer(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            Value::Int(v) => Some(*v),
+            Value::Bool(v) => Some(*v as i64),
+            _ => None,
+        }
+    }
+
+    pub fn as_float(&self) -> Option<f64> {
+        match self {
+            Value::Float(v) => Some(*v),
+            _ => None,
+        }
+    }
+}
+
+/// Reads the headers of all sections between `start` and `end`, which lie inside of `parent`
+fn read_sections(
+    f: &mut (impl Read + Seek),
+    parent: TagSectionSignature,
+    start: u64,
+    end: u64,
+) -> Result<Vec<TagSection>, HavokError> {
+    let mut sections = vec![];
+
+    let mut offset = start;
+    while offset < end {
+        let section = read_section(f, parent, offset, end)?;
+        offset = section.end();
+        sections.push(section);
+    }
+
+    Ok(sections)
+}
+
+/// Reads the section header at `offset`, making sure the section doesn't extend past `end`
+fn read_section(
+    f: &mut (impl Read + Seek),
+    parent: TagSectionSignature,
+    offset: u64,
+    end: u64,
+) -> Result<TagSection, HavokError> {
+    f.seek(SeekFrom::Start(offset))?;
+    let section: TagSection = f.read_be().map_err(|source| HavokError::Section {
+        section: parent,
+        offset,
+        source,
+    })?;
+
+    if section.end() > end {
+        return Err(HavokError::SectionOutOfBounds {
+            section: section.signature,
+            offset: section.offset,
+            size: section.size as u64,
+        });
+    }
+
+    Ok(section)
+}
+
+/// Reads the contents of a section. Callers must make sure the section lies within the file
+fn read_section_data(
+    f: &mut (impl Read + Seek),
+    section: &TagSection,
+) -> Result<Vec<u8>, HavokError> {
+    f.seek(SeekFrom::Start(section.offset))?;
+    let mut data = vec![0u8; section.size];
+    f.read_exact(&mut data)?;
+
+    Ok(data)
+}
@@ -31,7 +31,10 @@ pub struct hkpStaticCompoundShapeInstance {
     pub transform: hkQsTransform,
     pub shape: hkPointerIndex,
 
-    pub unk28: [u32; 6],
+    pub filter_info: u32,            // ?
+    pub child_filter_info_mask: u32, // ?
+    pub user_data: u64,              // ?
+    pub unk48: u64,
 }
 
 #[binread]
@@ -0,0 +1,147 @@
+use binrw::binread;
+use glam::{Vec3, Vec4};
+
+use super::{bvtree::BvTreeType, hkArrayIndex};
+
+#[binread]
+#[derive(Debug)]
+pub struct hkpBvCompressedMeshShape {
+    pub unk0: [u32; 10],
+
+    pub tree_type: BvTreeType,
+
+    pub convex_radius: f32,
+    pub welding_type: u8,
+    pub has_per_primitive_collision_filter_info: u8,
+    pub has_per_primitive_user_data: u8,
+    pub unk37: u8,
+
+    pub collision_filter_info_palette: hkArrayIndex,
+    pub unk40: u64,
+    pub user_data_palette: hkArrayIndex,
+    pub unk50: u64,
+    pub user_string_palette: hkArrayIndex,
+    pub unk60: [u64; 2],
+
+    // hkpBvCompressedMeshShapeTree
+    pub nodes: hkArrayIndex,
+    pub unk78: u64,
+
+    #[br(map = Vec4::from_array)]
+    pub domain_min: Vec4,
+    #[br(map = Vec4::from_array)]
+    pub domain_max: Vec4,
+
+    pub num_primitive_keys: i32,
+    pub bits_per_key: i32,
+    pub max_key_value: u32,
+    pub unkac: u32,
+
+    pub sections: hkArrayIndex,
+    pub unkb8: u64,
+    pub primitives: hkArrayIndex,
+    pub unkc8: u64,
+    pub shared_vertices_index: hkArrayIndex,
+    pub unkd8: u64,
+    pub packed_vertices: hkArrayIndex,
+    pub unke8: u64,
+    pub shared_vertices: hkArrayIndex,
+    pub unkf8: u64,
+    pub primitive_data_runs: hkArrayIndex,
+    pub unk108: u64,
+}
+
+impl hkpBvCompressedMeshShape {
+    /// Decodes a shared vertex, which is quantized to 21/21/22 bits within the tree domain
+    pub fn decode_shared_vertex(&self, v: u64) -> Vec3 {
+        decode_shared_vertex(self.domain_min.truncate(), self.domain_max.truncate(), v)
+    }
+}
+
+/// Decodes a shared vertex, which is quantized to 21/21/22 bits within the given domain
+pub fn decode_shared_vertex(min: Vec3, max: Vec3, v: u64) -> Vec3 {
+    let extent = max - min;
+
+    let x = (v & 0x1fffff) as f32 / 0x1fffff as f32;
+    let y = ((v >> 21) & 0x1fffff) as f32 / 0x1fffff as f32;
+    let z = (v >> 42) as f32 / 0x3fffff as f32;
+
+    min + Vec3::new(x, y, z) * extent
+}
+
+#[binread]
+#[derive(Debug, Default)]
+pub struct hkcdStaticMeshTreeBaseSection {
+    pub nodes: hkArrayIndex,
+    pub unk8: u64,
+
+    #[br(map = Vec4::from_array)]
+    pub domain_min: Vec4,
+    #[br(map = Vec4::from_array)]
+    pub domain_max: Vec4,
+
+    /// Offset (xyz) and scale (xyz) for packed vertices
+    pub codec_parms: [f32; 6],
+    pub first_packed_vertex: u32,
+    /// Offset into the shared vertex index array (upper 24 bits) and count (lower 8 bits)
+    pub shared_vertices: u32,
+    /// Offset into the primitive array (upper 24 bits) and count (lower 8 bits)
+    pub primitives: u32,
+    pub data_runs: u32,
+    pub num_packed_vertices: u8,
+    pub num_shared_indices: u8,
+    pub leaf_index: u16,
+    pub page: u8,
+    pub flags: u8,
+    pub layer_data: u8,
+    pub unused_data: u8,
+}
+
+impl hkcdStaticMeshTreeBaseSection {
+    pub fn shared_vertices_offset(&self) -> usize {
+        (self.shared_vertices >> 8) as usize
+    }
+
+    pub fn primitives_range(&self) -> std::ops::Range<usize> {
+        let start = (self.primitives >> 8) as usize;
+        start..start + (self.primitives & 0xff) as usize
+    }
+
+    /// Decodes a packed vertex, which is quantized to 11/11/10 bits using the section codec parameters
+    pub fn decode_packed_vertex(&self, v: u32) -> Vec3 {
+        let [ox, oy, oz, sx, sy, sz] = self.codec_parms;
+
+        Vec3::new(
+            ox + (v & 0x7ff) as f32 * sx,
+            oy + ((v >> 11) & 0x7ff) as f32 * sy,
+            oz + (v >> 22) as f32 * sz,
+        )
+    }
+}
+
+#[binread]
+#[derive(Debug, Clone, Copy)]
+pub struct hkcdStaticMeshTreeBasePrimitive {
+    pub indices: [u8; 4],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrimitiveType {
+    Triangle([u8; 3]),
+    Quad([u8; 4]),
+    /// Reference to a custom (convex) primitive, which we don't decode
+    Custom,
+}
+
+impl hkcdStaticMeshTreeBasePrimitive {
+    pub fn primitive_type(&self) -> PrimitiveType {
+        let [a, b, c, d] = self.indices;
+        if a == b {
+            PrimitiveType::Custom
+        } else if c == d {
+            PrimitiveType::Triangle([a, b, c])
+        } else {
+            PrimitiveType::Quad([a, b, c, d])
+        }
+    }
+}
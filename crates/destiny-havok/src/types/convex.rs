@@ -0,0 +1,55 @@
+//! Simple convex shapes. Radius is the convex radius inherited from hkpConvexShape
+
+use binrw::binread;
+use glam::Vec4;
+
+#[binread]
+#[derive(Debug)]
+pub struct hkpBoxShape {
+    pub unk0: [u32; 10],
+    pub radius: f32,
+    pub unk2c: u32,
+
+    #[br(map = Vec4::from_array)]
+    pub half_extents: Vec4,
+}
+
+#[binread]
+#[derive(Debug)]
+pub struct hkpSphereShape {
+    pub unk0: [u32; 10],
+    pub radius: f32,
+    pub unk2c: u32,
+}
+
+#[binread]
+#[derive(Debug)]
+pub struct hkpCapsuleShape {
+    pub unk0: [u32; 10],
+    pub radius: f32,
+    pub unk2c: u32,
+
+    #[br(map = Vec4::from_array)]
+    pub vertex_a: Vec4,
+    #[br(map = Vec4::from_array)]
+    pub vertex_b: Vec4,
+}
+
+#[binread]
+#[derive(Debug)]
+pub struct hkpTriangleShape {
+    pub unk0: [u32; 10],
+    pub radius: f32,
+    pub welding_info: u16,
+    pub welding_type: u8,
+    pub is_extruded: u8,
+
+    #[br(map = Vec4::from_array)]
+    pub vertex_a: Vec4,
+    #[br(map = Vec4::from_array)]
+    pub vertex_b: Vec4,
+    #[br(map = Vec4::from_array)]
+    pub vertex_c: Vec4,
+    #[br(map = Vec4::from_array)]
+    pub extrusion: Vec4,
+}
@@ -1,7 +1,7 @@
 use binrw::binread;
 use glam::{Vec3, Vec4};
 
-use super::hkArrayIndex;
+use super::{hkArrayIndex, hkPointerIndex};
 
 #[binread]
 #[derive(Debug)]
@@ -17,8 +17,20 @@ pub struct hkpConvexVerticesShape {
 
     pub unk70: [u64; 2],
 
+    /// Outward facing planes of the hull (xyz = normal, w = distance), one per face
     pub plane_equations: hkArrayIndex,
-    // pub connectivity: hkpConvexVerticesConnectivity,
+    pub connectivity: hkPointerIndex, // ?
+}
+
+/// Faces of a convex vertices shape. Optional, shapes without it only have their vertices and planes
+#[binread]
+#[derive(Debug)]
+pub struct hkpConvexVerticesConnectivity {
+    pub unk0: [u64; 2],
+
+    /// Vertex indices of all faces, `num_vertices_per_face` at a time
+    pub vertex_indices: hkArrayIndex,
+    pub num_vertices_per_face: hkArrayIndex,
 }
 
 #[binread]
@@ -2,6 +2,8 @@
 
 pub mod bvtree;
 pub mod compound_shape;
+pub mod compressed_mesh;
+pub mod convex;
 pub mod convex_vertices;
 pub mod unknown;
 
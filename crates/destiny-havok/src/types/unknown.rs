@@ -5,6 +5,7 @@ use glam::Vec4;
 
 use super::{hkArrayIndex, hkPointerIndex};
 
+/// hkpListShape?
 #[binread]
 #[derive(Debug)]
 pub struct Unk81 {
@@ -24,5 +25,7 @@ pub struct Unk81 {
 #[derive(Debug)]
 pub struct Unk84 {
     pub shape: hkPointerIndex,
-    pub unk8: [u64; 3],
+    pub collision_filter_info: u32, // ?
+    pub unkc: u32,
+    pub unk10: [u64; 2],
 }
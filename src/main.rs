@@ -21,7 +21,7 @@ use std::{
     time::{Duration, Instant},
 };
 
-use alkahest_data::{map::SBubbleParentShallow, render_globals::SRenderGlobals, tag::ExtendedHash};
+use alkahest_data::{map::SBubbleParent, render_globals::SRenderGlobals, tag::ExtendedHash};
 use anyhow::Context;
 use binrw::BinReaderExt;
 use clap::Parser;
@@ -160,6 +160,10 @@ struct Args {
 
     #[arg(long)]
     lowres: bool,
+
+    /// Scan the TFX bytecode of all techniques and scopes, write a report to the given file and exit
+    #[arg(long)]
+    scan_tfx: Option<String>,
 }
 
 #[tokio::main]
@@ -262,6 +266,17 @@ pub async fn main() -> anyhow::Result<()> {
 
     *PACKAGE_MANAGER.write() = Some(Arc::new(pm));
 
+    if let Some(report_path) = &args.scan_tfx {
+        let report = render::bytecode::scanner::scan_packages()?;
+        std::fs::write(report_path, report.to_text())?;
+        info!(
+            "Scanned {} TFX programs ({} failed to parse), report written to {report_path}",
+            report.programs, report.parse_failures
+        );
+
+        return Ok(());
+    }
+
     let window = winit::window::WindowBuilder::new()
         .with_title("Alkahest")
         .with_inner_size(config::with(|c| {
@@ -281,9 +296,9 @@ pub async fn main() -> anyhow::Result<()> {
 
     let stringmap = Arc::new(GlobalStringmap::load().context("Failed to load global strings")?);
 
-    for (mt, _) in package_manager().get_all_by_reference(SBubbleParentShallow::ID.unwrap()) {
-        if let Ok(bub) = package_manager().read_tag_struct::<SBubbleParentShallow>(mt) {
-            if bub.child_map.is_none() {
+    for (mt, _) in package_manager().get_all_by_reference(SBubbleParent::ID.unwrap()) {
+        if let Ok(bub) = package_manager().read_tag_struct::<SBubbleParent>(mt) {
+            if bub.child_map.hash().is_none() {
                 println!("Potential lynx map: {}", stringmap.get(bub.map_name));
             }
         }
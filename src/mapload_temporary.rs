@@ -12,14 +12,14 @@ use alkahest_data::{
     common::ResourceHash,
     entity::{SEntityModel, Unk808072c5, Unk8080906b, Unk80809905, Unk80809c0f},
     map::{
-        SBubbleParent, SBubbleParentShallow, SLightCollection, SMapDataTable, SShadowingLight,
-        SSlipSurfaceVolume, STerrain, Unk808068d4, Unk80806aa7, Unk80806abd, Unk80806ac2,
-        Unk80806b7f, Unk80806c98, Unk80806d19, Unk80806e68, Unk80806ef4, Unk8080714b, Unk80808246,
-        Unk808085c2, Unk80808604, Unk80808cb7, Unk80809178, Unk8080917b, Unk80809802,
+        SBubbleParent, SLightCollection, SMapDataTable, SShadowingLight, SSlipSurfaceVolume,
+        STerrain, Unk808068d4, Unk80806aa7, Unk80806abd, Unk80806ac2, Unk80806b7f, Unk80806c98,
+        Unk80806d19, Unk80806e68, Unk80806ef4, Unk8080714b, Unk80808246, Unk808085c2, Unk80808604,
+        Unk80808cb7, Unk80809178, Unk8080917b, Unk80809802,
     },
     occlusion::{SObjectOcclusionBounds, AABB},
     statics::SStaticMesh,
-    ExtendedHash, Tag,
+    ExtendedHash, LazyTag, Tag,
 };
 use anyhow::Context;
 use binrw::BinReaderExt;
@@ -58,7 +58,7 @@ use crate::{
 
 pub fn get_map_name(map_hash: TagHash, stringmap: &GlobalStringmap) -> anyhow::Result<String> {
     let _span = info_span!("Get map name", %map_hash).entered();
-    let map_name = match package_manager().read_tag_struct::<SBubbleParentShallow>(map_hash) {
+    let map_name = match package_manager().read_tag_struct::<SBubbleParent>(map_hash) {
         Ok(m) => m.map_name,
         Err(e) => {
             anyhow::bail!("Failed to load map {map_hash}: {e}");
@@ -85,7 +85,7 @@ pub fn query_activity_maps(
     let mut maps = vec![];
     for u1 in &activity.unk50 {
         for map in &u1.map_references {
-            let map_name = match package_manager().read_tag_struct::<SBubbleParentShallow>(*map) {
+            let map_name = match package_manager().read_tag_struct::<SBubbleParent>(*map) {
                 Ok(m) => m.map_name,
                 Err(e) => {
                     error!("Failed to load map {map}: {e:?}");
@@ -132,7 +132,7 @@ pub async fn load_map_scene(
         Err(e) => return Err(e.context("Failed to read SBubbleParent")),
     };
 
-    let mut activity_entrefs: Vec<(Tag<Unk80808e89>, ResourceHash, ResourceOriginType)> =
+    let mut activity_entrefs: Vec<(LazyTag<Unk80808e89>, ResourceHash, ResourceOriginType)> =
         Default::default();
     if let Some(activity_hash) = activity_hash {
         let activity: SActivity = package_manager().read_tag_struct(activity_hash)?;
@@ -187,14 +187,14 @@ pub async fn load_map_scene(
 
     let mut entity_worldid_name_map: FxHashMap<u64, String> = Default::default();
     for (e, _, _) in &activity_entrefs {
-        for resource in &e.unk18.entity_resources {
+        for resource in &e.get()?.unk18.get()?.entity_resources {
             if let Some(strings) = get_entity_labels(resource.entity_resource) {
                 entity_worldid_name_map.extend(strings);
             }
         }
     }
 
-    for map_container in &bubble_parent.child_map.map_resources {
+    for map_container in &bubble_parent.child_map.get()?.map_resources {
         for table in &map_container.data_tables {
             let table_data = package_manager().read_tag(table.hash()).unwrap();
             let mut cur = Cursor::new(&table_data);
@@ -218,7 +218,7 @@ pub async fn load_map_scene(
 
     let mut unknown_res_types: FxHashSet<u32> = Default::default();
     for (e, phase_name2, origin) in activity_entrefs {
-        for resource in &e.unk18.entity_resources {
+        for resource in &e.get()?.unk18.get()?.entity_resources {
             if resource.entity_resource.is_some() {
                 let data = package_manager().read_tag(resource.entity_resource)?;
                 let mut cur = Cursor::new(&data);
@@ -1972,7 +1972,7 @@ fn get_entity_labels(entity: TagHash) -> Option<FxHashMap<u64, String>> {
 //                         };
 
 //                         if let Ok(bubble) =
-//                             package_manager().read_tag_struct::<SBubbleParentShallow>(map32)
+//                             package_manager().read_tag_struct::<SBubbleParent>(map32)
 //                         {
 //                             let name = destination_strings
 //                                 .get(&bubble.map_name.0)
@@ -25,7 +25,11 @@ use tracing::{
 use tracing_subscriber::Layer;
 use winit::window::Window;
 
-use super::{gui::ViewerWindows, technique_viewer::TechniqueViewer, texture_viewer::TextureViewer};
+use super::{
+    gui::ViewerWindows,
+    technique_viewer::{record_trace, TechniqueViewer},
+    texture_viewer::TextureViewer,
+};
 use crate::{
     camera::FpsCamera,
     ecs::{
@@ -38,7 +42,11 @@ use crate::{
     overlays::gui::Overlay,
     packages::package_manager,
     render::{
-        bytecode::opcodes::TfxBytecodeOp,
+        bytecode::{
+            externs::TfxShaderStage,
+            opcodes::TfxBytecodeOp,
+            verifier::{TfxBytecodeVerifier, TfxDiagnosticSeverity},
+        },
         cbuffer::ConstantBufferCached,
         dcs::DcsShared,
         renderer::{Renderer, RendererShared},
@@ -46,7 +54,7 @@ use crate::{
         EntityRenderer,
     },
     resources::Resources,
-    technique::Technique,
+    technique::{shader_cbuffer_elements, Technique},
 };
 
 // ! Do NOT swap this RwLock to our own implementation, as it will cause infinite recursion
@@ -571,6 +579,126 @@ fn execute_command(
 
             // 3C0100340003293401340212232200350334050E44043C01003406032934073408122322003509340B0E440D
         }
+        "vtfx" | "verify_tfx" => {
+            let techniques: Vec<TagHash> = if let Some(arg) = args.first() {
+                match parse_extended_hash(arg) {
+                    Ok(o) => vec![o.hash32()],
+                    Err(e) => {
+                        error!("Failed to parse tag: {e}");
+                        return;
+                    }
+                }
+            } else {
+                package_manager()
+                    .get_all_by_reference(STechnique::ID.unwrap())
+                    .into_iter()
+                    .map(|(t, _)| t)
+                    .collect()
+            };
+
+            let verbose = techniques.len() == 1;
+            let mut programs = 0;
+            let mut parse_failures = 0;
+            let mut programs_with_errors = 0;
+            let mut programs_with_warnings = 0;
+            for tag in techniques {
+                let technique: STechnique = match package_manager().read_tag_struct(tag) {
+                    Ok(o) => o,
+                    Err(e) => {
+                        error!("Failed to read technique tag {tag}: {e}");
+                        continue;
+                    }
+                };
+
+                for (stage, shader) in technique.all_valid_shaders() {
+                    if shader.bytecode.is_empty() {
+                        continue;
+                    }
+
+                    programs += 1;
+                    let opcodes =
+                        match TfxBytecodeOp::parse_all(&shader.bytecode, binrw::Endian::Little) {
+                            Ok(o) => o,
+                            Err(e) => {
+                                parse_failures += 1;
+                                if verbose {
+                                    error!("{tag} {stage:?}: Failed to decode TFX bytecode: {e}");
+                                }
+                                continue;
+                            }
+                        };
+
+                    let diagnostics = TfxBytecodeVerifier::verify(
+                        &opcodes,
+                        shader.bytecode_constants.len(),
+                        shader_cbuffer_elements(shader),
+                    );
+
+                    if diagnostics
+                        .iter()
+                        .any(|d| d.severity == TfxDiagnosticSeverity::Error)
+                    {
+                        programs_with_errors += 1;
+                    } else if !diagnostics.is_empty() {
+                        programs_with_warnings += 1;
+                    }
+
+                    for d in diagnostics {
+                        match d.severity {
+                            TfxDiagnosticSeverity::Warning => warn!("{tag} {stage:?}: {d}"),
+                            TfxDiagnosticSeverity::Error => error!("{tag} {stage:?}: {d}"),
+                        }
+                    }
+                }
+            }
+
+            info!(
+                "Verified {programs} TFX programs: {programs_with_errors} with errors, {programs_with_warnings} with warnings, {parse_failures} failed to parse"
+            );
+        }
+        "tfx_trace" | "dump_tfx_trace" => {
+            if args.len() < 2 {
+                error!("Usage: tfx_trace <technique> <vs|ps> [output path]");
+                return;
+            }
+
+            let tag = match parse_extended_hash(args[0]) {
+                Ok(o) => o.hash32(),
+                Err(e) => {
+                    error!("Failed to parse tag: {e}");
+                    return;
+                }
+            };
+
+            let stage = match args[1].to_lowercase().as_str() {
+                "vs" | "vertex" => TfxShaderStage::Vertex,
+                "ps" | "pixel" => TfxShaderStage::Pixel,
+                s => {
+                    error!("Unsupported shader stage '{s}', expected vs or ps");
+                    return;
+                }
+            };
+
+            let trace = match record_trace(resources, tag, stage) {
+                Ok(o) => o,
+                Err(e) => {
+                    error!("Failed to trace {tag} {stage:?}: {e}");
+                    return;
+                }
+            };
+
+            let path = args
+                .get(2)
+                .map(|p| p.to_string())
+                .unwrap_or_else(|| format!("tfx_trace_{tag}_{stage:?}.json"));
+            match trace
+                .to_json()
+                .and_then(|json| Ok(std::fs::write(&path, json)?))
+            {
+                Ok(_) => info!("Wrote {} step TFX trace to {path}", trace.steps.len()),
+                Err(e) => error!("Failed to write TFX trace: {e}"),
+            }
+        }
         "reset_all_to_original_pos" => {
             if let Some(maps) = resources.get::<MapList>() {
                 if let Some(map) = maps.current_map() {
@@ -84,6 +84,26 @@ impl Overlay for RenderSettingsOverlay {
                 &mut render_settings.evaluate_bytecode,
                 "Evaluate TFX bytecode (WIP)",
             );
+            ui.indent("render settings bytecode option indent", |ui| {
+                ui.add_enabled_ui(render_settings.evaluate_bytecode, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut render_settings.freeze_bytecode_time, "Freeze time");
+                        ui.add_enabled(
+                            render_settings.freeze_bytecode_time,
+                            egui::DragValue::new(&mut render_settings.frozen_bytecode_time)
+                                .speed(0.05)
+                                .suffix("s"),
+                        );
+                    });
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut render_settings.fixed_bytecode_seed, "Fixed seed");
+                        ui.add_enabled(
+                            render_settings.fixed_bytecode_seed,
+                            egui::DragValue::new(&mut render_settings.bytecode_seed),
+                        );
+                    });
+                });
+            });
             ui.checkbox(&mut render_settings.alpha_blending, "Enable color blending");
             if render_settings.alpha_blending {
                 egui::ComboBox::from_label("Blend Override").show_index(
@@ -561,6 +581,12 @@ pub struct RenderSettings {
     pub compositor_mode: usize,
     pub blend_override: usize,
     pub evaluate_bytecode: bool,
+    /// Evaluate bytecode (and the frame scope) at `frozen_bytecode_time` instead of real time
+    pub freeze_bytecode_time: bool,
+    pub frozen_bytecode_time: f32,
+    /// Offset the input of the random bytecode ops by `bytecode_seed`
+    pub fixed_bytecode_seed: bool,
+    pub bytecode_seed: u32,
     pub ambient_light: Vec4,
     pub light_dir: Vec3,
     pub light_color: Vec4,
@@ -598,6 +624,10 @@ impl Default for RenderSettings {
             render_shadows: true,
             blend_override: 0,
             evaluate_bytecode: true,
+            freeze_bytecode_time: false,
+            frozen_bytecode_time: 0.0,
+            fixed_bytecode_seed: false,
+            bytecode_seed: 0,
             ambient_light: Vec4::splat(0.04),
             light_dir: Vec3::NEG_Z,
             light_color: Vec4::ONE,
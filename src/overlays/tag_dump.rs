@@ -1,6 +1,6 @@
 use std::io::Write;
 
-use alkahest_data::ExtendedHash;
+use alkahest_data::{dump, schema::SchemaSet, ExtendedHash};
 use destiny_pkg::{package::UEntryHeader, TagHash};
 use fs_err::File;
 use tracing::error;
@@ -20,6 +20,10 @@ pub struct TagDumper {
     message: Option<Result<(TagHash, UEntryHeader), String>>,
 
     use_full_hash: bool,
+
+    /// Schema to apply to dumped tags, see [`alkahest_data::schema`]. Reloaded on every dump
+    schema_path: String,
+    schema_struct: String,
 }
 
 impl TagDumper {
@@ -30,6 +34,8 @@ impl TagDumper {
             tag_string: String::new(),
             message: None,
             use_full_hash: true,
+            schema_path: String::new(),
+            schema_struct: String::new(),
         }
     }
 
@@ -44,18 +50,38 @@ impl TagDumper {
                 entry.file_type,
                 entry.file_subtype,
             );
+            let data = package_manager().read_tag(tag).unwrap();
             let mut file = File::create(&file_path).unwrap();
-            if let Err(e) = file.write_all(package_manager().read_tag(tag).unwrap().as_slice()) {
+            if let Err(e) = file.write_all(&data) {
                 error!("Failed to write tag {file_path} to disk: {e}");
-                Err(format!("Failed to dump tag!\n{e}"))
-            } else {
-                Ok(entry)
+                return Err(format!("Failed to dump tag!\n{e}"));
+            }
+
+            if !self.schema_struct.is_empty() {
+                if let Err(e) = self.dump_schema(tag, &data) {
+                    error!("Failed to apply schema to tag {tag}: {e:?}");
+                    return Err(format!("Failed to apply schema!\n{e:#}"));
+                }
             }
+
+            Ok(entry)
         } else {
             error!("Unable to find tag {tag}!");
             Err("Failed to dump tag!".to_string())
         }
     }
+
+    fn dump_schema(&self, tag: TagHash, data: &[u8]) -> anyhow::Result<()> {
+        let schema = SchemaSet::load(&self.schema_path)?;
+        let value = schema.read(&self.schema_struct, data)?;
+
+        std::fs::write(
+            format!("tags/{tag}_{}.json", self.schema_struct),
+            dump::to_json(&value)?,
+        )?;
+
+        Ok(())
+    }
 }
 
 impl Overlay for TagDumper {
@@ -88,6 +114,13 @@ impl Overlay for TagDumper {
                         && ui.input(|i| i.key_pressed(egui::Key::Enter))
                 };
 
+                ui.collapsing("Schema", |ui| {
+                    ui.label("Schema file (RON)");
+                    ui.text_edit_singleline(&mut self.schema_path);
+                    ui.label("Struct");
+                    ui.text_edit_singleline(&mut self.schema_struct);
+                });
+
                 if ui.button("Dump!").clicked() || pressed_enter {
                     if self.use_full_hash {
                         let tag = u32::from_str_radix(&self.tag_string, 16);
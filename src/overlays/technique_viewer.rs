@@ -3,7 +3,9 @@ use alkahest_data::{
     texture::STextureHeader,
     ExtendedHash,
 };
-use egui::{vec2, Image, ImageSource, Sense, TextureId};
+use anyhow::Context;
+use destiny_pkg::TagHash;
+use egui::{vec2, Color32, Image, ImageSource, RichText, Sense, TextureId};
 use glam::Vec4;
 use rustc_hash::FxHashMap;
 use tiger_parse::PackageManagerExt;
@@ -16,11 +18,18 @@ use crate::{
     packages::package_manager,
     render::{
         bytecode::{
-            decompiler::TfxBytecodeDecompiler, externs::TfxShaderStage, opcodes::TfxBytecodeOp,
+            assembler::TfxBytecodeAssembler,
+            decompiler::TfxBytecodeDecompiler,
+            externs::TfxShaderStage,
+            interpreter::TfxTrace,
+            opcodes::TfxBytecodeOp,
+            verifier::{TfxBytecodeVerifier, TfxDiagnostic, TfxDiagnosticSeverity},
         },
         dcs::DcsShared,
-        DeviceContextSwapchain,
+        renderer::{Renderer, RendererShared},
+        DeviceContextSwapchain, RenderData,
     },
+    technique::{shader_cbuffer_elements, TechniqueStage},
     texture::Texture,
 };
 
@@ -61,6 +70,7 @@ impl TechniqueViewer {
                     alkahest_data::tfx::TfxShaderStage::Domain => TfxShaderStage::Domain,
                 },
                 shader.1.clone(),
+                tag.hash32(),
                 &dcs,
                 gui,
             ));
@@ -106,7 +116,7 @@ impl Overlay for TechniqueViewer {
                     });
                 }
 
-                for s in &self.shaders {
+                for s in &mut self.shaders {
                     ui.label(format!("Stage: {:?}", s.stage));
                     s.draw(ui, window, resources, gui);
 
@@ -132,6 +142,18 @@ impl Overlay for TechniqueViewer {
 pub struct TechniqueShaderViewer {
     stage: TfxShaderStage,
     header: STechniqueShader,
+    technique: TagHash,
+
+    assembly: String,
+    assembly_status: Option<Result<String, String>>,
+    diagnostics: Vec<TfxDiagnostic>,
+
+    trace: Option<TfxTrace>,
+    trace_step: usize,
+    trace_error: Option<String>,
+    /// cbuffer elements to break on when they're written by a pop_output op
+    breakpoints: Vec<usize>,
+    breakpoint_input: usize,
 
     textures: FxHashMap<ExtendedHash, (STextureHeader, Texture, TextureId)>,
 }
@@ -140,6 +162,7 @@ impl TechniqueShaderViewer {
     pub fn new(
         stage: TfxShaderStage,
         header: STechniqueShader,
+        technique: TagHash,
         dcs: &DeviceContextSwapchain,
         gui: &mut GuiContext<'_>,
     ) -> Self {
@@ -165,15 +188,192 @@ impl TechniqueShaderViewer {
             textures.insert(assignment.texture, (header, texture, texture_egui));
         }
 
+        let assembly = Self::disassemble(&header);
+        let diagnostics = TfxBytecodeOp::parse_all(&header.bytecode, binrw::Endian::Little)
+            .map(|opcodes| Self::verify(&header, &opcodes))
+            .unwrap_or_default();
+
         Self {
             stage,
             header,
+            technique,
+            assembly,
+            assembly_status: None,
+            diagnostics,
+            trace: None,
+            trace_step: 0,
+            trace_error: None,
+            breakpoints: vec![],
+            breakpoint_input: 0,
             textures,
         }
     }
 
+    fn disassemble(header: &STechniqueShader) -> String {
+        match TfxBytecodeOp::parse_all(&header.bytecode, binrw::Endian::Little) {
+            Ok(opcodes) => opcodes
+                .iter()
+                .map(|op| op.disassemble(None))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            Err(e) => format!("// Failed to parse bytecode: {e}"),
+        }
+    }
+
+    fn verify(header: &STechniqueShader, opcodes: &[TfxBytecodeOp]) -> Vec<TfxDiagnostic> {
+        TfxBytecodeVerifier::verify(
+            opcodes,
+            header.bytecode_constants.len(),
+            shader_cbuffer_elements(header),
+        )
+    }
+
+    /// Assembles the edited bytecode and swaps it into the loaded technique
+    fn inject_assembly(
+        &mut self,
+        resources: &crate::resources::Resources,
+    ) -> Result<String, String> {
+        let opcodes =
+            TfxBytecodeAssembler::assemble(&self.assembly).map_err(|e| format!("{e:?}"))?;
+        let bytes = TfxBytecodeOp::write_all(&opcodes, binrw::Endian::Little);
+        self.diagnostics = Self::verify(&self.header, &opcodes);
+
+        with_technique_stage(resources, self.technique, self.stage, |_, _, stage| {
+            stage.set_bytecode(opcodes)
+        })
+        .map_err(|e| e.to_string())?;
+
+        Ok(hex::encode(bytes))
+    }
+
+    fn draw_debugger(&mut self, ui: &mut egui::Ui, resources: &crate::resources::Resources) {
+        ui.horizontal(|ui| {
+            if ui
+                .button("Record trace")
+                .on_hover_text("Evaluates the bytecode once and records every step")
+                .clicked()
+            {
+                match record_trace(resources, self.technique, self.stage) {
+                    Ok(trace) => {
+                        self.trace = Some(trace);
+                        self.trace_step = 0;
+                        self.trace_error = None;
+                    }
+                    Err(e) => self.trace_error = Some(e.to_string()),
+                }
+            }
+
+            if let Some(trace) = &self.trace {
+                if ui.button("Copy JSON").clicked() {
+                    match trace.to_json() {
+                        Ok(json) => ui.output_mut(|o| o.copied_text = json),
+                        Err(e) => error!("Failed to serialize TFX trace: {e}"),
+                    }
+                }
+            }
+        });
+
+        if let Some(e) = &self.trace_error {
+            ui.label(RichText::new(e).color(Color32::RED));
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("Break on pop_output to cb0[");
+            ui.add(egui::DragValue::new(&mut self.breakpoint_input).clamp_range(0..=255));
+            ui.label("]");
+            if ui.button("Add").clicked() && !self.breakpoints.contains(&self.breakpoint_input) {
+                self.breakpoints.push(self.breakpoint_input);
+                self.breakpoints.sort_unstable();
+            }
+        });
+
+        if !self.breakpoints.is_empty() {
+            ui.horizontal_wrapped(|ui| {
+                ui.label("Breakpoints:");
+                self.breakpoints.retain(|e| {
+                    !ui.button(format!("cb0[{e}] x"))
+                        .on_hover_text("Remove breakpoint")
+                        .clicked()
+                });
+            });
+        }
+
+        let Some(trace) = &self.trace else {
+            return;
+        };
+
+        if let Some((ip, e)) = &trace.error {
+            ui.label(
+                RichText::new(format!("Evaluation failed at IP {ip}: {e}")).color(Color32::RED),
+            );
+        }
+
+        if trace.steps.is_empty() {
+            ui.label("No ops were executed");
+            return;
+        }
+
+        ui.label(
+            RichText::new(
+                "Stepping replays the recorded trace, record a new one to see changes in externs or injected bytecode",
+            )
+            .weak(),
+        );
+
+        let last_step = trace.steps.len() - 1;
+        ui.horizontal(|ui| {
+            if ui.button("Restart").clicked() {
+                self.trace_step = 0;
+            }
+            if ui.button("Step back").clicked() {
+                self.trace_step = self.trace_step.saturating_sub(1);
+            }
+            if ui.button("Step").clicked() {
+                self.trace_step = (self.trace_step + 1).min(last_step);
+            }
+            if ui.button("Continue").clicked() {
+                self.trace_step = trace
+                    .next_output_write(self.trace_step, &self.breakpoints)
+                    .unwrap_or(last_step);
+            }
+        });
+        ui.add(egui::Slider::new(&mut self.trace_step, 0..=last_step).text("Step"));
+        self.trace_step = self.trace_step.min(last_step);
+
+        let step = &trace.steps[self.trace_step];
+        ui.label(RichText::new(format!("IP {}: {}", step.ip, step.op)).monospace());
+
+        let buffer = trace.buffer_at(self.trace_step);
+        ui.columns(3, |columns| {
+            columns[0].label(RichText::new("Stack (top first)").strong());
+            for (i, v) in step.stack.iter().rev().enumerate() {
+                columns[0].label(RichText::new(format!("{i}: {}", format_vec4(*v))).monospace());
+            }
+
+            columns[1].label(RichText::new("Temps").strong());
+            for (i, v) in step.temp.iter().enumerate() {
+                columns[1].label(RichText::new(format!("t{i}: {}", format_vec4(*v))).monospace());
+            }
+
+            columns[2].label(RichText::new("cb0").strong());
+            egui::ScrollArea::vertical()
+                .id_source("tfx debugger cbuffer")
+                .max_height(320.0)
+                .show(&mut columns[2], |ui| {
+                    for (i, v) in buffer.iter().enumerate() {
+                        let text = RichText::new(format!("[{i}]: {}", format_vec4(*v))).monospace();
+                        if step.cb_writes.iter().any(|(e, _)| *e == i) {
+                            ui.label(text.color(Color32::GREEN));
+                        } else {
+                            ui.label(text);
+                        }
+                    }
+                });
+        });
+    }
+
     pub fn draw(
-        &self,
+        &mut self,
         ui: &mut egui::Ui,
         _window: &winit::window::Window,
         resources: &mut crate::resources::Resources,
@@ -204,6 +404,64 @@ impl TechniqueShaderViewer {
                 }
             }
         }
+        if !self.diagnostics.is_empty() {
+            ui.collapsing(
+                format!("Bytecode diagnostics ({})", self.diagnostics.len()),
+                |ui| {
+                    for d in &self.diagnostics {
+                        let color = match d.severity {
+                            TfxDiagnosticSeverity::Warning => Color32::YELLOW,
+                            TfxDiagnosticSeverity::Error => Color32::RED,
+                        };
+
+                        ui.label(RichText::new(d.to_string()).color(color));
+                    }
+                },
+            );
+        }
+
+        ui.collapsing("Bytecode assembly", |ui| {
+            ui.add(
+                egui::TextEdit::multiline(&mut self.assembly)
+                    .code_editor()
+                    .desired_width(f32::INFINITY),
+            );
+
+            ui.horizontal(|ui| {
+                if ui.button("Assemble & inject").clicked() {
+                    self.assembly_status = Some(self.inject_assembly(resources));
+                }
+
+                if ui.button("Reset").clicked() {
+                    self.assembly = Self::disassemble(&self.header);
+                    self.diagnostics =
+                        TfxBytecodeOp::parse_all(&self.header.bytecode, binrw::Endian::Little)
+                            .map(|opcodes| Self::verify(&self.header, &opcodes))
+                            .unwrap_or_default();
+                    self.assembly_status = None;
+                }
+            });
+
+            match &self.assembly_status {
+                Some(Ok(bytes)) => {
+                    ui.horizontal(|ui| {
+                        ui.label(RichText::new("Injected").color(Color32::GREEN));
+                        if ui.button("Copy bytes").clicked() {
+                            ui.output_mut(|o| o.copied_text = bytes.clone());
+                        }
+                    });
+                }
+                Some(Err(e)) => {
+                    ui.label(RichText::new(e).color(Color32::RED));
+                }
+                None => {}
+            }
+        });
+
+        ui.collapsing("Bytecode debugger", |ui| {
+            self.draw_debugger(ui, resources);
+        });
+
         ui.collapsing(format!("Textures ({})", self.header.textures.len()), |ui| {
             for assignment in &self.header.textures {
                 let mut clicked = false;
@@ -309,3 +567,48 @@ impl TechniqueShaderViewer {
         }
     }
 }
+
+fn format_vec4(v: Vec4) -> String {
+    format!("{:>9.3} {:>9.3} {:>9.3} {:>9.3}", v.x, v.y, v.z, v.w)
+}
+
+/// Runs `f` on a stage of a technique loaded by the renderer
+fn with_technique_stage<R>(
+    resources: &crate::resources::Resources,
+    technique: TagHash,
+    stage: TfxShaderStage,
+    f: impl FnOnce(&Renderer, &RenderData, &TechniqueStage) -> R,
+) -> anyhow::Result<R> {
+    let renderer = resources
+        .get::<RendererShared>()
+        .context("Renderer is not available")?;
+    let renderer = renderer.read();
+    let render_data = renderer.render_data.data();
+    let technique = render_data
+        .techniques
+        .get(&technique)
+        .context("Technique is not loaded by the renderer")?;
+
+    let stage = match stage {
+        TfxShaderStage::Pixel => &technique.stage_pixel,
+        TfxShaderStage::Vertex => &technique.stage_vertex,
+        s => anyhow::bail!("{s:?} stages are not supported"),
+    };
+
+    Ok(f(&renderer, &render_data, stage))
+}
+
+/// Records a bytecode trace for a stage of a technique loaded by the renderer
+pub fn record_trace(
+    resources: &crate::resources::Resources,
+    technique: TagHash,
+    stage: TfxShaderStage,
+) -> anyhow::Result<TfxTrace> {
+    with_technique_stage(
+        resources,
+        technique,
+        stage,
+        |renderer, render_data, stage| stage.trace_bytecode(renderer, render_data),
+    )?
+    .context("Stage has no bytecode to trace")
+}
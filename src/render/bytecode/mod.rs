@@ -1,4 +1,4 @@
-pub mod decompiler;
-pub mod externs;
-pub mod interpreter;
-pub mod opcodes;
+pub use alkahest_tfx::{assembler, decompiler, externs, interpreter, opcodes, verifier};
+
+pub mod renderer_externs;
+pub mod scanner;
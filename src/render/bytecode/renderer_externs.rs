@@ -0,0 +1,397 @@
+use std::mem::{transmute, transmute_copy, ManuallyDrop};
+
+use glam::{Mat4, Vec3, Vec4};
+use windows::Win32::Graphics::Direct3D11::{
+    ID3D11Buffer, ID3D11SamplerState, ID3D11ShaderResourceView,
+};
+
+use super::{
+    externs::{TfxExtern, TfxShaderStage},
+    interpreter::{TfxBindCommand, TfxExternProvider},
+};
+use crate::render::{renderer::Renderer, DeviceContextSwapchain, RenderData};
+
+macro_rules! stage_function_match {
+    ($dcs:expr, $stage:expr, $name:ident, $($arg:expr),+) => {
+        paste::paste! {
+            match $stage {
+                TfxShaderStage::Pixel => $dcs
+                    .context()
+                    .[<PS $name>]($($arg, )*),
+                TfxShaderStage::Vertex => $dcs
+                    .context()
+                    .[<VS $name>]($($arg, )*),
+                TfxShaderStage::Geometry => $dcs
+                    .context()
+                    .[<GS $name>]($($arg, )*),
+                TfxShaderStage::Hull => $dcs
+                    .context()
+                    .[<HS $name>]($($arg, )*),
+                TfxShaderStage::Compute => $dcs
+                    .context()
+                    .[<CS $name>]($($arg, )*),
+                TfxShaderStage::Domain => $dcs
+                    .context()
+                    .[<DS $name>]($($arg, )*),
+            }
+        }
+    };
+}
+
+/// D3D11 binding helpers for [`TfxShaderStage`]
+pub trait TfxShaderStageExt {
+    fn set_shader_resources(
+        &self,
+        dcs: &DeviceContextSwapchain,
+        start_slot: u32,
+        shader_resource_views: Option<&[Option<ID3D11ShaderResourceView>]>,
+    );
+
+    fn set_constant_buffers(
+        &self,
+        dcs: &DeviceContextSwapchain,
+        start_slot: u32,
+        constant_buffers: Option<&[Option<ID3D11Buffer>]>,
+    );
+
+    fn set_samplers(
+        &self,
+        dcs: &DeviceContextSwapchain,
+        start_slot: u32,
+        samplers: Option<&[Option<ID3D11SamplerState>]>,
+    );
+}
+
+impl TfxShaderStageExt for TfxShaderStage {
+    fn set_shader_resources(
+        &self,
+        dcs: &DeviceContextSwapchain,
+        start_slot: u32,
+        shader_resource_views: Option<&[Option<ID3D11ShaderResourceView>]>,
+    ) {
+        unsafe {
+            stage_function_match!(
+                dcs,
+                self,
+                SetShaderResources,
+                start_slot,
+                shader_resource_views
+            );
+        }
+    }
+
+    fn set_constant_buffers(
+        &self,
+        dcs: &DeviceContextSwapchain,
+        start_slot: u32,
+        constant_buffers: Option<&[Option<ID3D11Buffer>]>,
+    ) {
+        unsafe {
+            stage_function_match!(dcs, self, SetConstantBuffers, start_slot, constant_buffers);
+        }
+    }
+
+    fn set_samplers(
+        &self,
+        dcs: &DeviceContextSwapchain,
+        start_slot: u32,
+        samplers: Option<&[Option<ID3D11SamplerState>]>,
+    ) {
+        unsafe {
+            stage_function_match!(dcs, self, SetSamplers, start_slot, samplers);
+        }
+    }
+}
+
+/// Returns the raw pointer of a view as a texture handle.
+/// No reference is added, the handle is only valid for as long as the renderer keeps the view alive
+fn texture_handle(view: &ID3D11ShaderResourceView) -> u64 {
+    unsafe { transmute_copy(view) }
+}
+
+/// Extern provider backed by the live renderer state.
+///
+/// Texture handles borrow the renderer's views without adding a reference, so evaluation output can be dropped
+/// without applying its binds
+pub struct RendererExterns<'a> {
+    pub renderer: &'a Renderer,
+    pub render_data: &'a RenderData,
+}
+
+impl<'a> RendererExterns<'a> {
+    pub fn new(renderer: &'a Renderer, render_data: &'a RenderData) -> Self {
+        Self {
+            renderer,
+            render_data,
+        }
+    }
+
+    /// Applies the resource bindings produced by an evaluation to the device context.
+    /// Returns the number of binds that were skipped because their resource type isn't supported yet
+    pub fn apply_binds(&self, binds: &[TfxBindCommand]) -> usize {
+        let mut skipped = 0;
+        for bind in binds {
+            match *bind {
+                TfxBindCommand::Texture {
+                    stage,
+                    slot,
+                    handle,
+                } => {
+                    // The handle doesn't own a reference, so it must not be released here
+                    let view: ManuallyDrop<Option<ID3D11ShaderResourceView>> =
+                        ManuallyDrop::new(unsafe { transmute(handle) });
+                    stage.set_shader_resources(
+                        &self.renderer.dcs,
+                        slot as u32,
+                        Some(std::slice::from_ref(&*view)),
+                    );
+                }
+                // TODO: Samplers and UAVs are not bound yet, their handles are still placeholders
+                TfxBindCommand::Sampler { .. } | TfxBindCommand::Uav { .. } => skipped += 1,
+            }
+        }
+
+        skipped
+    }
+}
+
+impl TfxExternProvider for RendererExterns<'_> {
+    fn get_extern_float(&self, extern_: TfxExtern, offset: usize) -> anyhow::Result<f32> {
+        match extern_ {
+            // Time externs are resolved from the TfxEvaluationContext by the interpreter
+            TfxExtern::Frame => match offset {
+                // Light mul
+                7 => Ok(*self.renderer.light_mul.read()),
+
+                _ => {
+                    Ok(1.0)
+                    // anyhow::bail!(
+                    //     "get_extern_float: Unsupported extern {extern_:?}+{offset} (0x{:0X})",
+                    //     offset * 4
+                    // )
+                }
+            },
+            TfxExtern::DeferredLight => match offset {
+                4 => Ok(1.0),
+                8 => Ok(1.0),
+                68 => Ok(1.0),
+                72 => Ok(1.0),
+                _u => {
+                    Ok(1.0)
+                    // anyhow::bail!(
+                    //     "get_extern_float: Unsupported deferred light extern offset {u} (0x{:0X})",
+                    //     u * 16
+                    // )
+                }
+            },
+            _u => {
+                Ok(1.0)
+                // anyhow::bail!(
+                //     "get_extern_float: Unsupported extern {u:?}+{offset} (0x{:0X})",
+                //     offset * 4
+                // )
+            }
+        }
+    }
+
+    fn get_extern_vec4(&self, extern_: TfxExtern, offset: usize) -> anyhow::Result<Vec4> {
+        Ok(match extern_ {
+            TfxExtern::Frame => match offset {
+                // 26.x is something to do with alpha clipping. We keep it disabled, as enabling it causes a fuzzy alpha clip pattern where we dont want it
+                26 => Vec4::ZERO,
+                27 => Vec4::ONE,
+                _u => {
+                    Vec4::ZERO
+                    // anyhow::bail!(
+                    //     "get_extern_vec4: Unsupported frame extern offset {u} (0x{:0X})",
+                    //     u * 16
+                    // )
+                }
+            },
+            TfxExtern::Deferred => match offset {
+                0 => Vec4::new(0.0, 1. / 0.0001, 0.0, 0.0),
+                _u => {
+                    Vec4::ZERO
+                    // anyhow::bail!(
+                    //     "get_extern_vec4: Unsupported deferred extern offset {u} (0x{:0X})",
+                    //     u * 16
+                    // )
+                }
+            },
+            TfxExtern::Atmosphere => match offset {
+                7 => Vec4::splat(1.0),
+                _u => {
+                    Vec4::ZERO
+                    // anyhow::bail!(
+                    //     "get_extern_vec4: Unsupported atmosphere extern offset {u} (0x{:0X})",
+                    //     u * 16
+                    // )
+                }
+            },
+            TfxExtern::DeferredLight => match offset {
+                4 => Vec4::splat(1.0),
+                12 => self.renderer.light_transform.read().translation.extend(1.0),
+                13 => Vec4::splat(1.0),
+                14 => Vec4::splat(1.0),
+                15 => Vec4::splat(1.0),
+                16 => Vec4::splat(1.0),
+                _u => {
+                    Vec4::ZERO
+                    // anyhow::bail!(
+                    //     "get_extern_vec4: Unsupported deferred light extern offset {u} (0x{:0X})",
+                    //     u * 16
+                    // )
+                }
+            },
+            _u => {
+                Vec4::ZERO
+                // anyhow::bail!(
+                //     "get_extern_vec4: Unsupported extern {u:?}+{offset} (0x{:0X})",
+                //     offset * 16
+                // )
+            }
+        })
+    }
+
+    fn get_extern_mat4(&self, extern_: TfxExtern, offset: usize) -> anyhow::Result<Mat4> {
+        Ok(match extern_ {
+            TfxExtern::SimpleGeometry => match offset {
+                0 => {
+                    let light_transform = self.renderer.light_transform.read();
+                    let slight_scale = *self.renderer.light_mat.read();
+
+                    let viewproj = *self.renderer.camera_viewproj.read();
+
+                    viewproj * (light_transform.to_mat4() * slight_scale)
+                }
+                _u => {
+                    Mat4::IDENTITY
+                    // anyhow::bail!(
+                    //     "get_extern_mat4: Unsupported simple geometry extern offset {u} (0x{:0X})",
+                    //     u * 16
+                    // )
+                }
+            },
+            TfxExtern::DeferredLight => match offset {
+                // TODO(cohae): Used for transforming projective textures
+                4 => Mat4::from_scale(Vec3::splat(0.15)),
+                8 => self.renderer.light_transform.read().to_mat4(),
+                _u => {
+                    Mat4::IDENTITY
+                    // anyhow::bail!(
+                    //     "get_extern_mat4: Unsupported deferred light extern offset {u} (0x{:0X})",
+                    //     u * 16
+                    // )
+                }
+            },
+            TfxExtern::View => match offset {
+                0 => Mat4::IDENTITY,
+                16 => Mat4::IDENTITY,
+                40 => *self.renderer.camera_svp_inv.read(),
+                _u => {
+                    Mat4::IDENTITY
+                    // anyhow::bail!(
+                    //     "get_extern_mat4: Unsupported view extern offset {u} (0x{:0X})",
+                    //     u * 16
+                    // )
+                }
+            },
+            _u => {
+                Mat4::IDENTITY
+                // anyhow::bail!(
+                //     "get_extern_mat4: Unsupported extern {u:?}+{offset} (0x{:0X})",
+                //     offset * 16
+                // )
+            }
+        })
+    }
+
+    fn get_extern_texture(&self, extern_: TfxExtern, offset: usize) -> anyhow::Result<u64> {
+        Ok(match extern_ {
+            TfxExtern::Frame => match offset {
+                24 => {
+                    if let Some(ir_lookup) = &self.render_data.iridescence_lookup {
+                        texture_handle(&ir_lookup.view)
+                    } else {
+                        texture_handle(&self.render_data.fallback_texture.view)
+                    }
+                }
+
+                _u => {
+                    0
+                    // anyhow::bail!(
+                    //     "get_extern_u64: Unsupported frame extern offset {u} (0x{:0X})",
+                    //     u * 8
+                    // )
+                }
+            },
+            TfxExtern::Deferred => match offset {
+                7 => texture_handle(&self.renderer.gbuffer.depth.texture_copy_view),
+                9 => texture_handle(&self.renderer.gbuffer.rt0.view),
+                10 => texture_handle(&self.renderer.gbuffer.rt1.view),
+                11 => texture_handle(&self.renderer.gbuffer.rt2.view),
+                12 => texture_handle(&self.renderer.gbuffer.light_diffuse.view),
+                13 => texture_handle(&self.renderer.gbuffer.light_specular.view),
+                14 => texture_handle(&self.renderer.gbuffer.light_ibl_specular.view),
+
+                _u => {
+                    0
+                    // anyhow::bail!(
+                    //     "get_extern_u64: Unsupported deferred extern offset {u} (0x{:0X})",
+                    //     u * 8
+                    // )
+                }
+            },
+            TfxExtern::Decal => match offset {
+                1 => texture_handle(&self.renderer.gbuffer.rt1_clone.view),
+                _u => {
+                    0
+                    // anyhow::bail!(
+                    //     "get_extern_u64: Unsupported decal extern offset {u} (0x{:0X})",
+                    //     u * 8
+                    // )
+                }
+            },
+            TfxExtern::Atmosphere => match offset {
+                11 => texture_handle(&self.render_data.debug_textures[1].view),
+                28 => texture_handle(&self.render_data.blend_texture.view),
+                // 28 => texture_handle(&self.render_data.debug_textures[2].view),
+                _u => {
+                    0
+                    // anyhow::bail!(
+                    //     "get_extern_u64: Unsupported atmosphere extern offset {u} (0x{:0X})",
+                    //     u * 8
+                    // )
+                }
+            },
+            TfxExtern::WaterDisplacement => match offset {
+                0 => texture_handle(&self.render_data.debug_textures[0].view),
+                _u => {
+                    0
+                    // anyhow::bail!(
+                    //     "get_extern_u64: Unsupported water displacement extern offset {u} (0x{:0X})",
+                    //     u * 8
+                    // )
+                }
+            },
+            TfxExtern::ShadowMask => match offset {
+                // 1 => texture_handle(&self.render_data.debug_textures[7].view),
+                1 => texture_handle(&self.render_data.black.view),
+                _u => {
+                    0
+                    // anyhow::bail!(
+                    //     "get_extern_u64: Unsupported shadow mask extern offset {u} (0x{:0X})",
+                    //     u * 8
+                    // )
+                }
+            },
+            _u => {
+                0
+                // anyhow::bail!(
+                //     "get_extern_u64: Unsupported extern {u:?}+{offset} (0x{:0X})",
+                //     offset * 8
+                // )
+            }
+        })
+    }
+}
@@ -0,0 +1,279 @@
+use std::{collections::BTreeMap, fmt::Write};
+
+use alkahest_data::{
+    render_globals::{SRenderGlobals, SScope},
+    technique::STechnique,
+};
+use anyhow::Context;
+use num_traits::FromPrimitive;
+use rayon::iter::{IntoParallelIterator, ParallelExtend, ParallelIterator};
+use tiger_parse::{PackageManagerExt, TigerReadable};
+
+use super::{
+    externs::{TfxExtern, TfxExternType},
+    opcodes::TfxBytecodeOp,
+};
+use crate::packages::package_manager;
+
+/// Maximum number of sample sources kept per opcode
+const MAX_SAMPLES: usize = 8;
+
+#[derive(Default)]
+pub struct TfxOpcodeStats {
+    pub mnemonic: String,
+    pub count: usize,
+    pub programs: usize,
+    /// Programs using this opcode, only collected for unknown opcodes
+    pub samples: Vec<String>,
+}
+
+#[derive(Default)]
+pub struct TfxParseFailureGroup {
+    pub count: usize,
+    /// First error message encountered for this opcode byte
+    pub error: String,
+    pub samples: Vec<String>,
+}
+
+/// Statistics over a set of TFX bytecode programs, used to find out which opcodes still need work
+#[derive(Default)]
+pub struct TfxScanReport {
+    pub programs: usize,
+    pub parse_failures: usize,
+    pub opcodes: BTreeMap<u8, TfxOpcodeStats>,
+    pub failures_by_opcode: BTreeMap<u8, TfxParseFailureGroup>,
+    /// Keyed by (extern, byte offset, type)
+    pub extern_usage: BTreeMap<(u8, usize, TfxExternType), usize>,
+}
+
+impl TfxScanReport {
+    /// Adds a bytecode program to the report. `source` describes where the program came from (eg. a tag hash)
+    pub fn add_program(&mut self, source: &str, bytecode: &[u8]) {
+        if bytecode.is_empty() {
+            return;
+        }
+
+        self.programs += 1;
+
+        let opcodes = match TfxBytecodeOp::parse_all(bytecode, binrw::Endian::Little) {
+            Ok(o) => o,
+            Err(_) => {
+                let (opcodes, failure) =
+                    TfxBytecodeOp::parse_partial(bytecode, binrw::Endian::Little);
+
+                if let Some((offset, e)) = failure {
+                    self.parse_failures += 1;
+
+                    let group = self.failures_by_opcode.entry(bytecode[offset]).or_default();
+                    group.count += 1;
+                    if group.error.is_empty() {
+                        group.error = e.to_string().lines().next().unwrap_or_default().to_string();
+                    }
+                    if group.samples.len() < MAX_SAMPLES {
+                        group.samples.push(source.to_string());
+                    }
+                }
+
+                opcodes
+            }
+        };
+
+        let mut seen = [false; 256];
+        for op in &opcodes {
+            let opcode = op.opcode();
+            let stats = self
+                .opcodes
+                .entry(opcode)
+                .or_insert_with(|| TfxOpcodeStats {
+                    mnemonic: mnemonic(op),
+                    ..Default::default()
+                });
+
+            stats.count += 1;
+            if !seen[opcode as usize] {
+                seen[opcode as usize] = true;
+                stats.programs += 1;
+
+                if stats.mnemonic.starts_with("unk") && stats.samples.len() < MAX_SAMPLES {
+                    stats.samples.push(source.to_string());
+                }
+            }
+
+            let extern_read = match *op {
+                TfxBytecodeOp::PushExternInputFloat { extern_, offset } => {
+                    Some((extern_, offset as usize * 4, TfxExternType::Float))
+                }
+                TfxBytecodeOp::PushExternInputVec4 { extern_, offset } => {
+                    Some((extern_, offset as usize * 16, TfxExternType::Vec4))
+                }
+                TfxBytecodeOp::PushExternInputMat4 { extern_, offset } => {
+                    Some((extern_, offset as usize * 16, TfxExternType::Mat4))
+                }
+                TfxBytecodeOp::PushExternInputTexture { extern_, offset } => {
+                    Some((extern_, offset as usize * 8, TfxExternType::Texture))
+                }
+                TfxBytecodeOp::PushExternInputU32 { extern_, offset } => {
+                    Some((extern_, offset as usize * 4, TfxExternType::U32))
+                }
+                TfxBytecodeOp::PushExternInputUav { extern_, offset } => {
+                    Some((extern_, offset as usize * 8, TfxExternType::Uav))
+                }
+                _ => None,
+            };
+
+            if let Some((extern_, offset, ty)) = extern_read {
+                *self
+                    .extern_usage
+                    .entry((extern_ as u8, offset, ty))
+                    .or_default() += 1;
+            }
+        }
+    }
+
+    /// Formats the report as plain text
+    pub fn to_text(&self) -> String {
+        let mut r = String::new();
+
+        writeln!(
+            r,
+            "{} programs scanned, {} failed to parse\n",
+            self.programs, self.parse_failures
+        )
+        .ok();
+
+        writeln!(r, "Parse failures by opcode:").ok();
+        let mut failures = self.failures_by_opcode.iter().collect::<Vec<_>>();
+        failures.sort_by_key(|(_, g)| std::cmp::Reverse(g.count));
+        for (opcode, group) in failures {
+            let known = if self.opcodes.contains_key(opcode) {
+                "known"
+            } else {
+                "unknown"
+            };
+
+            writeln!(
+                r,
+                "  0x{opcode:02x} ({known}): {} programs, first error: {}",
+                group.count, group.error
+            )
+            .ok();
+            writeln!(r, "    samples: {}", group.samples.join(", ")).ok();
+        }
+
+        writeln!(r, "\nOpcode frequency:").ok();
+        let mut opcodes = self.opcodes.iter().collect::<Vec<_>>();
+        opcodes.sort_by_key(|(_, s)| std::cmp::Reverse(s.count));
+        for (opcode, stats) in opcodes {
+            writeln!(
+                r,
+                "  0x{opcode:02x} {:<32} {:>10} uses in {:>8} programs",
+                stats.mnemonic, stats.count, stats.programs
+            )
+            .ok();
+            if !stats.samples.is_empty() {
+                writeln!(r, "    samples: {}", stats.samples.join(", ")).ok();
+            }
+        }
+
+        writeln!(r, "\nExtern usage:").ok();
+        for ((extern_, offset, ty), count) in &self.extern_usage {
+            let Some(extern_) = TfxExtern::from_u8(*extern_) else {
+                continue;
+            };
+
+            let name = extern_
+                .field_name(*offset, *ty)
+                .unwrap_or_else(|| format!("{extern_:?}+0x{offset:X}"));
+            writeln!(r, "  {name:<48} {:<8} {count:>10}", format!("{ty:?}")).ok();
+        }
+
+        r
+    }
+}
+
+/// Returns the mnemonic of an opcode without its operands
+fn mnemonic(op: &TfxBytecodeOp) -> String {
+    let disassembly = op.disassemble(None);
+    disassembly
+        .split([' ', '('])
+        .next()
+        .unwrap_or(&disassembly)
+        .to_string()
+}
+
+/// Scans the bytecode of every technique and scope in the loaded packages
+pub fn scan_packages() -> anyhow::Result<TfxScanReport> {
+    let mut report = TfxScanReport::default();
+
+    let technique_tags = package_manager().get_all_by_reference(STechnique::ID.unwrap());
+    info!("Scanning {} techniques", technique_tags.len());
+
+    let mut programs: Vec<(String, Vec<u8>)> = technique_tags
+        .into_par_iter()
+        .flat_map_iter(|(tag, _)| {
+            let technique: STechnique = match package_manager().read_tag_struct(tag) {
+                Ok(o) => o,
+                Err(e) => {
+                    error!("Failed to read technique {tag}: {e}");
+                    return vec![];
+                }
+            };
+
+            technique
+                .all_shaders()
+                .into_iter()
+                .map(|(stage, shader)| (format!("{tag} {stage:?}"), shader.bytecode.clone()))
+                .collect()
+        })
+        .collect();
+
+    let scope_tags = package_manager().get_all_by_reference(scope_reference()?);
+    info!("Scanning {} scopes", scope_tags.len());
+
+    programs.par_extend(scope_tags.into_par_iter().flat_map_iter(|(tag, _)| {
+        let scope: SScope = match package_manager().read_tag_struct(tag) {
+            Ok(o) => o,
+            Err(e) => {
+                error!("Failed to read scope {tag}: {e}");
+                return vec![];
+            }
+        };
+
+        let name = scope.name.to_string();
+        [
+            ("Pixel", &scope.stage_pixel),
+            ("Vertex", &scope.stage_vertex),
+            ("Geometry", &scope.stage_geometry),
+            ("Hull", &scope.stage_hull),
+            ("Compute", &scope.stage_compute),
+            ("Domain", &scope.stage_domain),
+        ]
+        .into_iter()
+        .map(|(stage, s)| (format!("scope {name} ({tag}) {stage}"), s.bytecode.clone()))
+        .collect()
+    }));
+
+    for (source, bytecode) in &programs {
+        report.add_program(source, bytecode);
+    }
+
+    Ok(report)
+}
+
+/// [`SScope`] doesn't have a known reference ID, so it's taken from the first scope referenced by the render globals
+fn scope_reference() -> anyhow::Result<u32> {
+    let globals: SRenderGlobals = package_manager()
+        .read_named_tag_struct("render_globals")
+        .context("Failed to read render globals")?;
+    let scope = globals
+        .unk8
+        .iter()
+        .flat_map(|g| &g.unk8.scopes)
+        .next()
+        .context("Render globals don't reference any scopes")?;
+
+    Ok(package_manager()
+        .get_entry(scope.scope.hash())
+        .context("Scope tag has no package entry")?
+        .reference)
+}
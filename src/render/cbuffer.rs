@@ -10,7 +10,7 @@ use std::{
 use anyhow::Context;
 use windows::Win32::Graphics::Direct3D11::*;
 
-use super::bytecode::externs::TfxShaderStage;
+use super::bytecode::{externs::TfxShaderStage, renderer_externs::TfxShaderStageExt};
 use crate::render::DeviceContextSwapchain;
 
 #[derive(Clone)]
@@ -1024,7 +1024,7 @@ impl CustomDebugShape {
         dcs: &DeviceContextSwapchain,
         shape: &destiny_havok::shape_collection::Shape,
     ) -> anyhow::Result<Self> {
-        Self::new(dcs, &shape.vertices, &shape.indices)
+        Self::new(dcs, &shape.vertices, &shape.indices_u16()?)
     }
 }
 
@@ -12,7 +12,7 @@ use windows::Win32::Graphics::{
 use winit::window::Window;
 
 use super::{
-    bytecode::externs::TfxShaderStage,
+    bytecode::{externs::TfxShaderStage, interpreter::TfxEvaluationContext},
     data::RenderDataManager,
     debug::{DebugShapeRenderer, DebugShapes},
     drawcall::{DrawCall, GeometryType, ShadingMode, SortValue3d, Transparency},
@@ -69,6 +69,8 @@ pub struct Renderer {
     pub start_time: Instant,
     pub last_frame: RwLock<Instant>,
     pub delta_time: RwLock<f32>,
+    /// Time and seed used for bytecode evaluation this frame, see [`RenderSettings::freeze_bytecode_time`]
+    pub tfx_context: RwLock<TfxEvaluationContext>,
 
     pub render_data: RenderDataManager,
 
@@ -383,6 +385,7 @@ impl Renderer {
             start_time: Instant::now(),
             last_frame: RwLock::new(Instant::now()),
             delta_time: RwLock::new(0.016),
+            tfx_context: RwLock::new(TfxEvaluationContext::default()),
             blend_state_none,
             blend_state_blend,
             blend_state_additive,
@@ -1687,11 +1690,31 @@ impl Renderer {
     fn update_buffers(&self, resources: &Resources) -> anyhow::Result<()> {
         let camera = resources.get::<FpsCamera>().unwrap();
         let overrides = resources.get::<ScopeOverrides>().unwrap();
+        let render_settings = resources.get::<RenderSettings>().unwrap();
+
+        let (time, delta_time) = if render_settings.freeze_bytecode_time {
+            (render_settings.frozen_bytecode_time, 0.0)
+        } else {
+            (
+                self.start_time.elapsed().as_secs_f32(),
+                *self.delta_time.read(),
+            )
+        };
+        let tfx_context = TfxEvaluationContext {
+            time,
+            delta_time,
+            seed: if render_settings.fixed_bytecode_seed {
+                render_settings.bytecode_seed
+            } else {
+                0
+            },
+        };
+        *self.tfx_context.write() = tfx_context;
 
         self.scope_frame.write(&ScopeFrame {
-            game_time: self.start_time.elapsed().as_secs_f32(),
-            render_time: self.start_time.elapsed().as_secs_f32(),
-            delta_game_time: *self.delta_time.read(),
+            game_time: tfx_context.time,
+            render_time: tfx_context.time,
+            delta_game_time: tfx_context.delta_time,
             // exposure_time: 0.0,
 
             // exposure_scale: 1.0,
@@ -1,4 +1,7 @@
-use std::ops::Deref;
+use std::{
+    ops::Deref,
+    sync::atomic::{AtomicBool, Ordering},
+};
 
 use alkahest_data::technique::{STechnique, STechniqueShader};
 use destiny_pkg::TagHash;
@@ -8,7 +11,10 @@ use crate::{
     packages::package_manager,
     render::{
         bytecode::{
-            externs::TfxShaderStage, interpreter::TfxBytecodeInterpreter, opcodes::TfxBytecodeOp,
+            externs::TfxShaderStage,
+            interpreter::{TfxBytecodeInterpreter, TfxTrace},
+            opcodes::TfxBytecodeOp,
+            renderer_externs::{RendererExterns, TfxShaderStageExt},
         },
         cbuffer::ConstantBufferCached,
         drawcall::ShaderStages,
@@ -102,6 +108,24 @@ pub struct TechniqueStage {
 
     cbuffer: Option<ConstantBufferCached<Vec4>>,
     bytecode: RwLock<Option<TfxBytecodeInterpreter>>,
+    /// Set once the bytecode has produced binds that [`RendererExterns::apply_binds`] can't apply yet
+    skipped_binds_shown: AtomicBool,
+}
+
+/// Returns the number of vec4 elements in the cbuffer that `shader` writes its bytecode output to, if any
+pub fn shader_cbuffer_elements(shader: &STechniqueShader) -> Option<usize> {
+    if shader.constant_buffer.is_some() {
+        let buffer_header_ref = package_manager()
+            .get_entry(shader.constant_buffer)?
+            .reference;
+
+        let data_raw = package_manager().read_tag(buffer_header_ref).ok()?;
+        Some(data_raw.len() / std::mem::size_of::<Vec4>())
+    } else if !shader.unk50.is_empty() {
+        Some(shader.unk50.len())
+    } else {
+        None
+    }
 }
 
 impl TechniqueStage {
@@ -178,9 +202,16 @@ impl TechniqueStage {
             stage,
             cbuffer,
             bytecode: RwLock::new(bytecode),
+            skipped_binds_shown: AtomicBool::new(false),
         }
     }
 
+    /// Replaces the bytecode program of this stage (eg. with hand-assembled bytecode)
+    pub fn set_bytecode(&self, opcodes: Vec<TfxBytecodeOp>) {
+        *self.bytecode.write() = Some(TfxBytecodeInterpreter::new(opcodes));
+        self.skipped_binds_shown.store(false, Ordering::Relaxed);
+    }
+
     pub fn bind(&self, dcs: &DeviceContextSwapchain, render_data: &RenderData) {
         unsafe {
             for (si, s) in self.shader.samplers.iter().enumerate() {
@@ -226,6 +257,32 @@ impl TechniqueStage {
         }
     }
 
+    /// Records a trace of the bytecode against a copy of the current cbuffer contents, leaving the live cbuffer untouched.
+    /// Binds are recorded but not applied. [`RendererExterns`] handles don't hold a reference, so nothing needs releasing.
+    /// Returns `None` if the stage has no bytecode or cbuffer
+    pub fn trace_bytecode(
+        &self,
+        renderer: &Renderer,
+        render_data: &RenderData,
+    ) -> Option<TfxTrace> {
+        let cbuffer = self.cbuffer.as_ref()?;
+        let bytecode = self.bytecode.read();
+        let interpreter = bytecode.as_ref()?;
+
+        let externs = RendererExterns::new(renderer, render_data);
+        let mut buffer = cbuffer.data_array().to_vec();
+        Some(interpreter.evaluate_traced(
+            &renderer.tfx_context.read(),
+            &externs,
+            &mut buffer,
+            if self.shader.bytecode_constants.is_empty() {
+                &[]
+            } else {
+                bytemuck::cast_slice(&self.shader.bytecode_constants)
+            },
+        ))
+    }
+
     pub fn evaluate_bytecode(
         &self,
         renderer: &Renderer,
@@ -235,20 +292,32 @@ impl TechniqueStage {
         if let Some(ref cbuffer) = self.cbuffer {
             let _span = info_span!("Evaluating TFX bytecode (VS)").entered();
             let res = if let Some(interpreter) = self.bytecode.read().as_ref() {
-                interpreter.evaluate(
-                    renderer,
-                    render_data,
-                    cbuffer,
-                    if self.shader.bytecode_constants.is_empty() {
-                        &[]
-                    } else {
-                        bytemuck::cast_slice(&self.shader.bytecode_constants)
-                    },
-                )
+                let externs = RendererExterns::new(renderer, render_data);
+                interpreter
+                    .evaluate(
+                        &renderer.tfx_context.read(),
+                        &externs,
+                        cbuffer.data_array(),
+                        if self.shader.bytecode_constants.is_empty() {
+                            &[]
+                        } else {
+                            bytemuck::cast_slice(&self.shader.bytecode_constants)
+                        },
+                    )
+                    .map(|output| externs.apply_binds(&output.binds))
             } else {
-                Ok(())
+                Ok(0)
             };
 
+            if let Ok(skipped) = res {
+                if skipped > 0 && !self.skipped_binds_shown.swap(true, Ordering::Relaxed) {
+                    warn!(
+                        "TFX bytecode for {} ({:?}) binds {skipped} sampler(s)/UAV(s), which aren't supported yet",
+                        parent, self.stage
+                    );
+                }
+            }
+
             if !self
                 .bytecode
                 .read()
@@ -267,7 +336,7 @@ impl TechniqueStage {
                         } else {
                             bytemuck::cast_slice(&self.shader.bytecode_constants)
                         },
-                        cbuffer,
+                        cbuffer.data_array(),
                     );
                     self.bytecode.write().as_mut().unwrap().error_shown = true;
                 }